@@ -0,0 +1,184 @@
+//! Turns every example fixture into a test, see `src/examples.rs` for the layout,
+//! lists the days with a source module for `src/registry.rs` and generates
+//! the C header of `src/ffi.rs`.
+
+use std::fmt::Write;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=examples");
+    println!("cargo:rerun-if-changed=src");
+
+    let mut tests = String::new();
+    for (day, name, input, answers) in find_examples() {
+        let _ = writeln!(
+            tests,
+            "#[test]\n#[cfg(feature = \"day{day:0>2}\")]\nfn day{day:0>2}_example_{name}() {{\n    aoc2022::examples::check_example(\n        aoc2022::day{day:0>2}::answer,\n        include_str!({input:?}),\n        include_str!({answers:?}),\n    );\n}}\n"
+        );
+    }
+
+    let out_dir = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let out_file = Path::new(&out_dir).join("example_tests.rs");
+    std::fs::write(out_file, tests).expect("couldn't write the generated example tests");
+
+    let ffi_source = std::fs::read_to_string("src/ffi.rs").expect("couldn't read src/ffi.rs");
+    let out_file = Path::new(&out_dir).join("aoc2022.h");
+    std::fs::write(out_file, c_header(&ffi_source)).expect("couldn't write the C header");
+
+    let days: Vec<String> = find_days().iter().map(u32::to_string).collect();
+    let out_file = Path::new(&out_dir).join("source_days.rs");
+    std::fs::write(out_file, format!("&[{}]", days.join(", ")))
+        .expect("couldn't write the list of days");
+}
+
+/// Days with a `src/dayNN/mod.rs`.
+fn find_days() -> Vec<u32> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    (1..=25)
+        .filter(|day| root.join(format!("src/day{day:0>2}/mod.rs")).is_file())
+        .collect()
+}
+
+/// (day, fixture name, input path, answers path) of every fixture that has
+/// both files and belongs to a day with a source module.
+fn find_examples() -> Vec<(u32, String, String, String)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut found = Vec::new();
+    let Ok(day_dirs) = std::fs::read_dir(root.join("examples")) else {
+        return found;
+    };
+
+    for day_dir in day_dirs.flatten() {
+        let dir_name = day_dir.file_name().to_string_lossy().into_owned();
+        let Some(day) = dir_name
+            .strip_prefix("day")
+            .and_then(|x| x.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if !root.join(format!("src/day{day:0>2}/mod.rs")).is_file() {
+            continue;
+        }
+        let Ok(files) = std::fs::read_dir(day_dir.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().is_some_and(|x| x == "txt") {
+                let answers = path.with_extension("answers");
+                let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                // test names have to be identifiers
+                let name: String = name
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+                if answers.is_file() {
+                    found.push((
+                        day,
+                        name,
+                        path.display().to_string(),
+                        answers.display().to_string(),
+                    ));
+                }
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Turns the `pub const`s and `extern "C"` functions of `src/ffi.rs` into a
+/// C header, their doc comments included.
+fn c_header(ffi_source: &str) -> String {
+    let mut header = String::from(
+        "/* Generated from src/ffi.rs by build.rs, don't edit. */\n\
+         #ifndef AOC2022_H\n#define AOC2022_H\n\n\
+         #include <stddef.h>\n#include <stdint.h>\n\n",
+    );
+    let mut docs = Vec::new();
+    let mut lines = ffi_source.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        if line.starts_with("#[cfg(test)]") {
+            break;
+        } else if let Some(doc) = line.strip_prefix("///") {
+            docs.push(doc.trim().to_string());
+        } else if let Some(constant) = line.strip_prefix("pub const ") {
+            let (name, value) = constant.split_once(':').expect("typed constant");
+            let value = value.split_once('=').expect("constant value").1;
+            let _ = writeln!(header, "/* {} */", docs.join(" "));
+            let _ = writeln!(
+                header,
+                "#define {name} {}\n",
+                value.trim_end_matches(';').trim()
+            );
+            docs.clear();
+        } else if line.starts_with("pub unsafe extern \"C\" fn") {
+            // rustfmt may put the arguments on separate lines
+            let mut signature = line.to_string();
+            while !signature.ends_with('{') {
+                signature.push_str(lines.next().expect("function body"));
+            }
+            let _ = writeln!(header, "{}", c_function(&signature, &docs));
+            docs.clear();
+        } else if !line.starts_with("#[") {
+            docs.clear();
+        }
+    }
+    header.push_str("#endif /* AOC2022_H */\n");
+    header
+}
+
+fn c_function(signature: &str, docs: &[String]) -> String {
+    let signature = signature.trim_start_matches("pub unsafe extern \"C\" fn ");
+    let (name, rest) = signature.split_once('(').expect("argument list");
+    let (arguments, rest) = rest.rsplit_once(')').expect("argument list");
+    let return_type = match rest.trim_end_matches('{').trim().strip_prefix("->") {
+        Some(rust_type) => c_type(rust_type.trim()),
+        None => "void ".to_string(),
+    };
+    let arguments: Vec<String> = arguments
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|argument| {
+            let (name, rust_type) = argument.split_once(':').expect("typed argument");
+            format!("{}{name}", c_type(rust_type.trim()))
+        })
+        .collect();
+
+    // the `# Safety` sections are about the rust side, C callers get the rest
+    let docs: Vec<&str> = docs
+        .iter()
+        .map(String::as_str)
+        .take_while(|x| *x != "# Safety")
+        .collect();
+    let comment: Vec<String> = docs
+        .join("\n")
+        .trim()
+        .lines()
+        .map(|x| format!(" * {x}").trim_end().to_string())
+        .collect();
+    format!(
+        "/*\n{}\n */\n{return_type}{name}({});\n",
+        comment.join("\n"),
+        arguments.join(", ")
+    )
+}
+
+/// C spelling of a rust type, pointers keep the trailing space of the type.
+fn c_type(rust_type: &str) -> String {
+    if let Some(pointee) = rust_type.strip_prefix("*const ") {
+        format!("const {}*", c_type(pointee))
+    } else if let Some(pointee) = rust_type.strip_prefix("*mut ") {
+        format!("{}*", c_type(pointee))
+    } else {
+        let c_name = match rust_type {
+            "i32" => "int32_t",
+            "u8" => "uint8_t",
+            "usize" => "size_t",
+            "c_char" => "char",
+            other => panic!("no C type for '{other}'"),
+        };
+        format!("{c_name} ")
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Exercises the C interface of the library.
+ *
+ *   cargo build
+ *   cc ffi/test_aoc2022.c -Iinclude -Ltarget/debug -laoc2022 -o target/test_aoc2022
+ *   LD_LIBRARY_PATH=target/debug target/test_aoc2022
+ *
+ * Without arguments it runs a few checks and exits with 1 if any of them
+ * fails, `test_aoc2022 DAY PART FILE` solves the given input instead.
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "aoc2022.h"
+
+static int failures = 0;
+
+static void check(int condition, const char *what) {
+    printf("%s %s\n", condition ? "ok  " : "FAIL", what);
+    if (!condition) {
+        failures++;
+    }
+}
+
+static int solve_file(int32_t day, int32_t part, const char *path) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        fprintf(stderr, "can't open '%s'\n", path);
+        return 1;
+    }
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    uint8_t *input = malloc(size > 0 ? (size_t)size : 1);
+    size_t read = fread(input, 1, (size_t)size, file);
+    fclose(file);
+
+    char *result = NULL;
+    int32_t code = aoc_solve(day, part, input, read, &result);
+    free(input);
+    printf("code: %d\n", code);
+    if (result != NULL) {
+        printf("result: %s\n", result);
+        aoc_free_string(result);
+    }
+    return code == AOC_OK ? 0 : 1;
+}
+
+int main(int argc, char **argv) {
+    if (argc == 4) {
+        return solve_file(atoi(argv[1]), atoi(argv[2]), argv[3]);
+    }
+
+    size_t count = aoc_days(NULL, 0);
+    int32_t *days = calloc(count > 0 ? count : 1, sizeof(int32_t));
+    check(aoc_days(days, count) == count, "aoc_days reports the same count twice");
+    printf("compiled days:");
+    for (size_t i = 0; i < count; i++) {
+        printf(" %d", days[i]);
+    }
+    printf("\n");
+    free(days);
+
+    const char *calories = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+    char *result = NULL;
+    int32_t code = aoc_solve(1, 2, (const uint8_t *)calories, strlen(calories), &result);
+    check(code == AOC_OK, "day 1 part 2 succeeds");
+    check(result != NULL && strcmp(result, "45000") == 0, "day 1 part 2 answer is 45000");
+    aoc_free_string(result);
+
+    result = NULL;
+    code = aoc_solve(7, 1, (const uint8_t *)"$ cd", 4, &result);
+    check(code == AOC_SOLVE_FAILED, "a malformed transcript is rejected");
+    check(result != NULL, "the rejection comes with a message");
+    aoc_free_string(result);
+
+    check(aoc_solve(26, 1, NULL, 0, &result) == AOC_UNKNOWN_DAY, "day 26 is unknown");
+    check(result == NULL, "no result for an unknown day");
+    check(aoc_solve(1, 3, NULL, 0, &result) == AOC_INVALID_PART, "part 3 is invalid");
+    check(aoc_solve(1, 1, (const uint8_t *)"\xff", 1, &result) == AOC_INVALID_UTF8,
+          "invalid UTF-8 is rejected");
+    check(aoc_solve(1, 1, NULL, 0, NULL) == AOC_NULL_POINTER, "a null result is rejected");
+    aoc_free_string(NULL);
+
+    printf("%d failure(s)\n", failures);
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,51 @@
+/* Generated from src/ffi.rs by build.rs, don't edit. */
+#ifndef AOC2022_H
+#define AOC2022_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* The call succeeded. */
+#define AOC_OK 0
+
+/* A required pointer argument was null. */
+#define AOC_NULL_POINTER 1
+
+/* The input is not valid UTF-8. */
+#define AOC_INVALID_UTF8 2
+
+/* The day doesn't exist or was compiled out. */
+#define AOC_UNKNOWN_DAY 3
+
+/* The part is neither 1 nor 2. */
+#define AOC_INVALID_PART 4
+
+/* The solver rejected the input, the result holds the error message. */
+#define AOC_SOLVE_FAILED 5
+
+/* The solver panicked. */
+#define AOC_PANIC 6
+
+/*
+ * Writes up to `capacity` compiled day numbers into `days` and returns the
+ * number of compiled days, call it with a null `days` to get the count only.
+ */
+size_t aoc_days(int32_t *days, size_t capacity);
+
+/*
+ * Solves `part` (1 or 2) of `day` for the `input_len` bytes at `input`.
+ * CRLF line ends, a UTF-8 BOM and trailing blanks are dropped from the input
+ * first, like the runner does.
+ *
+ * On `AOC_OK` the answer and on `AOC_SOLVE_FAILED` the error message is
+ * stored in `*result`, the string has to be released with `aoc_free_string`.
+ * For any other code `*result` is set to null.
+ */
+int32_t aoc_solve(int32_t day, int32_t part, const uint8_t *input, size_t input_len, char **result);
+
+/*
+ * Releases a string returned by `aoc_solve`, null is ignored.
+ */
+void aoc_free_string(char *text);
+
+#endif /* AOC2022_H */
@@ -0,0 +1,27 @@
+//! A plugin with a second solver of day 1, the `sample-plugin` example of the
+//! crate. `cargo build --example sample-plugin` writes it to
+//! `target/debug/examples`, the runner loads it from the directory given with
+//! `--plugins`.
+
+aoc2022::export_plugin! {
+    (1, "sorted", sorted),
+}
+
+/// Sorts the calories carried by the elves and sums the largest one or three.
+fn sorted(input: String, is_part_one: bool) -> Result<String, String> {
+    let mut calories = Vec::new();
+    for elf in aoc2022::parsing::paragraphs(&input) {
+        let total = elf
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .parse::<u64>()
+                    .map_err(|_| format!("'{line}' isn't a number of calories"))
+            })
+            .sum::<Result<u64, String>>()?;
+        calories.push(total);
+    }
+    calories.sort_unstable();
+    let count = if is_part_one { 1 } else { 3 };
+    Ok(calories.iter().rev().take(count).sum::<u64>().to_string())
+}
@@ -0,0 +1,130 @@
+//! Counting allocator behind the `count-alloc` feature.
+//!
+//! The runner installs [`CountingAlloc`] as its global allocator when the
+//! feature is on, `bench` then reports the memory used by every part next to
+//! its time. Without the feature nothing is counted and [`ENABLED`] is false.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Whether the runner counts its allocations.
+pub const ENABLED: bool = cfg!(feature = "count-alloc");
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Forwards to the system allocator and keeps track of the live bytes.
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: same contract as the caller's
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            added(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: same contract as the caller's
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            added(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: same contract as the caller's
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: same contract as the caller's
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            // a grown block counts as a new allocation of the whole new size
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            added(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn added(size: usize) {
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+    TOTAL.fetch_add(size, Ordering::Relaxed);
+    COUNT.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Allocations since a [`Counter`] was started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Highest number of live bytes above the ones live at the start.
+    pub peak_bytes: usize,
+    /// Bytes of all allocations, freed or not.
+    pub total_bytes: usize,
+    pub allocations: usize,
+}
+
+/// Measures the allocations between [`Counter::start`] and [`Counter::stop`].
+///
+/// The counters are global, measurements on several threads at once mix up.
+pub struct Counter {
+    base: usize,
+    total: usize,
+    count: usize,
+}
+
+impl Counter {
+    pub fn start() -> Self {
+        let base = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(base, Ordering::Relaxed);
+        Counter {
+            base,
+            total: TOTAL.load(Ordering::Relaxed),
+            count: COUNT.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn stop(self) -> Usage {
+        Usage {
+            peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(self.base),
+            total_bytes: TOTAL.load(Ordering::Relaxed) - self.total,
+            allocations: COUNT.load(Ordering::Relaxed) - self.count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        // the allocator isn't installed in the tests, it's only called here
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let counter = Counter::start();
+        unsafe {
+            let a = CountingAlloc.alloc(layout);
+            let b = CountingAlloc.alloc_zeroed(layout);
+            CountingAlloc.dealloc(a, layout);
+            let b = CountingAlloc.realloc(b, layout, 300);
+            CountingAlloc.dealloc(b, Layout::from_size_align(300, 8).unwrap());
+        }
+        let usage = counter.stop();
+        assert_eq!(
+            usage,
+            Usage {
+                peak_bytes: 300,
+                total_bytes: 500,
+                allocations: 3,
+            }
+        );
+    }
+}
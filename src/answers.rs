@@ -0,0 +1,25 @@
+//! Answers accepted by the site, the store the runner checks results against.
+//!
+//! They are kept next to the inputs as `inputs/dayNN.answers`, in the format
+//! of the example answers: one `part1: <answer>` or `part2: <answer>` line
+//! per solved part.
+
+use crate::examples::{parse_expected, Expected};
+use std::path::{Path, PathBuf};
+
+pub fn answers_path(input_dir: &Path, day: i32) -> PathBuf {
+    input_dir.join(format!("day{day:0>2}.answers"))
+}
+
+/// The recorded answers of the day, `None` if nothing was recorded yet.
+pub fn read(input_dir: &Path, day: i32) -> Result<Option<Expected>, String> {
+    let path = answers_path(input_dir, day);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("couldn't read '{}': {e}", path.display()))?;
+    parse_expected(&text)
+        .map(Some)
+        .map_err(|e| format!("'{}' {e}", path.display()))
+}
@@ -0,0 +1,290 @@
+//! Timing of the solvers, behind the `bench` command.
+//!
+//! Every part runs a few times on its own copy of the input and the fastest
+//! run is kept. With the `count-alloc` feature the memory of the first run is
+//! reported too, see [`crate::alloc`].
+
+use crate::alloc::{self, Counter, Usage};
+use crate::json::{self, Value};
+use crate::registry::{ParseAny, Solver};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    pub day: i32,
+    pub part: u8,
+    /// Fastest of the runs.
+    pub time: Duration,
+    /// `None` when the allocations aren't counted.
+    pub memory: Option<Usage>,
+    pub answer: Result<String, String>,
+}
+
+/// Runs one part `runs` times, at least once.
+pub fn measure(solver: &dyn Solver, input: &str, is_part_one: bool, runs: usize) -> Measurement {
+    let mut time = Duration::MAX;
+    let mut memory = None;
+    let mut answer = Err(String::new());
+    for run in 0..runs.max(1) {
+        // copying the input is neither timed nor counted
+        let text = input.to_string();
+        let counter = Counter::start();
+        let start = Instant::now();
+        answer = solver.solve(text, is_part_one);
+        time = time.min(start.elapsed());
+        let usage = counter.stop();
+        if run == 0 && alloc::ENABLED {
+            memory = Some(usage);
+        }
+    }
+    Measurement {
+        day: solver.day(),
+        part: if is_part_one { 1 } else { 2 },
+        time,
+        memory,
+        answer,
+    }
+}
+
+/// Answer and time of one part solved on an already parsed input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartRun {
+    pub answer: Result<String, String>,
+    pub time: Duration,
+}
+
+/// Both parts solved on a single parse, every phase timed on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phases {
+    pub parse_time: Duration,
+    /// The error of the parse if it failed, the parts aren't run then.
+    pub parts: Result<[PartRun; 2], String>,
+}
+
+pub fn run_phases(parse: ParseAny, input: &str) -> Phases {
+    let start = Instant::now();
+    let parsed = parse(input);
+    let parse_time = start.elapsed();
+    let parts = parsed.map(|parsed| {
+        [true, false].map(|is_part_one| {
+            let start = Instant::now();
+            let answer = parsed.part(is_part_one);
+            PartRun {
+                answer,
+                time: start.elapsed(),
+            }
+        })
+    });
+    Phases { parse_time, parts }
+}
+
+/// One line per part, the memory columns only when they were measured.
+pub fn table(measurements: &[Measurement]) -> String {
+    let with_memory = measurements.iter().any(|x| x.memory.is_some());
+    let mut text = String::from("day  part          time");
+    if with_memory {
+        text.push_str("         peak    allocated  allocations");
+    }
+    text.push_str("  answer\n");
+    for x in measurements {
+        let _ = write!(
+            text,
+            "{:>3}  {:>4}  {:>12}",
+            x.day,
+            x.part,
+            format!("{:?}", x.time)
+        );
+        if with_memory {
+            let usage = x.memory.unwrap_or_default();
+            let _ = write!(
+                text,
+                "  {:>11}  {:>11}  {:>11}",
+                bytes(usage.peak_bytes),
+                bytes(usage.total_bytes),
+                usage.allocations
+            );
+        }
+        match &x.answer {
+            Ok(answer) => writeln!(text, "  {answer}"),
+            Err(err) => writeln!(text, "  WARNING {err}"),
+        }
+        .unwrap();
+    }
+    text
+}
+
+/// Name of the file in the inputs directory that keeps the last run on the
+/// inputs, `status` shows the times from it.
+pub const LATEST_FILE: &str = "bench.json";
+
+/// The measurements as a JSON array, times in nanoseconds and sizes in bytes.
+pub fn json(measurements: &[Measurement]) -> String {
+    let items: Vec<String> = measurements
+        .iter()
+        .map(|x| {
+            let mut item = format!(
+                "{{\"day\": {}, \"part\": {}, \"time_ns\": {}",
+                x.day,
+                x.part,
+                x.time.as_nanos()
+            );
+            if let Some(usage) = x.memory {
+                let _ = write!(
+                    item,
+                    ", \"peak_bytes\": {}, \"total_bytes\": {}, \"allocations\": {}",
+                    usage.peak_bytes, usage.total_bytes, usage.allocations
+                );
+            }
+            match &x.answer {
+                Ok(answer) => write!(item, ", \"answer\": {}}}", json::quote(answer)),
+                Err(err) => write!(item, ", \"error\": {}}}", json::quote(err)),
+            }
+            .unwrap();
+            item
+        })
+        .collect();
+    if items.is_empty() {
+        "[]\n".into()
+    } else {
+        format!("[\n  {}\n]\n", items.join(",\n  "))
+    }
+}
+
+/// Reads the measurements back from [`json`].
+pub fn parse_json(text: &str) -> Result<Vec<Measurement>, String> {
+    let document = crate::json::parse(text).map_err(|e| e.to_string())?;
+    let items = document
+        .as_array()
+        .ok_or("expected an array of measurements")?;
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let number = |key: &str| item.get(key).and_then(Value::as_i64);
+            let missing = |key: &str| format!("measurement {}: no '{key}'", i + 1);
+            let text = |key: &str| item.get(key).and_then(Value::as_str).map(String::from);
+            let memory = match (
+                number("peak_bytes"),
+                number("total_bytes"),
+                number("allocations"),
+            ) {
+                (Some(peak), Some(total), Some(count)) => Some(Usage {
+                    peak_bytes: peak as usize,
+                    total_bytes: total as usize,
+                    allocations: count as usize,
+                }),
+                _ => None,
+            };
+            Ok(Measurement {
+                day: number("day").ok_or_else(|| missing("day"))? as i32,
+                part: number("part").ok_or_else(|| missing("part"))? as u8,
+                time: Duration::from_nanos(
+                    number("time_ns").ok_or_else(|| missing("time_ns"))? as u64
+                ),
+                memory,
+                answer: match (text("answer"), text("error")) {
+                    (Some(answer), _) => Ok(answer),
+                    (None, Some(error)) => Err(error),
+                    (None, None) => return Err(missing("answer")),
+                },
+            })
+        })
+        .collect()
+}
+
+fn bytes(count: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = count as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{count} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurements() -> Vec<Measurement> {
+        vec![
+            Measurement {
+                day: 1,
+                part: 1,
+                time: Duration::from_micros(15),
+                memory: Some(Usage {
+                    peak_bytes: 2048,
+                    total_bytes: 3 << 20,
+                    allocations: 7,
+                }),
+                answer: Ok("24000".into()),
+            },
+            Measurement {
+                day: 7,
+                part: 2,
+                time: Duration::from_nanos(900),
+                memory: None,
+                answer: Err("line 1: \"$ cd\"\nmissing name".into()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(json(&[]), "[]\n");
+        assert_eq!(
+            json(&measurements()),
+            "[\n  {\"day\": 1, \"part\": 1, \"time_ns\": 15000, \"peak_bytes\": 2048, \
+             \"total_bytes\": 3145728, \"allocations\": 7, \"answer\": \"24000\"},\n  \
+             {\"day\": 7, \"part\": 2, \"time_ns\": 900, \
+             \"error\": \"line 1: \\\"$ cd\\\"\\nmissing name\"}\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        assert_eq!(parse_json(&json(&measurements())), Ok(measurements()));
+        assert!(parse_json("[{\"day\": 1}]").is_err());
+    }
+
+    #[test]
+    fn test_table() {
+        let text = table(&measurements());
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].contains("peak"));
+        assert!(lines[1]
+            .starts_with("  1     1          15µs      2.0 KiB      3.0 MiB            7  24000"));
+        assert!(lines[2].contains("900ns"));
+
+        let mut measurements = measurements();
+        measurements[0].memory = None;
+        assert!(!table(&measurements).contains("peak"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "day01", feature = "day07"))]
+    fn test_run_phases() {
+        let parse = crate::registry::find(1).unwrap().parse.unwrap();
+        let phases = run_phases(parse, "1\n2\n\n3\n\n4\n");
+        let answers: Vec<_> = phases.parts.unwrap().map(|x| x.answer).into();
+        assert_eq!(answers, [Ok("4".to_string()), Ok("10".to_string())]);
+        let parse = crate::registry::find(7).unwrap().parse.unwrap();
+        assert!(run_phases(parse, "$ cd").parts.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_measure() {
+        let day = crate::registry::find(1).unwrap();
+        let measurement = measure(day, "1\n2\n\n3\n", true, 3);
+        assert_eq!(measurement.answer, Ok("3".into()));
+        assert_eq!(measurement.part, 1);
+        assert_eq!(measurement.memory.is_some(), alloc::ENABLED);
+    }
+}
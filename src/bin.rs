@@ -1,240 +1,1357 @@
 use aoc2022::*;
 use argparse::ArgumentParser;
-use regex::Regex;
-use std::{io::Write, path::Path};
+use registry::Solver;
+use std::{fs::File, io::BufReader, path::Path};
 
 const INPUT_DIR: &str = "inputs";
 
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAlloc = alloc::CountingAlloc;
+
 fn main() {
-    let (day, do_part_two, init_day) = parse_arguments();
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("generate") => {
+            generate_input(args[1..].to_vec());
+            return;
+        }
+        Some("list") => {
+            list_days(args[1..].to_vec());
+            return;
+        }
+        Some("repl") => {
+            start_repl(args[1..].to_vec());
+            return;
+        }
+        Some("examples") => {
+            match args.get(2).map(String::as_str) {
+                Some("extract") => extract_example(args[2..].to_vec()),
+                _ => println!("usage: examples extract <day> <puzzle page>"),
+            }
+            return;
+        }
+        Some("read") => {
+            read_puzzle(args[1..].to_vec());
+            return;
+        }
+        Some("leaderboard") => {
+            show_leaderboard(args[1..].to_vec());
+            return;
+        }
+        Some("status") => {
+            show_status();
+            return;
+        }
+        Some("bench") => {
+            run_bench(args[1..].to_vec());
+            return;
+        }
+        Some("verify") => {
+            verify_inputs(args[1..].to_vec());
+            return;
+        }
+        Some("compare") => {
+            compare_solvers(args[1..].to_vec());
+            return;
+        }
+        Some("serve") => {
+            serve(args[1..].to_vec());
+            return;
+        }
+        Some("deinit") => {
+            deinit_day(args[1..].to_vec());
+            return;
+        }
+        Some("mv") => {
+            move_day(args[1..].to_vec());
+            return;
+        }
+        Some("dashboard") => {
+            let runner = std::env::current_exe().unwrap_or_else(|_| args[0].clone().into());
+            if let Err(err) = dashboard::run(&runner, Path::new(INPUT_DIR)) {
+                println!("WARNING {err}");
+            }
+            return;
+        }
+        _ => {}
+    }
 
-    if init_day {
-        init_new_day(day);
+    let arguments = parse_arguments();
+    if arguments.init_day {
+        init_new_day(arguments.day);
         return;
     }
+    let json_path = arguments.json_path.clone();
+    let run = run_day(arguments);
+    if !json_path.is_empty() {
+        if let Err(e) = std::fs::write(&json_path, dashboard::run_json(&run)) {
+            println!("WARNING couldn't write '{json_path}'");
+            println!("{e}");
+        }
+    }
+}
 
-    let fun = match day {
-        1 => |x, y| day01::solve(x, y),
-        2 => |x, y| day02::solve(x, y),
-        3 => |x, y| day03::solve(x, y),
-        4 => |x, y| day04::solve(x, y),
-        5 => |x, y| day05::solve(x, y),
-        6 => |x, y| day06::solve(x, y),
-        7 => |x, y| day07::solve(x, y),
-        8 => |x, y| day08::solve(x, y),
-        9 => |x, y| day09::solve(x, y),
-        _ => {
-            println!("the day has not been created yet, sorry …");
-            return;
+/// Runs the parts of the day asked for on the command line, what is printed
+/// is also returned for `--json`.
+fn run_day(arguments: Arguments) -> dashboard::Run {
+    let Arguments {
+        day,
+        do_part_one,
+        do_part_two,
+        example,
+        input_name,
+        visualize,
+        fps,
+        paused,
+        variant,
+        plugin_dir,
+        stream,
+        ..
+    } = arguments;
+    let parts = selected_parts(do_part_one, do_part_two);
+    if !variant.is_empty() {
+        if visualize {
+            let message = "the solvers of plugins can't be visualised, sorry …";
+            println!("{message}");
+            return dashboard::Run::failed(parts, message);
+        }
+        return run_variant(day, &variant, &plugin_dir, parts, &example, input_name);
+    }
+
+    let Some(entry) = registry::find(day) else {
+        let message = if registry::is_compiled_out(day) {
+            format!(
+                "day {day} was compiled out, build with the '{}' feature",
+                registry::day_name(day)
+            )
+        } else {
+            "the day has not been created yet, sorry …".to_string()
+        };
+        println!("{message}");
+        return dashboard::Run::failed(parts, &message);
+    };
+
+    let input_dir = Path::new(INPUT_DIR);
+    if example.is_empty()
+        && input_name.is_empty()
+        && !visualize
+        && inputs::has_named_inputs(input_dir, day)
+    {
+        println!("running day {day} problem on every input");
+        let rows: Vec<inputs::Row> = inputs::named_inputs(input_dir, day)
+            .iter()
+            .map(|input| inputs::solve(entry, input, parts))
+            .collect();
+        print!("{}", inputs::matrix(&rows));
+        return dashboard::Run::from_rows(&rows);
+    }
+
+    let (file_path, expected) = input_file(day, &example, input_name);
+    let mut run = dashboard::Run::default();
+    let mut report = |is_part_one: bool, answer: Result<String, String>, time| {
+        let part_nr = if is_part_one { 1 } else { 2 };
+        match (&answer, time) {
+            (Ok(answer), Some(time)) => println!("part {part_nr} output: {answer} ({time:?})"),
+            (Ok(answer), None) => println!("output: {answer}"),
+            (Err(err), Some(_)) => println!("WARNING part {part_nr}: {err}"),
+            (Err(err), None) => println!("WARNING {err}"),
         }
+        print_expected(expected.as_ref(), is_part_one, !example.is_empty());
+        run.parts[part_nr - 1] = Some(dashboard::PartResult {
+            answer,
+            time,
+            expected: expected
+                .as_ref()
+                .and_then(|x| x.part(is_part_one))
+                .map(String::from),
+        });
+    };
+    let missing_input = || {
+        let message = format!("can't find input file at: '{file_path}'");
+        println!("{message}");
+        dashboard::Run::failed(parts, &message)
     };
 
-    let file_path = deduce_input_file_path(day);
-    if let Result::Ok(text) = std::fs::read_to_string(&file_path) {
-        let part_nr = if do_part_two { 2 } else { 1 };
+    let both_parts = do_part_one == do_part_two && !visualize;
+    let stream_parts = stream && entry.answer_stream.is_some();
+    // both parts share one parse unless a single part was asked for
+    if let (true, false, Some(parse)) = (both_parts, stream_parts, entry.parse) {
+        let Result::Ok(text) = std::fs::read_to_string(&file_path) else {
+            return missing_input();
+        };
+        println!("running both parts of day {day} problem");
+        println!("input from: '{file_path}'");
+        let (text, changes) = normalize::normalize(&text);
+        if !changes.is_empty() {
+            println!("input normalised: {changes}");
+        }
+        let phases = bench::run_phases(parse, &text);
+        println!("parse: {:?}", phases.parse_time);
+        match phases.parts {
+            Ok(results) => {
+                for (part, is_part_one) in results.into_iter().zip([true, false]) {
+                    report(is_part_one, part.answer, Some(part.time));
+                }
+            }
+            Err(err) => {
+                println!("WARNING {err}");
+                return dashboard::Run::failed(parts, &err);
+            }
+        }
+        run.parse_time = Some(phases.parse_time);
+        return run;
+    }
+
+    // with --stream, streamed days read the file once per part rather than
+    // keeping it in memory
+    if let (true, Some(answer_stream)) = (both_parts, entry.answer_stream) {
+        if !Path::new(&file_path).is_file() {
+            return missing_input();
+        }
+        println!("running both parts of day {day} problem");
+        println!("input from: '{file_path}' (streamed)");
+        for is_part_one in [true, false] {
+            let start = std::time::Instant::now();
+            let answer = File::open(&file_path)
+                .map_err(|e| format!("couldn't open the input: {e}"))
+                .and_then(|file| {
+                    let reader = normalize::NormalizedReader::new(BufReader::new(file));
+                    let changes = reader.changes();
+                    let answer = answer_stream(Box::new(reader), is_part_one);
+                    if is_part_one && !changes.get().is_empty() {
+                        println!("input normalised: {}", changes.get());
+                    }
+                    answer
+                });
+            report(is_part_one, answer, Some(start.elapsed()));
+        }
+        return run;
+    }
+
+    let part_nr = if do_part_two { 2 } else { 1 };
+    let changes;
+    let result = if visualize {
+        let Some(visualize) = entry.visualize else {
+            let message = format!("day {day} has no visualisation, sorry …");
+            println!("{message}");
+            return dashboard::Run::failed(parts, &message);
+        };
+        let Result::Ok(text) = std::fs::read_to_string(&file_path) else {
+            return missing_input();
+        };
+        let (text, text_changes) = normalize::normalize(&text);
+        changes = text_changes;
+        let options = visualize::Options {
+            fps,
+            start_paused: paused,
+            ..Default::default()
+        };
+        let mut visualizer = visualize::Visualizer::interactive(std::io::stdout(), options);
+        let result = visualize(&text, !do_part_two, &mut visualizer);
+        visualizer.finish();
         println!("running part {part_nr} of day {day} problem");
         println!("input from: '{file_path}'");
-        fun(text, !do_part_two);
+        result
+    } else if let Some(answer_stream) = entry.answer_stream {
+        let Result::Ok(file) = File::open(&file_path) else {
+            return missing_input();
+        };
+        println!("running part {part_nr} of day {day} problem");
+        println!("input from: '{file_path}' (streamed)");
+        let reader = normalize::NormalizedReader::new(BufReader::new(file));
+        let stream_changes = reader.changes();
+        let result = answer_stream(Box::new(reader), !do_part_two);
+        changes = stream_changes.get();
+        result
+    } else if let Result::Ok(text) = std::fs::read_to_string(&file_path) {
+        println!("running part {part_nr} of day {day} problem");
+        println!("input from: '{file_path}'");
+        let (text, text_changes) = normalize::normalize(&text);
+        changes = text_changes;
+        (entry.answer)(text, !do_part_two)
     } else {
-        println!("can't find input file at: '{file_path}'");
+        return missing_input();
+    };
+    if !changes.is_empty() {
+        println!("input normalised: {changes}");
     }
+    report(!do_part_two, result, None);
+    run
 }
 
-fn deduce_input_file_path(day: i32) -> String {
-    format!("{INPUT_DIR}/day{day:0>2}.txt")
+/// The parts asked for on the command line, both when none was.
+fn selected_parts(do_part_one: bool, do_part_two: bool) -> &'static [bool] {
+    match (do_part_one, do_part_two) {
+        (true, false) => &[true],
+        (false, true) => &[false],
+        _ => &[true, false],
+    }
 }
 
-fn init_new_day(day: i32) {
-    let input_dir_exists = Path::new(INPUT_DIR).is_dir();
-    let input_file_path = deduce_input_file_path(day);
-    let input_file_exists = Path::new(&input_file_path).is_file();
-
-    let day_src_dir = format!("src/day{day:0>2}");
-    let day_src_file = format!("{day_src_dir}/mod.rs");
-    let src_dir_exists = Path::new(&day_src_dir).is_dir();
-    let src_file_exists = Path::new(&day_src_file).is_file();
-
-    if !input_dir_exists {
-        if let Err(e) = std::fs::create_dir(&INPUT_DIR) {
-            println!("WARNING failed to create input directory");
-            println!("{e}");
-        } else {
-            println!("created input directory at: 'HOME/{INPUT_DIR}'");
+/// Path of the input to run and its recorded answers: the named input, the
+/// example or else `inputs/dayNN.txt`.
+fn input_file(day: i32, example: &str, input_name: String) -> (String, Option<examples::Expected>) {
+    let named_input = if input_name.is_empty() || input_name == inputs::DEFAULT_NAME {
+        None
+    } else {
+        let dir = inputs::input_dir_of_day(Path::new(INPUT_DIR), day);
+        Some(inputs::NamedInput {
+            input: dir.join(format!("{input_name}.txt")),
+            answers: dir.join(format!("{input_name}.answers")),
+            name: input_name,
+        })
+    };
+    let file_path = if let Some(named_input) = &named_input {
+        named_input.input.display().to_string()
+    } else if example.is_empty() {
+        deduce_input_file_path(day)
+    } else {
+        examples::example_input_path(day, example)
+            .display()
+            .to_string()
+    };
+    let expected = match &named_input {
+        Some(named_input) => inputs::read_expected(named_input).unwrap_or_else(|err| {
+            println!("WARNING {err}");
+            None
+        }),
+        None => read_expected_answers(day, example),
+    };
+    (file_path, expected)
+}
+
+/// Runs a day with the solver of a plugin, like the built-in one is run.
+fn run_variant(
+    day: i32,
+    variant: &str,
+    plugin_dir: &str,
+    parts: &[bool],
+    example: &str,
+    input_name: String,
+) -> dashboard::Run {
+    let plugins = load_plugins(Path::new(plugin_dir));
+    let Some(solver) = plugins
+        .iter()
+        .find(|x| x.day() == day && x.variant() == variant)
+    else {
+        let message = format!(
+            "no plugin in '{plugin_dir}' has a '{variant}' solver of day {day}, \
+             see 'list --plugins {plugin_dir}'"
+        );
+        println!("{message}");
+        return dashboard::Run::failed(parts, &message);
+    };
+
+    let input_dir = Path::new(INPUT_DIR);
+    if example.is_empty() && input_name.is_empty() && inputs::has_named_inputs(input_dir, day) {
+        println!("running day {day} problem with '{variant}' on every input");
+        let rows: Vec<inputs::Row> = inputs::named_inputs(input_dir, day)
+            .iter()
+            .map(|input| inputs::solve_variant(solver, input, parts))
+            .collect();
+        print!("{}", inputs::matrix(&rows));
+        return dashboard::Run::from_rows(&rows);
+    }
+
+    let (file_path, expected) = input_file(day, example, input_name);
+    let Result::Ok(text) = std::fs::read_to_string(&file_path) else {
+        let message = format!("can't find input file at: '{file_path}'");
+        println!("{message}");
+        return dashboard::Run::failed(parts, &message);
+    };
+    println!(
+        "running day {day} problem with the '{variant}' solver of '{}'",
+        solver.origin
+    );
+    println!("input from: '{file_path}'");
+    let (text, changes) = normalize::normalize(&text);
+    if !changes.is_empty() {
+        println!("input normalised: {changes}");
+    }
+    let mut run = dashboard::Run::default();
+    for &is_part_one in parts {
+        let part_nr = if is_part_one { 1 } else { 2 };
+        let start = std::time::Instant::now();
+        let answer = solver.solve(text.clone(), is_part_one);
+        let time = start.elapsed();
+        match &answer {
+            Ok(answer) => println!("part {part_nr} output: {answer} ({time:?})"),
+            Err(err) => println!("WARNING part {part_nr}: {err}"),
         }
+        print_expected(expected.as_ref(), is_part_one, !example.is_empty());
+        run.parts[part_nr - 1] = Some(dashboard::PartResult {
+            answer,
+            time: Some(time),
+            expected: expected
+                .as_ref()
+                .and_then(|x| x.part(is_part_one))
+                .map(String::from),
+        });
     }
+    run
+}
 
-    if !input_file_exists {
-        if let Err(e) = std::fs::File::create(&input_file_path) {
-            println!("WARNING failed to create input file");
-            println!("{e}");
-        } else {
-            println!("created input file at: 'HOME/{input_file_path}'")
+/// Answers of the example fixture, or of the answers store for the input.
+fn read_expected_answers(day: i32, example: &str) -> Option<examples::Expected> {
+    let expected = if example.is_empty() {
+        answers::read(Path::new(INPUT_DIR), day)
+    } else {
+        examples::read_expected(day, example).map(Some)
+    };
+    expected.unwrap_or_else(|err| {
+        println!("WARNING {err}");
+        None
+    })
+}
+
+fn print_expected(expected: Option<&examples::Expected>, is_part_one: bool, is_example: bool) {
+    let Some(expected) = expected else {
+        return;
+    };
+    match expected.part(is_part_one) {
+        Some(answer) => println!("expected: {answer}"),
+        None if is_example => {
+            let part_nr = if is_part_one { 1 } else { 2 };
+            println!("the example has no answer for part {part_nr}");
         }
+        None => {}
     }
+}
 
-    if !src_dir_exists {
-        if let Err(e) = std::fs::create_dir(&day_src_dir) {
-            println!("WARNING failed to create src directory");
-            println!("{e}");
+fn list_days(args: Vec<String>) {
+    let mut plugin_dir = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("list the days of the runner");
+        ap.refer(&mut plugin_dir).add_option(
+            &["-p", "--plugins"],
+            argparse::Store,
+            "also list the solvers of the plugins in the directory",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+    let plugins = if plugin_dir.is_empty() {
+        Vec::new()
+    } else {
+        load_plugins(Path::new(&plugin_dir))
+    };
+    let mut days: Vec<i32> = registry::DAYS.iter().map(|x| x.number).collect();
+    days.extend(registry::source_days());
+    days.extend(plugins.iter().map(Solver::day));
+    days.sort();
+    days.dedup();
+    for day in days {
+        let mut line = match registry::find(day) {
+            Some(entry) if entry.answer_stream.is_some() => format!("day {day:0>2}  streamed"),
+            Some(_) => format!("day {day:0>2}"),
+            None if registry::is_compiled_out(day) => format!(
+                "day {day:0>2}  compiled out, build with the '{}' feature",
+                registry::day_name(day)
+            ),
+            None => format!("day {day:0>2}  no built-in solver"),
+        };
+        let variants: Vec<String> = plugins
+            .iter()
+            .filter(|x| x.day() == day)
+            .map(|x| format!("{} ({})", x.variant(), x.origin))
+            .collect();
+        if !variants.is_empty() {
+            line.push_str(&format!("  plugins: {}", variants.join(", ")));
+        }
+        println!("{line}");
+    }
+}
+
+#[cfg(feature = "plugins")]
+fn load_plugins(dir: &Path) -> Vec<plugin::PluginSolver> {
+    let (plugins, warnings) = plugin::load_dir(dir);
+    for warning in warnings {
+        println!("WARNING {warning}");
+    }
+    plugins
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugins(_dir: &Path) -> Vec<plugin::PluginSolver> {
+    println!("WARNING the runner was built without the 'plugins' feature, no plugin is loaded");
+    Vec::new()
+}
+
+/// Times every solver of the days, the built-in ones and those of the
+/// plugins, and checks them against the recorded answers.
+fn compare_solvers(args: Vec<String>) {
+    let mut days: Vec<i32> = Vec::new();
+    let mut example = String::new();
+    let mut runs = 10;
+    let mut plugin_dir = plugin::PLUGIN_DIR.to_string();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("run the built-in solvers and the plugins' side by side");
+        ap.refer(&mut days).add_argument(
+            "days",
+            argparse::List,
+            "the advent days to compare, all days with a plugin by default",
+        );
+        ap.refer(&mut example).add_option(
+            &["-e", "--example"],
+            argparse::Store,
+            "run on the example fixture 'examples/dayNN/<EXAMPLE>.txt' instead of the input",
+        );
+        ap.refer(&mut runs).add_option(
+            &["-r", "--runs"],
+            argparse::Store,
+            "number of runs of every part, the fastest is reported",
+        );
+        ap.refer(&mut plugin_dir).add_option(
+            &["-p", "--plugins"],
+            argparse::Store,
+            "directory of the plugins, 'plugins' by default",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+    let plugins = load_plugins(Path::new(&plugin_dir));
+    if days.is_empty() {
+        days = plugins.iter().map(Solver::day).collect();
+        days.sort();
+        days.dedup();
+        if days.is_empty() {
+            println!("no plugins found in '{plugin_dir}'");
+            return;
+        }
+    }
+
+    let mut lines = Vec::new();
+    for day in days {
+        let solvers = plugin::solvers_of_day(day, &plugins);
+        if solvers.is_empty() {
+            println!("WARNING the day {day} has no solver, see 'list'");
+            continue;
+        }
+        let file_path = if example.is_empty() {
+            deduce_input_file_path(day)
         } else {
-            println!("created src directory at: 'HOME/{day_src_dir}'");
+            examples::example_input_path(day, &example)
+                .display()
+                .to_string()
+        };
+        let Result::Ok(text) = std::fs::read_to_string(&file_path) else {
+            println!("WARNING can't find input file at: '{file_path}'");
+            continue;
+        };
+        let (text, _) = normalize::normalize(&text);
+        let expected = read_expected_answers(day, &example);
+        for solver in solvers {
+            for is_part_one in [true, false] {
+                let measurement = bench::measure(solver, &text, is_part_one, runs);
+                let cell = inputs::Cell {
+                    answer: measurement.answer,
+                    expected: expected
+                        .as_ref()
+                        .and_then(|x| x.part(is_part_one))
+                        .map(String::from),
+                };
+                lines.push((
+                    day,
+                    measurement.part,
+                    solver.variant().to_string(),
+                    measurement.time,
+                    cell,
+                ));
+            }
         }
     }
 
-    if !src_file_exists {
-        match std::fs::File::create(&day_src_file) {
-            Err(e) => {
-                println!("WARNING failed to create src file");
-                println!("{e}");
+    if lines.is_empty() {
+        return;
+    }
+    let width = lines
+        .iter()
+        .map(|x| x.2.len())
+        .chain([7])
+        .max()
+        .unwrap_or(7);
+    println!("day  part  {:width$}          time  answer", "variant");
+    for (day, part, variant, time, cell) in lines {
+        println!(
+            "{day:>3}  {part:>4}  {variant:width$}  {:>12}  {cell}",
+            format!("{time:?}")
+        );
+    }
+}
+
+fn start_repl(args: Vec<String>) {
+    let mut day = 1;
+    let mut example = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("inspect the parsed input of a day, type 'help' for the commands");
+        ap.refer(&mut day)
+            .add_argument("day", argparse::Store, "the number of the advent day")
+            .required();
+        ap.refer(&mut example).add_option(
+            &["-e", "--example"],
+            argparse::Store,
+            "load the example fixture 'examples/dayNN/<EXAMPLE>.txt' instead of the input",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let Some(entry) = registry::find(day) else {
+        println!("the day {day} isn't compiled in, see 'list'");
+        return;
+    };
+    let file_path = if example.is_empty() {
+        deduce_input_file_path(day).into()
+    } else {
+        examples::example_input_path(day, &example)
+    };
+    match repl::Repl::new(entry, &file_path) {
+        Ok(mut repl) => {
+            if let Err(e) = repl.run(std::io::stdin().lock(), std::io::stdout()) {
+                println!("WARNING {e}");
             }
-            Ok(mut file) => {
-                println!("created src file at: 'HOME/{day_src_file}'");
-                if let Err(_) = file.write_all(SRC_TEMPLATE) {
-                    println!("WARNING failed to write day snippet");
+        }
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+fn run_bench(args: Vec<String>) {
+    let mut days: Vec<i32> = Vec::new();
+    let mut example = String::new();
+    let mut runs = 10;
+    let mut json_path = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "time both parts of the days, build with the 'count-alloc' feature to measure memory",
+        );
+        ap.refer(&mut days).add_argument(
+            "days",
+            argparse::List,
+            "the advent days to run, all compiled days by default",
+        );
+        ap.refer(&mut example).add_option(
+            &["-e", "--example"],
+            argparse::Store,
+            "run on the example fixture 'examples/dayNN/<EXAMPLE>.txt' instead of the input",
+        );
+        ap.refer(&mut runs).add_option(
+            &["-r", "--runs"],
+            argparse::Store,
+            "number of runs of every part, the fastest is reported",
+        );
+        ap.refer(&mut json_path).add_option(
+            &["--json"],
+            argparse::Store,
+            "also write the results as JSON to this file",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let entries: Vec<&registry::Day> = if days.is_empty() {
+        registry::DAYS.iter().collect()
+    } else {
+        days.iter()
+            .filter_map(|&day| {
+                let entry = registry::find(day);
+                if entry.is_none() {
+                    eprintln!("WARNING the day {day} isn't compiled in, see 'list'");
                 }
-            }
+                entry
+            })
+            .collect()
+    };
+
+    let mut measurements = Vec::new();
+    for entry in entries {
+        let file_path = if example.is_empty() {
+            deduce_input_file_path(entry.number)
+        } else {
+            examples::example_input_path(entry.number, &example)
+                .display()
+                .to_string()
+        };
+        let Result::Ok(text) = std::fs::read_to_string(&file_path) else {
+            eprintln!("WARNING can't find input file at: '{file_path}'");
+            continue;
+        };
+        let (text, changes) = normalize::normalize(&text);
+        if !changes.is_empty() {
+            eprintln!("day {} input normalised: {changes}", entry.number);
+        }
+        for is_part_one in [true, false] {
+            measurements.push(bench::measure(entry, &text, is_part_one, runs));
         }
     }
 
-    let this_file_text = include_str!("bin.rs");
+    // the solvers print their own diagnostics, so JSON goes to a file of its own
+    print!("{}", bench::table(&measurements));
+    if !json_path.is_empty() {
+        if let Err(e) = std::fs::write(&json_path, bench::json(&measurements)) {
+            println!("WARNING couldn't write '{json_path}'");
+            println!("{e}");
+        }
+    }
+    if example.is_empty() {
+        save_latest_bench(measurements);
+    }
+}
 
-    let re_line = Regex::new(r"^ *\d+ => \|x, y\| day\d\d::solve\(x, y\),").unwrap();
-    let re_num = Regex::new(r"\d+").unwrap();
+/// Solves every input of the days against its recorded answers, exits with 1
+/// when an answer is wrong or an input couldn't be solved.
+fn verify_inputs(args: Vec<String>) {
+    let mut days: Vec<i32> = Vec::new();
+    let mut plugin_dir = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "check both parts of every input 'inputs/dayNN.txt' and 'inputs/dayNN/<NAME>.txt'",
+        );
+        ap.refer(&mut days).add_argument(
+            "days",
+            argparse::List,
+            "the advent days to verify, all compiled days by default",
+        );
+        ap.refer(&mut plugin_dir).add_option(
+            &["-p", "--plugins"],
+            argparse::Store,
+            "also verify the solvers of the plugins in the directory",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
 
-    let mut idx_to_insert = None;
-    let mut found = false;
-    for (i, line) in this_file_text.lines().enumerate() {
-        if re_line.is_match(line) {
-            found = true;
-            if let Some(mat) = re_num.find(line) {
-                if let Ok(day_nr) = &line[mat.start()..mat.end()].parse::<i32>() {
-                    if *day_nr == day {
-                        println!("day sollution was found in source code");
-                        break;
-                    } else if *day_nr > day {
-                        idx_to_insert = Some(i);
-                        break;
-                    }
-                }
+    let plugins = if plugin_dir.is_empty() {
+        Vec::new()
+    } else {
+        load_plugins(Path::new(&plugin_dir))
+    };
+    let chosen_days = !days.is_empty();
+    if !chosen_days {
+        days = registry::DAYS.iter().map(|x| x.number).collect();
+        days.extend(plugins.iter().map(Solver::day));
+        days.sort();
+        days.dedup();
+    }
+
+    let input_dir = Path::new(INPUT_DIR);
+    let mut failures = 0;
+    let mut results = Vec::new();
+    for day in days {
+        let entry = registry::find(day);
+        let variants: Vec<&plugin::PluginSolver> =
+            plugins.iter().filter(|x| x.day() == day).collect();
+        if entry.is_none() && variants.is_empty() {
+            println!("WARNING the day {day} isn't compiled in, see 'list'");
+            continue;
+        }
+        let named_inputs = inputs::named_inputs(input_dir, day);
+        if named_inputs.is_empty() {
+            if chosen_days {
+                println!("day {day} has no input yet\n");
             }
-        } else if found {
-            idx_to_insert = Some(i);
-            break;
+            continue;
+        }
+        let mut rows: Vec<inputs::Row> = Vec::new();
+        if let Some(entry) = entry {
+            rows.extend(
+                named_inputs
+                    .iter()
+                    .map(|input| inputs::solve(entry, input, &[true, false])),
+            );
+        }
+        for solver in variants {
+            rows.extend(
+                named_inputs
+                    .iter()
+                    .map(|input| inputs::solve_variant(solver, input, &[true, false])),
+            );
         }
+        failures += rows
+            .iter()
+            .flat_map(|x| &x.parts)
+            .filter(|(_, x)| {
+                matches!(
+                    x.verdict(),
+                    inputs::Verdict::Wrong | inputs::Verdict::Failed
+                )
+            })
+            .count();
+        println!("day {day}");
+        print!("{}", inputs::matrix(&rows));
+        println!();
+        results.push((day, rows));
     }
+    let path = input_dir.join(inputs::LATEST_FILE);
+    if results.is_empty() {
+        println!("no inputs to verify");
+        return;
+    }
+    if let Err(e) = std::fs::write(&path, inputs::json(&results)) {
+        println!("WARNING couldn't write '{}'", path.display());
+        println!("{e}");
+    }
+    if failures > 0 {
+        println!("{failures} part(s) failed");
+        std::process::exit(1);
+    }
+    println!("every recorded answer matches");
+}
 
-    let insert_line_nr = if let Some(x) = idx_to_insert {
-        x
-    } else {
-        println!("WARNING couldn't find the place to insert new function call");
+fn serve(args: Vec<String>) {
+    let mut port: u16 = 8022;
+    let mut max_body = 1024 * 1024;
+    let mut timeout = 10.0;
+    let mut max_solves = serve::Config::default().max_solves;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "answer GET /days, POST /dayNN/partN and GET /verify over HTTP on localhost",
+        );
+        ap.refer(&mut port)
+            .add_option(&["-p", "--port"], argparse::Store, "port to listen on");
+        ap.refer(&mut max_body).add_option(
+            &["--max-body"],
+            argparse::Store,
+            "largest accepted input in bytes",
+        );
+        ap.refer(&mut timeout).add_option(
+            &["--timeout"],
+            argparse::Store,
+            "seconds to read a request and to solve it",
+        );
+        ap.refer(&mut max_solves).add_option(
+            &["--max-solves"],
+            argparse::Store,
+            "solvers running at once, the number of cores by default",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let timeout = match std::time::Duration::try_from_secs_f64(timeout) {
+        Ok(duration) if !duration.is_zero() => duration,
+        _ => {
+            println!("WARNING --timeout {timeout} isn't a usable number of seconds above 0");
+            return;
+        }
+    };
+    if max_solves == 0 {
+        println!("WARNING at least one solver has to be allowed to run");
         return;
+    }
+    let config = serve::Config {
+        max_body,
+        timeout,
+        max_solves,
+        input_dir: INPUT_DIR.into(),
     };
+    match serve::Server::bind(("127.0.0.1", port), config) {
+        Ok(server) => {
+            println!("listening on http://{}", server.local_addr());
+            server.run();
+        }
+        Err(err) => println!("WARNING {err}"),
+    }
+}
 
-    let mut failed_to_override = false;
-    match std::fs::OpenOptions::new().write(true).open(file!()) {
-        Ok(mut file) => {
-            for line in this_file_text.lines().take(insert_line_nr) {
-                if let Err(_) = writeln!(file, "{line}") {
-                    failed_to_override = true;
-                    println!("WARNING something went wrong while updating file");
-                    break;
-                }
+/// Keeps the times of the last run of every day for `status`.
+fn save_latest_bench(mut measurements: Vec<bench::Measurement>) {
+    let path = Path::new(INPUT_DIR).join(bench::LATEST_FILE);
+    if let Result::Ok(text) = std::fs::read_to_string(&path) {
+        match bench::parse_json(&text) {
+            Ok(previous) => {
+                let days: Vec<i32> = measurements.iter().map(|x| x.day).collect();
+                measurements.extend(previous.into_iter().filter(|x| !days.contains(&x.day)));
+                measurements.sort_by_key(|x| (x.day, x.part));
             }
+            Err(err) => println!("WARNING '{}' is replaced: {err}", path.display()),
+        }
+    }
+    if let Err(e) = std::fs::write(&path, bench::json(&measurements)) {
+        println!("WARNING couldn't write '{}'", path.display());
+        println!("{e}");
+    }
+}
 
-            if let Err(_) = writeln!(file, "        {day} => |x, y| day{day:0>2}::solve(x, y),") {
-                failed_to_override = true;
-                println!("WARNING something went wrong while updating file");
-            }
+fn show_status() {
+    let (days, warnings) = status::collect(Path::new(INPUT_DIR));
+    for warning in warnings {
+        println!("WARNING {warning}");
+    }
+    print!("{}", status::calendar(&days));
+}
 
-            for line in this_file_text.lines().skip(insert_line_nr) {
-                if let Err(_) = writeln!(file, "{line}") {
-                    failed_to_override = true;
-                    println!("WARNING something went wrong while updating file");
-                    break;
-                }
-            }
+fn extract_example(args: Vec<String>) {
+    let mut day = 1;
+    let mut page_path = String::new();
+    let mut name = String::new();
+    let mut force = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("write the example of a saved puzzle page as a fixture of the day");
+        ap.refer(&mut day)
+            .add_argument("day", argparse::Store, "the number of the advent day")
+            .required();
+        ap.refer(&mut page_path)
+            .add_argument("page", argparse::Store, "the puzzle page saved as HTML")
+            .required();
+        ap.refer(&mut name).add_option(
+            &["-n", "--name"],
+            argparse::Store,
+            "name of the fixture, the first free number by default",
+        );
+        ap.refer(&mut force).add_option(
+            &["-f", "--force"],
+            argparse::StoreTrue,
+            "overwrite an existing fixture",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
         }
-        Err(e) => {
-            println!("WARNING couldn't open bin file to modification");
-            println!("{e}");
+    }
+
+    let Result::Ok(page) = std::fs::read_to_string(&page_path) else {
+        println!("can't read the page at: '{page_path}'");
+        return;
+    };
+    let extracted = match examples::extract_from_html(&page) {
+        Ok(extracted) => extracted,
+        Err(err) => {
+            println!("WARNING {err}");
+            return;
+        }
+    };
+    println!(
+        "picked the example input out of {} code blocks:",
+        extracted.block_count
+    );
+    print!("{}", extracted.input);
+    for (is_part_one, part_nr) in [(true, 1), (false, 2)] {
+        match extracted.expected.part(is_part_one) {
+            Some(answer) => println!("part {part_nr} answer: {answer}"),
+            None => println!("no answer found for part {part_nr}"),
         }
     }
 
-    if failed_to_override {
-        match std::fs::OpenOptions::new().write(true).open(file!()) {
-            Ok(mut file) => {
-                if let Err(_) = write!(file, "{this_file_text}") {
-                    println!("something went terribly wrong, I am sorry");
-                } else {
-                    println!(
-                        "couldn't override a src file but thankfully it has not been corrupted"
-                    );
-                }
-            }
-            Err(e) => {
-                println!("something went terribly wrong, I am sorry");
-                println!("{e}");
+    if name.is_empty() {
+        name = (1..)
+            .map(|x: u32| x.to_string())
+            .find(|x| !examples::example_input_path(day, x).exists())
+            .expect("there is a free number");
+    }
+    let input_path = examples::example_input_path(day, &name);
+    let answers_path = examples::example_answers_path(day, &name);
+    if !force && (input_path.exists() || answers_path.exists()) {
+        println!(
+            "the fixture '{}' already exists, use --force to overwrite it",
+            input_path.display()
+        );
+        return;
+    }
+    let answers = format!(
+        "# extracted from '{page_path}'\n{}",
+        examples::format_expected(&extracted.expected)
+    );
+    let written = std::fs::create_dir_all(examples::example_dir(day))
+        .and_then(|_| std::fs::write(&input_path, &extracted.input))
+        .and_then(|_| std::fs::write(&answers_path, answers));
+    if let Err(e) = written {
+        println!("WARNING failed to write the fixture");
+        println!("{e}");
+        return;
+    }
+    println!("created fixture at: '{}'", input_path.display());
+
+    let stub = examples::test_stub(day, &name, &extracted.expected);
+    let src_file = format!("src/day{day:0>2}/mod.rs");
+    match std::fs::read_to_string(&src_file) {
+        Ok(src) => match std::fs::write(&src_file, examples::insert_test(&src, &stub)) {
+            Ok(()) => println!("added a test of the example to '{src_file}'"),
+            Err(e) => println!("WARNING couldn't update '{src_file}': {e}"),
+        },
+        Err(_) => {
+            println!("no source at '{src_file}', a test of the example:");
+            print!("{stub}");
+        }
+    }
+}
+
+fn read_puzzle(args: Vec<String>) {
+    let mut day = 1;
+    let mut page_path = String::new();
+    let mut plain = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("show the description of a puzzle saved as HTML");
+        ap.refer(&mut day)
+            .add_argument("day", argparse::Store, "the number of the advent day")
+            .required();
+        ap.refer(&mut page_path).add_option(
+            &["-p", "--page"],
+            argparse::Store,
+            "the saved puzzle page, 'inputs/dayNN.html' by default",
+        );
+        ap.refer(&mut plain).add_option(
+            &["--plain"],
+            argparse::StoreTrue,
+            "no colors, even on a terminal",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+    if page_path.is_empty() {
+        page_path = format!("{INPUT_DIR}/day{day:0>2}.html");
+    }
+    let cache_path = format!("{INPUT_DIR}/day{day:0>2}.puzzle.txt");
+    let color = !plain && std::io::IsTerminal::is_terminal(&std::io::stdout());
+    let width = visualize::terminal_size().0.min(100);
+
+    let cache = std::fs::read_to_string(&cache_path).ok();
+    let Result::Ok(page) = std::fs::read_to_string(&page_path) else {
+        // the page may have been cleaned up, the text stays readable
+        match cache {
+            Some(entry) => print!("{}", puzzle::any_cached_text(&entry)),
+            None => println!("can't find the puzzle page at: '{page_path}'"),
+        }
+        return;
+    };
+    let modified = |path: &str| std::fs::metadata(path).and_then(|x| x.modified()).ok();
+    let cache_is_fresh = match (modified(&cache_path), modified(&page_path)) {
+        (Some(cache), Some(page)) => cache >= page,
+        _ => false,
+    };
+    let cached = cache
+        .as_deref()
+        .filter(|_| cache_is_fresh)
+        .and_then(|entry| puzzle::cached_text(entry, width, color));
+    if let Some(text) = cached {
+        print!("{text}");
+        return;
+    }
+    match puzzle::render_page(&page, width, color) {
+        Ok(text) => {
+            print!("{text}");
+            let entry = puzzle::cache_entry(&text, width, color);
+            if let Err(e) = std::fs::write(&cache_path, entry) {
+                println!("WARNING couldn't cache the puzzle at '{cache_path}': {e}");
             }
         }
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+fn show_leaderboard(args: Vec<String>) {
+    let mut path = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "stars, scores and daily times of a private leaderboard, \
+             the JSON isn't fetched: save it from the site or pipe it in",
+        );
+        ap.refer(&mut path)
+            .add_argument(
+                "file",
+                argparse::Store,
+                "the leaderboard JSON saved from the site, '-' reads it from stdin",
+            )
+            .required();
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let text = if path == "-" {
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut text).map(|_| text)
     } else {
-        if let Ok(mut file) = std::fs::OpenOptions::new().append(true).open("src/lib.rs") {
-            if let Err(_) = writeln!(file, "pub mod day{day:0>2};") {
-                println!("WARNING couldn't update lib.rs");
-            }
-        } else {
-            println!("WARNING couldn't update lib.rs");
+        std::fs::read_to_string(&path)
+    };
+    let Result::Ok(text) = text else {
+        println!("can't read the leaderboard at: '{path}'");
+        return;
+    };
+    match leaderboard::parse(&text) {
+        Ok(leaderboard) => print!("{}", leaderboard::report(&leaderboard)),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+fn deduce_input_file_path(day: i32) -> String {
+    format!("{INPUT_DIR}/day{day:0>2}.txt")
+}
+
+#[cfg(feature = "init")]
+fn init_new_day(day: i32) {
+    scaffold::init_day(day, Path::new(INPUT_DIR));
+}
+
+#[cfg(feature = "init")]
+fn deinit_day(args: Vec<String>) {
+    let mut day = 0;
+    let mut archive = false;
+    let mut dry_run = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("remove a day from the registry, the features and the library");
+        ap.refer(&mut day)
+            .add_argument("day", argparse::Store, "the number of the advent day")
+            .required();
+        ap.refer(&mut archive).add_option(
+            &["--archive"],
+            argparse::StoreTrue,
+            "move its sources, examples and inputs to 'archive/dayNN'",
+        );
+        ap.refer(&mut dry_run).add_option(
+            &["-n", "--dry-run"],
+            argparse::StoreTrue,
+            "only print what would change",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
         }
     }
+    scaffold::deinit_day(day, archive, dry_run, Path::new(INPUT_DIR));
+}
+
+#[cfg(feature = "init")]
+fn move_day(args: Vec<String>) {
+    let mut from = 0;
+    let mut to = 0;
+    let mut dry_run = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("give a day another number");
+        ap.refer(&mut from)
+            .add_argument("from", argparse::Store, "the current number of the day")
+            .required();
+        ap.refer(&mut to)
+            .add_argument("to", argparse::Store, "the new number of the day")
+            .required();
+        ap.refer(&mut dry_run).add_option(
+            &["-n", "--dry-run"],
+            argparse::StoreTrue,
+            "only print what would change",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+    scaffold::move_day(from, to, dry_run, Path::new(INPUT_DIR));
+}
 
-    println!("I think init has been successful");
-    println!("remember to pase the input at: {input_file_path}")
+#[cfg(not(feature = "init"))]
+fn deinit_day(_args: Vec<String>) {
+    println!("the runner was built without the 'init' feature");
 }
 
-fn parse_arguments() -> (i32, bool, bool) {
+#[cfg(not(feature = "init"))]
+fn move_day(_args: Vec<String>) {
+    println!("the runner was built without the 'init' feature");
+}
+
+#[cfg(not(feature = "init"))]
+fn init_new_day(_day: i32) {
+    println!("the runner was built without the 'init' feature");
+}
+
+fn generate_input(args: Vec<String>) {
     let mut day = 1;
+    let mut params = generate::Params {
+        seed: 0,
+        size: 1000,
+        marker: None,
+    };
+    let mut marker: i64 = -1;
+    let mut output = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("generate a random input for the day, known answers go to stderr");
+        ap.refer(&mut day)
+            .add_argument("day", argparse::Store, "the number of the advent day")
+            .required();
+        ap.refer(&mut params.seed).add_option(
+            &["-s", "--seed"],
+            argparse::Store,
+            "seed of the generator",
+        );
+        ap.refer(&mut params.size).add_option(
+            &["-n", "--size"],
+            argparse::Store,
+            "number of entries (elves, rounds, moves, directories, …)",
+        );
+        ap.refer(&mut marker).add_option(
+            &["--marker"],
+            argparse::Store,
+            "day 6 only, position of the block of distinct characters",
+        );
+        ap.refer(&mut output).add_option(
+            &["-o", "--output"],
+            argparse::Store,
+            "write the input to this file instead of stdout",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+    params.marker = usize::try_from(marker).ok();
+
+    let generated = match generate::generate(day, params) {
+        None => {
+            println!("there is no generator for day {day}, sorry …");
+            return;
+        }
+        Some(Err(err)) => {
+            println!("WARNING couldn't generate the input: {err}");
+            return;
+        }
+        Some(Ok(generated)) => generated,
+    };
+
+    if output.is_empty() {
+        print!("{}", generated.input);
+    } else if let Err(e) = std::fs::write(&output, &generated.input) {
+        println!("WARNING couldn't write the input to '{output}'");
+        println!("{e}");
+        return;
+    }
+
+    for (part_nr, answer) in [(1, &generated.part_one), (2, &generated.part_two)] {
+        match answer {
+            Some(answer) => eprintln!("part {part_nr} answer: {answer}"),
+            None => eprintln!("part {part_nr} answer: unknown"),
+        }
+    }
+}
+
+struct Arguments {
+    day: i32,
+    do_part_one: bool,
+    do_part_two: bool,
+    init_day: bool,
+    example: String,
+    input_name: String,
+    visualize: bool,
+    fps: u32,
+    paused: bool,
+    variant: String,
+    plugin_dir: String,
+    json_path: String,
+    stream: bool,
+}
+
+fn parse_arguments() -> Arguments {
+    let mut day = 1;
+    let mut do_part_one = false;
     let mut do_part_two = false;
     let mut init_day = false;
+    let mut example = String::new();
+    let mut input_name = String::new();
+    let mut visualize = false;
+    let mut fps = 10;
+    let mut paused = false;
+    let mut variant = String::new();
+    let mut plugin_dir = plugin::PLUGIN_DIR.to_string();
+    let mut json_path = String::new();
+    let mut stream = false;
     {
         let mut ap = ArgumentParser::new();
         ap.set_description("choose the day of the advent");
         ap.refer(&mut day)
             .add_argument("day", argparse::Store, "the number of the advent day");
+        ap.refer(&mut do_part_one).add_option(
+            &["-1", "--part_one"],
+            argparse::StoreTrue,
+            "if specified running only the first part of the day problem",
+        );
         ap.refer(&mut do_part_two).add_option(
             &["-t", "-2", "--part_two"],
             argparse::StoreTrue,
-            "if specified running the second part of the day problem",
+            "if specified running only the second part of the day problem",
         );
         ap.refer(&mut init_day).add_option(
             &["--init", "--init_day"],
             argparse::StoreTrue,
             "if specified generating boilerplate code and files for writing a new day",
         );
+        ap.refer(&mut example).add_option(
+            &["-e", "--example"],
+            argparse::Store,
+            "solve the example fixture 'examples/dayNN/<EXAMPLE>.txt' instead of the input",
+        );
+        ap.refer(&mut input_name).add_option(
+            &["-i", "--input"],
+            argparse::Store,
+            "solve only the named input 'inputs/dayNN/<INPUT>.txt', 'default' for 'inputs/dayNN.txt'",
+        );
+        ap.refer(&mut visualize).add_option(
+            &["--visualize"],
+            argparse::StoreTrue,
+            "animate the solution in the terminal, for the days that can draw it",
+        );
+        ap.refer(&mut fps).add_option(
+            &["--fps"],
+            argparse::Store,
+            "frames per second of the animation, 0 draws as fast as possible",
+        );
+        ap.refer(&mut paused).add_option(
+            &["--paused"],
+            argparse::StoreTrue,
+            "start the animation paused, step with 'n'",
+        );
+        ap.refer(&mut variant).add_option(
+            &["--variant"],
+            argparse::Store,
+            "solve with the solver of a plugin instead of the built-in one",
+        );
+        ap.refer(&mut plugin_dir).add_option(
+            &["-p", "--plugins"],
+            argparse::Store,
+            "directory of the plugins for --variant, 'plugins' by default",
+        );
+        ap.refer(&mut json_path).add_option(
+            &["--json"],
+            argparse::Store,
+            "also write the answers of the run on a single input as JSON to this file",
+        );
+        ap.refer(&mut stream).add_option(
+            &["--stream"],
+            argparse::StoreTrue,
+            "stream the input of a streamed day once per part instead of parsing it once",
+        );
         ap.parse_args_or_exit();
     }
 
-    if day < 1 || day > 25 {
+    if !(1..=25).contains(&day) {
         println!("There is no day number {day}, you were living a lie!");
         std::process::exit(0);
     }
 
-    (day, do_part_two, init_day)
-}
-
-const SRC_TEMPLATE: &[u8] = b"pub fn solve(input: String, is_part_one: bool) {
-    let result = if is_part_one {
-        solve_part_one(input)
-    } else {
-        solve_part_two(input)
-    };
-
-    println!(\"output: {result}\");
-}
-
-fn solve_part_one(input: String) -> i32 {
-    0
-}
-
-fn solve_part_two(input: String) -> i32 {
-    0
+    Arguments {
+        day,
+        do_part_one,
+        do_part_two,
+        init_day,
+        example,
+        input_name,
+        visualize,
+        fps,
+        paused,
+        variant,
+        plugin_dir,
+        json_path,
+        stream,
+    }
 }
-";
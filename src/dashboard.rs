@@ -0,0 +1,848 @@
+//! Full-screen terminal dashboard, behind the `dashboard` command.
+//!
+//! The days are listed on the left, the selected one is shown on the right:
+//! a preview of its input, the answers of the last run, the timings of the
+//! previous runs and the output of the runner. The days run in a child
+//! process of the runner so that whatever the solvers print lands in the log
+//! instead of on the screen, the answers come back in the report the runner
+//! writes with `--json`. The animations run in place with [`crate::visualize`].
+
+use crate::examples;
+use crate::inputs;
+use crate::json::{self, Value};
+use crate::registry;
+use crate::visualize::{self, RawTerminal, Visualizer};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// How often a running child is checked for its end.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Runs kept per day for the timing history.
+const HISTORY: usize = 20;
+const LOG_LINES: usize = 200;
+const LIST_WIDTH: usize = 14;
+
+/// One part of a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartResult {
+    pub answer: Result<String, String>,
+    /// Only known when both parts ran on a single parse.
+    pub time: Option<Duration>,
+    pub expected: Option<String>,
+}
+
+/// The answers of one run of a day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Run {
+    /// Name of the example fixture, `None` for the input.
+    pub example: Option<String>,
+    pub parse_time: Option<Duration>,
+    pub parts: [Option<PartResult>; 2],
+}
+
+impl Run {
+    /// A run where every part asked for failed with the same error, like
+    /// when the input is missing.
+    pub fn failed(parts: &[bool], err: &str) -> Run {
+        let mut run = Run::default();
+        for &is_part_one in parts {
+            run.parts[usize::from(!is_part_one)] = Some(PartResult {
+                answer: Err(err.into()),
+                time: None,
+                expected: None,
+            });
+        }
+        run
+    }
+
+    /// The parts of a run on every named input: the answers are listed per
+    /// input, and a part fails when any input failed or gave a wrong answer.
+    pub fn from_rows(rows: &[inputs::Row]) -> Run {
+        let mut run = Run::default();
+        for (i, slot) in run.parts.iter_mut().enumerate() {
+            let cells: Vec<(String, &inputs::Cell)> = rows
+                .iter()
+                .flat_map(|row| {
+                    let cells = row
+                        .parts
+                        .iter()
+                        .filter(|(part, _)| usize::from(*part) == i + 1);
+                    cells.map(|(_, cell)| (row.label(), cell))
+                })
+                .collect();
+            if cells.is_empty() {
+                continue;
+            }
+            let failures: Vec<String> = cells
+                .iter()
+                .filter(|(_, cell)| {
+                    matches!(
+                        cell.verdict(),
+                        inputs::Verdict::Failed | inputs::Verdict::Wrong
+                    )
+                })
+                .map(|(label, cell)| format!("{label}: {cell}"))
+                .collect();
+            let listed = |value: &dyn Fn(&inputs::Cell) -> Option<String>| {
+                let values: Option<Vec<String>> = cells
+                    .iter()
+                    .map(|(label, cell)| Some(format!("{label}: {}", value(cell)?)))
+                    .collect();
+                values.map(|x| x.join(", "))
+            };
+            *slot = Some(PartResult {
+                answer: if failures.is_empty() {
+                    Ok(listed(&|cell| cell.answer.clone().ok()).unwrap_or_default())
+                } else {
+                    Err(format!(
+                        "{} of {} inputs failed, {}",
+                        failures.len(),
+                        cells.len(),
+                        failures.join("; ")
+                    ))
+                },
+                time: None,
+                expected: listed(&|cell| cell.expected.clone()),
+            });
+        }
+        run
+    }
+}
+
+/// The run as the runner writes it with `--json`, times in nanoseconds. The
+/// example isn't part of it, the dashboard knows which one it asked for.
+pub fn run_json(run: &Run) -> String {
+    let parts: Vec<String> = run
+        .parts
+        .iter()
+        .zip(1..)
+        .filter_map(|(part, part_nr)| {
+            let part = part.as_ref()?;
+            let mut item = format!("{{\"part\": {part_nr}");
+            match &part.answer {
+                Ok(answer) => write!(item, ", \"answer\": {}", json::quote(answer)),
+                Err(err) => write!(item, ", \"error\": {}", json::quote(err)),
+            }
+            .unwrap();
+            if let Some(time) = part.time {
+                let _ = write!(item, ", \"time_ns\": {}", time.as_nanos());
+            }
+            if let Some(expected) = &part.expected {
+                let _ = write!(item, ", \"expected\": {}", json::quote(expected));
+            }
+            item.push('}');
+            Some(item)
+        })
+        .collect();
+    let mut text = String::from("{");
+    if let Some(time) = run.parse_time {
+        let _ = write!(text, "\"parse_time_ns\": {}, ", time.as_nanos());
+    }
+    let _ = writeln!(text, "\"parts\": [{}]}}", parts.join(", "));
+    text
+}
+
+/// Reads a run back from [`run_json`].
+pub fn parse_run_json(text: &str) -> Result<Run, String> {
+    let document = json::parse(text).map_err(|e| e.to_string())?;
+    let items = document
+        .get("parts")
+        .and_then(Value::as_array)
+        .ok_or("expected the parts of a run")?;
+    let nanos = |item: &Value, key: &str| {
+        let nanos = item.get(key).and_then(Value::as_i64)?;
+        Some(Duration::from_nanos(u64::try_from(nanos).ok()?))
+    };
+    let mut run = Run {
+        parse_time: nanos(&document, "parse_time_ns"),
+        ..Run::default()
+    };
+    for (i, item) in items.iter().enumerate() {
+        let text = |key: &str| item.get(key).and_then(Value::as_str).map(String::from);
+        let missing = |key: &str| format!("part {}: no '{key}'", i + 1);
+        let index = match item.get("part").and_then(Value::as_i64) {
+            Some(part_nr @ 1..=2) => part_nr as usize - 1,
+            _ => return Err(missing("part")),
+        };
+        run.parts[index] = Some(PartResult {
+            answer: match (text("answer"), text("error")) {
+                (Some(answer), _) => Ok(answer),
+                (None, Some(err)) => Err(err),
+                (None, None) => return Err(missing("answer")),
+            },
+            time: nanos(item, "time_ns"),
+            expected: text("expected"),
+        });
+    }
+    Ok(run)
+}
+
+/// What the loop has to do after a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Nothing,
+    /// The state changed, the screen has to be drawn again.
+    Redraw,
+    /// Runs one part, or both parts for `None`.
+    Run(Option<bool>),
+    /// Animates part one (`true`) or two.
+    Visualize(bool),
+    Quit,
+}
+
+pub struct Dashboard {
+    pub days: Vec<i32>,
+    pub selected: usize,
+    /// Runs on the first example fixture of the day instead of the input.
+    pub example: bool,
+    /// Runs the day again when its input or the runner changes.
+    pub watch: bool,
+    pub history: BTreeMap<i32, Vec<Run>>,
+    pub log: Vec<String>,
+    input_dir: PathBuf,
+    /// Bytes of an escape sequence read so far, the arrow keys send three.
+    escape: Vec<u8>,
+}
+
+impl Dashboard {
+    /// The compiled days and those with a source module.
+    pub fn new(input_dir: &Path) -> Self {
+        let mut days: Vec<i32> = registry::DAYS.iter().map(|x| x.number).collect();
+        days.extend(registry::source_days());
+        days.sort();
+        days.dedup();
+        Dashboard {
+            days,
+            selected: 0,
+            example: false,
+            watch: false,
+            history: BTreeMap::new(),
+            log: Vec::new(),
+            input_dir: input_dir.into(),
+            escape: Vec::new(),
+        }
+    }
+
+    pub fn day(&self) -> Option<i32> {
+        self.days.get(self.selected).copied()
+    }
+
+    /// First fixture of the day in name order.
+    pub fn example_name(&self) -> Option<String> {
+        let dir = examples::example_dir(self.day()?);
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|x| {
+                let path = x.ok()?.path();
+                (path.extension()? == "txt").then_some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        names.sort();
+        names.into_iter().next()
+    }
+
+    /// The file the selected day runs on.
+    pub fn input_path(&self) -> Option<PathBuf> {
+        let day = self.day()?;
+        if self.example {
+            Some(examples::example_input_path(day, &self.example_name()?))
+        } else {
+            Some(self.input_dir.join(format!("day{day:0>2}.txt")))
+        }
+    }
+
+    /// Arguments of the runner for a run of the selected day.
+    pub fn run_args(&self, part: Option<bool>) -> Vec<String> {
+        let mut args = vec![self.day().unwrap_or(1).to_string()];
+        match part {
+            Some(true) => args.push("-1".into()),
+            Some(false) => args.push("-2".into()),
+            None => {}
+        }
+        match (self.example, self.example_name()) {
+            (true, Some(name)) => args.extend(["-e".into(), name]),
+            // only 'inputs/dayNN.txt', even when the day has named inputs
+            _ => args.extend(["-i".into(), inputs::DEFAULT_NAME.into()]),
+        }
+        args
+    }
+
+    pub fn key(&mut self, byte: u8) -> Action {
+        if byte == 0x1b || !self.escape.is_empty() {
+            self.escape.push(byte);
+            return match self.escape.as_slice() {
+                [0x1b] | [0x1b, b'['] => Action::Nothing,
+                [0x1b, b'[', b'A'] => {
+                    self.escape.clear();
+                    self.select(-1)
+                }
+                [0x1b, b'[', b'B'] => {
+                    self.escape.clear();
+                    self.select(1)
+                }
+                _ => {
+                    self.escape.clear();
+                    Action::Nothing
+                }
+            };
+        }
+        match byte {
+            b'k' => self.select(-1),
+            b'j' => self.select(1),
+            b'1' => Action::Run(Some(true)),
+            b'2' => Action::Run(Some(false)),
+            b'r' | b'\n' | b'\r' => Action::Run(None),
+            b'v' => Action::Visualize(true),
+            b'V' => Action::Visualize(false),
+            b'w' => {
+                self.watch = !self.watch;
+                Action::Redraw
+            }
+            b'e' => {
+                self.example = !self.example;
+                if self.example && self.example_name().is_none() {
+                    self.example = false;
+                    self.push_log(format!(
+                        "day {} has no example fixture",
+                        self.day().unwrap_or(0)
+                    ));
+                }
+                Action::Redraw
+            }
+            b'q' => Action::Quit,
+            _ => Action::Nothing,
+        }
+    }
+
+    fn select(&mut self, step: isize) -> Action {
+        let last = self.days.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(step).min(last);
+        if self.example && self.example_name().is_none() {
+            self.example = false;
+        }
+        Action::Redraw
+    }
+
+    pub fn push_log(&mut self, line: String) {
+        self.log.push(line);
+        let extra = self.log.len().saturating_sub(LOG_LINES);
+        self.log.drain(..extra);
+    }
+
+    /// Keeps a run of the selected day and what the runner printed.
+    pub fn record(&mut self, mut run: Run, output: &str) {
+        let Some(day) = self.day() else {
+            return;
+        };
+        run.example = self.example.then(|| self.example_name()).flatten();
+        let runs = self.history.entry(day).or_default();
+        runs.push(run);
+        let extra = runs.len().saturating_sub(HISTORY);
+        runs.drain(..extra);
+        for line in output.lines() {
+            self.push_log(line.to_string());
+        }
+    }
+
+    /// Latest modification of the files a watched run depends on.
+    fn watched_time(&self, runner: &Path) -> Option<SystemTime> {
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|x| x.modified()).ok();
+        let input = self.input_path().and_then(|x| modified(&x));
+        input.max(modified(runner))
+    }
+
+    /// The whole screen, `height` lines of at most `width` characters.
+    pub fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let right_width = width.saturating_sub(LIST_WIDTH + 3);
+        let body_height = height.saturating_sub(1);
+
+        let list: Vec<String> = self
+            .days
+            .iter()
+            .enumerate()
+            .map(|(i, day)| {
+                let marker = if i == self.selected { '>' } else { ' ' };
+                let stars: String = self
+                    .history
+                    .get(day)
+                    .and_then(|x| x.last())
+                    .map_or([None, None], |x| x.parts.clone())
+                    .iter()
+                    .map(|x| match x {
+                        Some(part) => match (&part.answer, &part.expected) {
+                            (Ok(answer), Some(expected)) if answer == expected => '*',
+                            (Ok(_), None) => '+',
+                            _ => '!',
+                        },
+                        None => '.',
+                    })
+                    .collect();
+                let compiled = if registry::find(*day).is_some() {
+                    ' '
+                } else {
+                    '-'
+                };
+                format!("{marker} day {day:0>2}{compiled}{stars}")
+            })
+            .collect();
+
+        let right = self.right_panel(body_height);
+        let mut lines: Vec<String> = (0..body_height)
+            .map(|row| {
+                let left = list.get(row).map_or("", String::as_str);
+                let right: String = right
+                    .get(row)
+                    .map_or("", String::as_str)
+                    .chars()
+                    .take(right_width)
+                    .collect();
+                format!("{left:LIST_WIDTH$} | {right}")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        let help = format!(
+            "[j/k] day [1/2] part [r] both [v/V] visualise [w] watch: {} [e] example: {} [q] quit",
+            if self.watch { "on" } else { "off" },
+            if self.example { "on" } else { "off" },
+        );
+        lines.push(help.chars().take(width).collect());
+        lines
+    }
+
+    fn right_panel(&self, height: usize) -> Vec<String> {
+        let Some(day) = self.day() else {
+            return vec!["no days yet, create one with '--init'".into()];
+        };
+        let mut lines = Vec::new();
+        let path = self.input_path();
+        let path_text = path
+            .as_ref()
+            .map_or(String::new(), |x| x.display().to_string());
+        lines.push(format!("day {day} on '{path_text}'"));
+
+        let preview_rows = (height / 4).max(3);
+        match path.and_then(|x| std::fs::read_to_string(x).ok()) {
+            Some(text) => {
+                lines.push(format!("-- input, {} lines --", text.lines().count()));
+                lines.extend(text.lines().take(preview_rows).map(String::from));
+            }
+            None => lines.push("-- input missing --".into()),
+        }
+
+        let runs = self.history.get(&day).map_or(&[][..], Vec::as_slice);
+        lines.push("-- answers --".into());
+        for (i, part) in runs
+            .last()
+            .map_or([None, None], |x| x.parts.clone())
+            .iter()
+            .enumerate()
+        {
+            let text = match part {
+                None => "-".into(),
+                Some(part) => match (&part.answer, &part.expected) {
+                    (Err(err), _) => format!("WARNING {err}"),
+                    (Ok(answer), None) => answer.clone(),
+                    (Ok(answer), Some(expected)) if answer == expected => format!("{answer} ok"),
+                    (Ok(answer), Some(expected)) => format!("{answer} WRONG, expected {expected}"),
+                },
+            };
+            lines.push(format!("part {}  {text}", i + 1));
+        }
+
+        lines.push("-- timings --".into());
+        lines.push(format!(
+            "{:>3}  {:>12}  {:>12}  {:>12}",
+            "run", "parse", "part 1", "part 2"
+        ));
+        let time = |x: Option<Duration>| x.map_or("-".into(), |x| format!("{x:?}"));
+        let history_rows = (height / 5).max(2);
+        let first = runs.len().saturating_sub(history_rows);
+        for (i, run) in runs.iter().enumerate().skip(first) {
+            let part_time = |index: usize| time(run.parts[index].as_ref().and_then(|x| x.time));
+            lines.push(format!(
+                "{:>3}  {:>12}  {:>12}  {:>12}{}",
+                i + 1,
+                time(run.parse_time),
+                part_time(0),
+                part_time(1),
+                run.example
+                    .as_ref()
+                    .map_or(String::new(), |x| format!("  example {x}"))
+            ));
+        }
+
+        lines.push("-- log --".into());
+        let log_rows = height.saturating_sub(lines.len());
+        let first = self.log.len().saturating_sub(log_rows);
+        lines.extend(self.log[first..].iter().cloned());
+        lines
+    }
+}
+
+/// Shows the cursor and leaves the alternate screen the dashboard draws on.
+const SCREEN_RESET: &str = "\x1b[?25h\x1b[?1049l";
+
+/// Runs the dashboard until `q`, `runner` is the executable of the runner.
+pub fn run(runner: &Path, input_dir: &Path) -> Result<(), String> {
+    if !std::io::stdin().is_terminal() {
+        return Err("the dashboard needs a terminal".into());
+    }
+    let mut dashboard = Dashboard::new(input_dir);
+    let keys = visualize::stdin_bytes();
+    let mut terminal = RawTerminal::enable(SCREEN_RESET);
+    let mut out = std::io::stdout();
+    let _ = write!(out, "\x1b[?1049h\x1b[?25l");
+    let mut watched = dashboard.watched_time(runner);
+
+    loop {
+        draw(&mut out, &dashboard);
+        let byte = keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .recv_timeout(WATCH_INTERVAL);
+        let action = match byte {
+            Ok(byte) => dashboard.key(byte),
+            Err(RecvTimeoutError::Timeout) if dashboard.watch => {
+                let now = dashboard.watched_time(runner);
+                if now == watched {
+                    continue;
+                }
+                Action::Run(None)
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => Action::Quit,
+        };
+        match action {
+            Action::Nothing | Action::Redraw => {}
+            Action::Quit => break,
+            Action::Run(part) => {
+                let args = dashboard.run_args(part);
+                dashboard.push_log(format!("$ {} {}", runner.display(), args.join(" ")));
+                dashboard.push_log("running, [q] cancels".into());
+                draw(&mut out, &dashboard);
+                let parts: &[bool] = match part {
+                    Some(is_part_one) => &[is_part_one],
+                    None => &[true, false],
+                };
+                match run_runner(runner, &args, parts, keys) {
+                    Ok((run, output)) => dashboard.record(run, &output),
+                    Err(err) => dashboard.push_log(format!("WARNING {err}")),
+                }
+            }
+            Action::Visualize(is_part_one) => {
+                let _ = write!(out, "{SCREEN_RESET}");
+                let _ = out.flush();
+                drop(terminal);
+                let message = animate(&dashboard, is_part_one);
+                println!("{message}\n[press any key to return to the dashboard]");
+                terminal = RawTerminal::enable(SCREEN_RESET);
+                let _ = keys.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                dashboard.push_log(message);
+                let _ = write!(out, "\x1b[?1049h\x1b[?25l");
+            }
+        }
+        watched = dashboard.watched_time(runner);
+    }
+
+    let _ = write!(out, "{SCREEN_RESET}");
+    let _ = out.flush();
+    drop(terminal);
+    Ok(())
+}
+
+/// Runs the runner until it exits or `q` is pressed, with the run it reports
+/// and what it printed.
+fn run_runner(
+    runner: &Path,
+    args: &[String],
+    parts: &[bool],
+    keys: &Mutex<Receiver<u8>>,
+) -> Result<(Run, String), String> {
+    let report =
+        std::env::temp_dir().join(format!("aoc2022-dashboard-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&report);
+    let mut child = Command::new(runner)
+        .args(args)
+        .arg("--json")
+        .arg(&report)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("couldn't start the runner: {e}"))?;
+    // read while the child runs, a full pipe would block it
+    let read_all = |stream: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(mut stream) = stream {
+                let _ = stream.read_to_end(&mut bytes);
+            }
+            String::from_utf8_lossy(&bytes).into_owned()
+        })
+    };
+    let stdout = read_all(child.stdout.take().map(|x| Box::new(x) as _));
+    let stderr = read_all(child.stderr.take().map(|x| Box::new(x) as _));
+
+    let cancelled = loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break false,
+            Ok(None) => {}
+            Err(e) => return Err(format!("couldn't wait for the runner: {e}")),
+        }
+        let key = keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .recv_timeout(POLL_INTERVAL);
+        match key {
+            Ok(b'q') | Err(RecvTimeoutError::Disconnected) => break true,
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+        }
+    };
+    if cancelled {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    let mut output = stdout.join().unwrap_or_default();
+    output.push_str(&stderr.join().unwrap_or_default());
+    if cancelled {
+        return Err("the run was cancelled".into());
+    }
+    let run = match std::fs::read_to_string(&report) {
+        Ok(text) => parse_run_json(&text)
+            .unwrap_or_else(|err| Run::failed(parts, &format!("unreadable report, {err}"))),
+        Err(_) => Run::failed(parts, "the runner wrote no report, see the log"),
+    };
+    let _ = std::fs::remove_file(&report);
+    Ok((run, output))
+}
+
+fn draw(out: &mut impl Write, dashboard: &Dashboard) {
+    let (width, height) = visualize::terminal_size();
+    let mut screen = String::from("\x1b[H");
+    for line in dashboard.render(width, height) {
+        screen.push_str(&line);
+        screen.push_str("\x1b[K\r\n");
+    }
+    screen.truncate(screen.len() - 2);
+    screen.push_str("\x1b[J");
+    let _ = out.write_all(screen.as_bytes());
+    let _ = out.flush();
+}
+
+/// Animates the selected day in place, the returned line ends up in the log.
+fn animate(dashboard: &Dashboard, is_part_one: bool) -> String {
+    let Some(day) = dashboard.day() else {
+        return "no day selected".into();
+    };
+    let Some(visualize) = registry::find(day).and_then(|x| x.visualize) else {
+        return format!("day {day} has no visualisation");
+    };
+    let Some(path) = dashboard.input_path() else {
+        return format!("day {day} has no example fixture");
+    };
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return format!("can't find input file at: '{}'", path.display());
+    };
+    let (text, _) = crate::normalize::normalize(&text);
+    let mut visualizer = Visualizer::interactive(std::io::stdout(), visualize::Options::default());
+    let result = visualize(&text, is_part_one, &mut visualizer);
+    visualizer.finish();
+    let part_nr = if is_part_one { 1 } else { 2 };
+    match result {
+        Ok(answer) => format!("visualised part {part_nr} of day {day}: {answer}"),
+        Err(err) => format!("WARNING visualised part {part_nr} of day {day}: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(answer: Result<&str, &str>, time: Option<u64>, expected: Option<&str>) -> PartResult {
+        PartResult {
+            answer: answer.map(String::from).map_err(String::from),
+            time: time.map(Duration::from_micros),
+            expected: expected.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_run_json() {
+        let run = Run {
+            example: None,
+            parse_time: Some(Duration::from_micros(20)),
+            parts: [
+                Some(part(Ok("24000"), Some(5), Some("24000"))),
+                Some(part(Err("no elves"), None, None)),
+            ],
+        };
+        assert_eq!(
+            run_json(&run),
+            "{\"parse_time_ns\": 20000, \"parts\": [\
+             {\"part\": 1, \"answer\": \"24000\", \"time_ns\": 5000, \"expected\": \"24000\"}, \
+             {\"part\": 2, \"error\": \"no elves\"}]}\n"
+        );
+        assert_eq!(parse_run_json(&run_json(&run)), Ok(run));
+
+        let failed = Run::failed(&[false], "missing input");
+        assert!(failed.parts[0].is_none());
+        assert_eq!(parse_run_json(&run_json(&failed)), Ok(failed));
+        assert_eq!(parse_run_json("{\"parts\": []}"), Ok(Run::default()));
+        assert!(parse_run_json("{\"parts\": [{\"part\": 3, \"answer\": \"1\"}]}").is_err());
+        assert!(parse_run_json("{\"parts\": [{\"part\": 1}]}").is_err());
+        assert!(parse_run_json("part 1 output: 3").is_err());
+    }
+
+    #[test]
+    fn test_run_from_rows() {
+        let cell = |answer: Result<&str, &str>, expected: Option<&str>| inputs::Cell {
+            answer: answer.map(String::from).map_err(String::from),
+            expected: expected.map(String::from),
+        };
+        let mut rows = vec![
+            inputs::Row {
+                name: "default".into(),
+                variant: None,
+                parts: vec![(1, cell(Ok("3"), Some("3"))), (2, cell(Ok("7"), None))],
+            },
+            inputs::Row {
+                name: "alice".into(),
+                variant: None,
+                parts: vec![(1, cell(Ok("5"), Some("5"))), (2, cell(Ok("8"), Some("8")))],
+            },
+        ];
+        assert_eq!(
+            Run::from_rows(&rows),
+            Run {
+                example: None,
+                parse_time: None,
+                parts: [
+                    Some(part(
+                        Ok("default: 3, alice: 5"),
+                        None,
+                        Some("default: 3, alice: 5")
+                    )),
+                    Some(part(Ok("default: 7, alice: 8"), None, None)),
+                ],
+            }
+        );
+
+        rows[1].parts[0].1 = cell(Ok("4"), Some("5"));
+        rows[1].parts[1].1 = cell(Err("no rope"), None);
+        rows[0].parts.truncate(1);
+        let run = Run::from_rows(&rows);
+        assert_eq!(
+            run.parts[0].as_ref().unwrap().answer,
+            Err("1 of 2 inputs failed, alice: 4 WRONG, expected 5".into())
+        );
+        assert_eq!(
+            run.parts[1].as_ref().unwrap().answer,
+            Err("1 of 1 inputs failed, alice: WARNING no rope".into())
+        );
+        assert_eq!(Run::from_rows(&[]), Run::default());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_runner() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let keys = Mutex::new(receiver);
+        // `sh -c SCRIPT sh --json REPORT` gets the report as "$2"
+        let script = |text: &str| ["-c".to_string(), text.to_string(), "sh".to_string()];
+        let report = "printf '{\"parts\": [{\"part\": 2, \"answer\": \"42\"}]}' > \"$2\"";
+        let (run, output) = run_runner(
+            Path::new("sh"),
+            &script(&format!("echo solving; {report}")),
+            &[false],
+            &keys,
+        )
+        .unwrap();
+        assert_eq!(run.parts[1], Some(part(Ok("42"), None, None)));
+        assert_eq!(output, "solving\n");
+
+        let (run, _) = run_runner(Path::new("sh"), &script("exit 101"), &[true], &keys).unwrap();
+        assert_eq!(
+            run.parts[0].as_ref().unwrap().answer,
+            Err("the runner wrote no report, see the log".into())
+        );
+
+        sender.send(b'q').unwrap();
+        let start = std::time::Instant::now();
+        let cancelled = run_runner(Path::new("sh"), &script("sleep 10"), &[true], &keys);
+        assert_eq!(cancelled, Err("the run was cancelled".into()));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut dashboard = Dashboard::new(Path::new("inputs"));
+        dashboard.days = vec![1, 2, 3];
+        assert_eq!(dashboard.key(b'j'), Action::Redraw);
+        assert_eq!(dashboard.day(), Some(2));
+        for byte in [0x1b, b'[', b'B', 0x1b, b'[', b'B'] {
+            dashboard.key(byte);
+        }
+        assert_eq!(dashboard.day(), Some(3));
+        for byte in [0x1b, b'[', b'A'] {
+            dashboard.key(byte);
+        }
+        assert_eq!(dashboard.day(), Some(2));
+        assert_eq!(dashboard.key(b'2'), Action::Run(Some(false)));
+        assert_eq!(
+            dashboard.run_args(Some(false)),
+            ["2", "-2", "-i", "default"]
+        );
+        assert_eq!(dashboard.key(b'r'), Action::Run(None));
+        assert_eq!(dashboard.key(b'V'), Action::Visualize(false));
+        dashboard.key(b'w');
+        assert!(dashboard.watch);
+        assert_eq!(dashboard.key(b'q'), Action::Quit);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut dashboard = Dashboard::new(Path::new("no-inputs"));
+        dashboard.days = vec![1, 2];
+        let run = Run {
+            example: None,
+            parse_time: Some(Duration::from_micros(2)),
+            parts: [
+                Some(part(Ok("3"), Some(1), Some("3"))),
+                Some(part(Ok("4"), Some(1), Some("5"))),
+            ],
+        };
+        dashboard.record(
+            run,
+            "running both parts of day 1 problem\npart 1 output: 3 (1µs)\n",
+        );
+        let lines = dashboard.render(100, 30);
+        assert_eq!(lines.len(), 30);
+        assert!(lines[0].starts_with("> day 01"));
+        assert!(lines[0].ends_with("*!    | day 1 on 'no-inputs/day01.txt'"));
+        assert!(lines[1].ends_with("..    | -- input missing --"));
+        assert_eq!(lines[20].trim_start(), "|");
+        assert!(lines
+            .iter()
+            .any(|x| x.ends_with("| part 2  4 WRONG, expected 5")));
+        assert!(lines
+            .iter()
+            .any(|x| x.contains("  1           2µs           1µs")));
+        assert!(lines
+            .iter()
+            .any(|x| x.ends_with("| part 1 output: 3 (1µs)")));
+        assert!(lines[29].starts_with("[j/k] day"));
+        assert!(dashboard
+            .render(20, 5)
+            .iter()
+            .all(|x| x.chars().count() <= 20));
+    }
+}
@@ -1,104 +1,210 @@
+use crate::overflow::{checked_sum_signed, Overflow};
+use crate::parsing::{read_lines, ParseError, ReadError};
+use crate::registry::Parsed;
+use crate::topk::TopK;
+use std::io::BufRead;
+
 pub fn solve(input: String, is_part_one: bool) {
-    println!("output: {}", solution(input, is_part_one));
+    solve_stream(input.as_bytes(), is_part_one);
+}
+
+pub fn solve_stream(input: impl BufRead, is_part_one: bool) {
+    match answer_stream(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    answer_stream(input.as_bytes(), is_part_one)
 }
 
-fn solution(input: String, is_part_one: bool) -> i32 {
+pub fn answer_stream(input: impl BufRead, is_part_one: bool) -> Result<String, String> {
+    let mut read_error = ReadError::default();
+    let result = solution(read_lines(input, &mut read_error), is_part_one);
+    read_error.check()?;
+    result.map(|x| x.to_string())
+}
+
+/// Total calories carried by every elf, in the order of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inventory {
+    elves: Vec<i64>,
+}
+
+impl Inventory {
+    pub fn elves(&self) -> &[i64] {
+        &self.elves
+    }
+}
+
+pub fn parse(input: &str) -> Result<Inventory, String> {
+    let mut elves = Vec::new();
+    for_each_elf(input.lines().map(String::from), |calories| {
+        elves.push(calories)
+    })
+    .map_err(|err| err.to_string())?;
+    Ok(Inventory { elves })
+}
+
+/// Calories carried by the elf with the most calories.
+pub fn part_one(inventory: &Inventory) -> Result<i64, Overflow> {
+    top_calories(inventory.elves.iter().copied(), 1)
+}
+
+/// Calories carried by the three elves with the most calories.
+pub fn part_two(inventory: &Inventory) -> Result<i64, Overflow> {
+    top_calories(inventory.elves.iter().copied(), 3)
+}
+
+fn solution(lines: impl Iterator<Item = String>, is_part_one: bool) -> Result<i64, String> {
     let topk = if is_part_one { 1 } else { 3 };
+    let mut tracker = TopK::new(topk);
+    for_each_elf(lines, |calories| {
+        tracker.push(calories);
+    })
+    .map_err(|err| err.to_string())?;
+    checked_sum_signed(tracker.into_sorted_vec()).map_err(|err| err.to_string())
+}
+
+fn top_calories(elves: impl Iterator<Item = i64>, k: usize) -> Result<i64, Overflow> {
+    let mut tracker = TopK::new(k);
+    tracker.extend(elves);
+    checked_sum_signed(tracker.into_sorted_vec())
+}
+
+/// Calls `on_elf` with the total calories of every paragraph of the input,
+/// the first line that isn't a number is an error.
+fn for_each_elf(
+    lines: impl Iterator<Item = String>,
+    mut on_elf: impl FnMut(i64),
+) -> Result<(), ParseError> {
     let mut current_calories = 0;
-    let mut tracker = TopKTracker::new(topk);
-    for line in input.split('\n') {
-        match line.parse::<i32>() {
-            Result::Ok(number) => current_calories += number,
-            Result::Err(..) => {
-                tracker.update(current_calories);
-                current_calories = 0;
+    let mut in_paragraph = false;
+    for (i, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            if in_paragraph {
+                on_elf(current_calories);
             }
+            current_calories = 0;
+            in_paragraph = false;
+            continue;
         }
-    }
-
-    tracker.get_topk().iter().sum()
-}
 
-struct TopKTracker {
-    k: i32,
-    topk: Vec<i32>,
+        in_paragraph = true;
+        let number = line
+            .trim()
+            .parse::<i64>()
+            .map_err(|err| ParseError::new(i + 1, format!("{err} in '{line}'")))?;
+        current_calories = checked_sum_signed([current_calories, number])
+            .map_err(|err| ParseError::new(i + 1, err.to_string()))?;
+    }
+    if in_paragraph {
+        on_elf(current_calories);
+    }
+    Ok(())
 }
 
-impl TopKTracker {
-    fn new(k: i32) -> Self {
-        TopKTracker { k, topk: vec![] }
+impl Parsed for Inventory {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
     }
 
-    fn get_topk(&self) -> &[i32] {
-        &self.topk
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        result.map(|x| x.to_string()).map_err(|err| err.to_string())
     }
 
-    pub fn update(&mut self, given_number: i32) -> bool {
-        let mut working_number = given_number;
-        let mut updated = false;
-        for i in 0..self.k as usize {
-            if self.topk.len() == i {
-                self.topk.push(working_number);
-                return true;
-            }
-            if self.topk[i] < working_number {
-                let temp = self.topk[i];
-                self.topk[i] = working_number;
-                working_number = temp;
-                updated = true;
-            }
+    fn query(&self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        match (command, args) {
+            ("elf", [nr]) => Some(
+                nr.parse::<usize>()
+                    .ok()
+                    .and_then(|nr| self.elves.get(nr.checked_sub(1)?))
+                    .map(|calories| format!("{calories} calories"))
+                    .ok_or_else(|| format!("there is no elf number {nr}")),
+            ),
+            ("top", [k]) => Some(
+                k.parse::<usize>()
+                    .map_err(|err| err.to_string())
+                    .and_then(|k| {
+                        top_calories(self.elves.iter().copied(), k).map_err(|err| err.to_string())
+                    })
+                    .map(|calories| format!("{calories} calories")),
+            ),
+            _ => None,
         }
-        updated
+    }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &[
+            "elf <N>: calories carried by the N-th elf",
+            "top <K>: calories of the top K elves",
+        ]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::check_property;
 
     #[test]
-    fn test_example() {
-        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+    fn test_property_matches_sorting_reference() {
+        check_property("day01 equals summing the sorted elves", |rng| {
+            let mut input = String::new();
+            let mut elves = Vec::new();
+            for _ in 0..rng.range(1, 12) {
+                let calories: Vec<i64> = (0..rng.range(1, 4))
+                    .map(|_| rng.range(1, 9) as i64)
+                    .collect();
+                for x in &calories {
+                    input.push_str(&format!("{x}\n"));
+                }
+                input.push_str(&"\n".repeat(rng.range(1, 2) as usize));
+                elves.push(calories.iter().sum::<i64>());
+            }
 
-        assert_eq!(solution(input.into(), true), 24000);
+            elves.sort_by(|a, b| b.cmp(a));
+            for (is_part_one, k) in [(true, 1), (false, 3)] {
+                let expected: i64 = elves.iter().take(k).sum();
+                let found = solution(input.lines().map(String::from), is_part_one);
+                if found != Ok(expected) {
+                    return Err(format!("{input:?} gave {found:?} instead of {expected}"));
+                }
+            }
+            Ok(())
+        });
     }
 
     #[test]
-    fn test_example_part_two() {
-        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
-
-        assert_eq!(solution(input.into(), false), 45000);
+    fn test_top_query() {
+        let inventory = parse("1000\n\n3000\n").unwrap();
+        assert_eq!(
+            inventory.query("top", &["100000000000000"]),
+            Some(Ok("4000 calories".into()))
+        );
     }
 
     #[test]
-    fn test_top_k_tracker() {
-        let mut tracker = TopKTracker::new(4);
-
-        for num in [15, -4, 8, 13, 26, 8, 13, 3].iter() {
-            tracker.update(*num);
-        }
-
-        assert_eq!(tracker.get_topk().len(), 4);
-        for (&n, gt) in std::iter::zip(tracker.get_topk(), [26, 15, 13, 13]) {
-            assert_eq!(n, gt);
-        }
-
-        tracker = TopKTracker::new(4);
-        for num in [15, -4].iter() {
-            tracker.update(*num);
-        }
-        assert_eq!(tracker.get_topk().len(), 2);
-        for (&n, gt) in std::iter::zip(tracker.get_topk(), [15, -4]) {
-            assert_eq!(n, gt);
-        }
-
-        tracker = TopKTracker::new(3);
-        for num in [6000, 4000, 11000, 24000, 10000].iter() {
-            tracker.update(*num);
-        }
-        assert_eq!(tracker.get_topk().len(), 3);
-        for (&n, gt) in std::iter::zip(tracker.get_topk(), [24000, 11000, 10000]) {
-            assert_eq!(n, gt);
-        }
+    fn test_malformed_calories() {
+        let input = "1000\n2000\n\n3x00\n";
+        assert_eq!(
+            parse(input),
+            Err("line 4: invalid digit found in string in '3x00'".into())
+        );
+        assert_eq!(
+            answer(input.into(), true),
+            parse(input).map(|_| String::new())
+        );
+        assert_eq!(
+            answer("1000\n2000\n\n4000\n".into(), true),
+            Ok("4000".into())
+        );
     }
 }
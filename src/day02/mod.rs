@@ -1,106 +1,167 @@
+use crate::overflow::{checked_sum, Overflow};
+use crate::parsing::{read_lines, ParseError, ReadError};
+use crate::registry::Parsed;
 use std::convert::TryFrom;
+use std::io::BufRead;
 
 pub fn solve(input: String, part_one: bool) {
+    solve_stream(input.as_bytes(), part_one);
+}
+
+pub fn solve_stream(input: impl BufRead, part_one: bool) {
+    match answer_stream(input, part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, part_one: bool) -> Result<String, String> {
+    answer_stream(input.as_bytes(), part_one)
+}
+
+pub fn answer_stream(input: impl BufRead, part_one: bool) -> Result<String, String> {
+    let mut read_error = ReadError::default();
+    let lines = read_lines(input, &mut read_error);
+    let mut parse_error = None;
+    let rounds =
+        input_parser(lines).map_while(|round| round.map_err(|err| parse_error = Some(err)).ok());
     let result = if part_one {
-        solve_part_one(input)
+        total_score_part_one(rounds)
     } else {
-        solve_part_two(input)
+        total_score_part_two(rounds)
     };
-    println!("output: {result}");
+    read_error.check()?;
+    if let Some(err) = parse_error {
+        return Err(err.to_string());
+    }
+    result.map(|x| x.to_string()).map_err(|err| err.to_string())
+}
+
+/// The rounds of the strategy guide as (opponent's column, second column).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrategyGuide {
+    rounds: Vec<(char, char)>,
+}
+
+impl StrategyGuide {
+    pub fn rounds(&self) -> &[(char, char)] {
+        &self.rounds
+    }
+}
+
+/// Parses one round like `A Y` per line, the first malformed line is an error.
+pub fn parse(input: &str) -> Result<StrategyGuide, String> {
+    let rounds = input_parser(input.lines().map(String::from))
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    Ok(StrategyGuide { rounds })
+}
+
+/// Total score when the second column is the shape to play.
+pub fn part_one(guide: &StrategyGuide) -> Result<u64, Overflow> {
+    total_score_part_one(guide.rounds.iter().copied())
+}
+
+/// Total score when the second column is the outcome of the round.
+pub fn part_two(guide: &StrategyGuide) -> Result<u64, Overflow> {
+    total_score_part_two(guide.rounds.iter().copied())
 }
 
-fn solve_part_one(input: String) -> i32 {
+fn total_score_part_one(rounds: impl Iterator<Item = (char, char)>) -> Result<u64, Overflow> {
     let mut total_score = 0;
-    for (left, right) in input_parser(&input) {
-        if let (Ok(oponent_play), Ok(my_play)) = (RPS::try_from(left), RPS::try_from(right)) {
-            total_score += score(oponent_play, my_play);
+    for (left, right) in rounds {
+        if let (Ok(oponent_play), Ok(my_play)) = (Shape::try_from(left), Shape::try_from(right)) {
+            total_score = checked_sum([total_score, score(oponent_play, my_play)])?;
         }
     }
-    total_score
+    Ok(total_score)
 }
 
-fn solve_part_two(input: String) -> i32 {
+fn total_score_part_two(rounds: impl Iterator<Item = (char, char)>) -> Result<u64, Overflow> {
     let mut total_score = 0;
-    for (left, right) in input_parser(&input) {
-        if let Ok(oponent_play) = RPS::try_from(left) {
-            total_score += score_part_two(oponent_play, right);
+    for (left, right) in rounds {
+        if let Ok(oponent_play) = Shape::try_from(left) {
+            total_score = checked_sum([total_score, score_part_two(oponent_play, right)])?;
         }
     }
 
-    total_score
+    Ok(total_score)
 }
 
-fn input_parser(input: &str) -> impl Iterator<Item = (char, char)> + '_ {
-    input.lines().filter(|x| x.len() == 3).flat_map(|line| {
-        let pair: Vec<_> = line.split(" ").collect();
-        if pair.len() != 2 || pair[0].len() != 1 || pair[1].len() != 1 {
-            return None;
-        }
-
-        if let Some(left) = pair[0].chars().next() {
-            if let Some(right) = pair[1].chars().next() {
-                return Some((left, right));
+/// The first column is `A`, `B` or `C` and the second one `X`, `Y` or `Z`.
+fn input_parser(
+    lines: impl Iterator<Item = String>,
+) -> impl Iterator<Item = Result<(char, char), ParseError>> {
+    lines
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            match columns[..] {
+                [left @ ("A" | "B" | "C"), right @ ("X" | "Y" | "Z")] => {
+                    Ok((left.as_bytes()[0] as char, right.as_bytes()[0] as char))
+                }
+                _ => Err(ParseError::new(
+                    i + 1,
+                    format!("couldn't parse round '{line}'"),
+                )),
             }
-        }
-
-        None
-    })
+        })
 }
 
 #[derive(PartialEq, Eq)]
-enum RPS {
+enum Shape {
     Rock = 1,
     Paper = 2,
     Scissors = 3,
 }
 
-impl RPS {
-    pub fn beats(&self, other: RPS) -> bool {
-        if *self == RPS::Rock && other == RPS::Scissors {
-            return true;
-        } else if *self == RPS::Paper && other == RPS::Rock {
-            return true;
-        } else if *self == RPS::Scissors && other == RPS::Paper {
-            return true;
-        }
-        false
+impl Shape {
+    pub fn beats(&self, other: Shape) -> bool {
+        matches!(
+            (self, other),
+            (Shape::Rock, Shape::Scissors)
+                | (Shape::Paper, Shape::Rock)
+                | (Shape::Scissors, Shape::Paper)
+        )
     }
 
-    pub fn loses_with(self) -> RPS {
+    pub fn loses_with(self) -> Shape {
         match self {
-            RPS::Rock => RPS::Paper,
-            RPS::Paper => RPS::Scissors,
-            RPS::Scissors => RPS::Rock,
+            Shape::Rock => Shape::Paper,
+            Shape::Paper => Shape::Scissors,
+            Shape::Scissors => Shape::Rock,
         }
     }
 
-    pub fn draws_with(self) -> RPS {
+    pub fn draws_with(self) -> Shape {
         self
     }
 
-    pub fn wins_with(self) -> RPS {
+    pub fn wins_with(self) -> Shape {
         match self {
-            RPS::Rock => RPS::Scissors,
-            RPS::Paper => RPS::Rock,
-            RPS::Scissors => RPS::Paper,
+            Shape::Rock => Shape::Scissors,
+            Shape::Paper => Shape::Rock,
+            Shape::Scissors => Shape::Paper,
         }
     }
 }
 
-impl std::convert::TryFrom<char> for RPS {
+impl std::convert::TryFrom<char> for Shape {
     type Error = ();
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            'A' | 'X' => Ok(RPS::Rock),
-            'B' | 'Y' => Ok(RPS::Paper),
-            'C' | 'Z' => Ok(RPS::Scissors),
+            'A' | 'X' => Ok(Shape::Rock),
+            'B' | 'Y' => Ok(Shape::Paper),
+            'C' | 'Z' => Ok(Shape::Scissors),
             _ => Err(()),
         }
     }
 }
 
-fn score(oponent_play: RPS, your_play: RPS) -> i32 {
+fn score(oponent_play: Shape, your_play: Shape) -> u64 {
     let points = if oponent_play == your_play {
         3
     } else if your_play.beats(oponent_play) {
@@ -109,20 +170,30 @@ fn score(oponent_play: RPS, your_play: RPS) -> i32 {
         0
     };
 
-    points + your_play as i32
+    points + your_play as u64
 }
 
-fn score_part_two(oponent_play: RPS, how_to_play: char) -> i32 {
-    let (lose, draw, win) = ('X', 'Y', 'Z');
-    if how_to_play == lose {
-        0 + oponent_play.wins_with() as i32
-    } else if how_to_play == draw {
-        3 + oponent_play.draws_with() as i32
-    } else if how_to_play == win {
-        6 + oponent_play.loses_with() as i32
-    } else {
-        println!("WARNING unknown character: {how_to_play}");
-        0
+/// `how_to_play` is `X`, `Y` or `Z`, the parser lets nothing else through.
+fn score_part_two(oponent_play: Shape, how_to_play: char) -> u64 {
+    match how_to_play {
+        'X' => oponent_play.wins_with() as u64,
+        'Y' => 3 + oponent_play.draws_with() as u64,
+        _ => 6 + oponent_play.loses_with() as u64,
+    }
+}
+
+impl Parsed for StrategyGuide {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        result.map(|x| x.to_string()).map_err(|err| err.to_string())
     }
 }
 
@@ -131,16 +202,16 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example_part_one() {
-        let input = "A Y\nB X\nC Z\n";
-
-        assert_eq!(solve_part_one(input.into()), 15);
-    }
-
-    #[test]
-    fn test_example_part_two() {
-        let input = "A Y\nB X\nC Z\n";
-
-        assert_eq!(solve_part_two(input.into()), 12);
+    fn test_malformed_round() {
+        let input = "A Y\n\nB W\nC Z\n";
+        assert_eq!(
+            parse(input),
+            Err("line 3: couldn't parse round 'B W'".into())
+        );
+        assert_eq!(
+            answer(input.into(), false),
+            parse(input).map(|_| String::new())
+        );
+        assert_eq!(answer("A Y\nB X\nC Z\n".into(), true), Ok("15".into()));
     }
 }
@@ -1,80 +1,171 @@
+use crate::overflow::checked_sum;
+use crate::parsing::{read_lines, ParseError, ReadError};
+use crate::registry::Parsed;
 use itertools::Itertools;
 use std::collections::HashSet;
+use std::io::BufRead;
 
 pub fn solve(input: String, part_one: bool) {
+    solve_stream(input.as_bytes(), part_one);
+}
+
+pub fn solve_stream(input: impl BufRead, part_one: bool) {
+    match answer_stream(input, part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, part_one: bool) -> Result<String, String> {
+    answer_stream(input.as_bytes(), part_one)
+}
+
+pub fn answer_stream(input: impl BufRead, part_one: bool) -> Result<String, String> {
+    let mut read_error = ReadError::default();
+    let lines = read_lines(input, &mut read_error);
+    let mut parse_error = None;
+    let rucksacks = parse_rucksacks(lines)
+        .map_while(|rucksack| rucksack.map_err(|err| parse_error = Some(err)).ok());
     let result = if part_one {
-        solve_part_one(input)
+        priorities_part_one(rucksacks)
     } else {
-        solve_part_two(input)
+        priorities_part_two(rucksacks)
     };
-    println!("output: {result}");
+    read_error.check()?;
+    if let Some(err) = parse_error {
+        return Err(err.to_string());
+    }
+    result.map(|x| x.to_string())
 }
 
-fn solve_part_one(input: String) -> i32 {
-    let mut total_sum = 0;
-    for line in input.lines().filter(|x| x.len() > 0) {
-        let half_ptr = line.len() / 2;
-        let in_both = find_chars_shared_by_strings(&[&line[..half_ptr], &line[half_ptr..]]);
+/// Contents of every rucksack, one item per character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rucksacks {
+    rucksacks: Vec<String>,
+}
 
-        if let Some(item) = in_both.into_iter().next() {
-            total_sum += calc_priority(&item);
-        }
+impl Rucksacks {
+    pub fn rucksacks(&self) -> &[String] {
+        &self.rucksacks
     }
+}
 
-    total_sum as i32
+/// Parses one rucksack of letters per line, the first malformed line is an
+/// error.
+pub fn parse(input: &str) -> Result<Rucksacks, String> {
+    let rucksacks = parse_rucksacks(input.lines().map(String::from))
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    Ok(Rucksacks { rucksacks })
+}
+
+/// Sum of the priorities of the items found in both compartments.
+pub fn part_one(rucksacks: &Rucksacks) -> Result<u64, String> {
+    priorities_part_one(rucksacks.rucksacks.iter().cloned())
+}
+
+/// Sum of the priorities of the badges of every group of three elves.
+pub fn part_two(rucksacks: &Rucksacks) -> Result<u64, String> {
+    priorities_part_two(rucksacks.rucksacks.iter().cloned())
+}
+
+/// Every item is a letter and both compartments hold as many items.
+fn parse_rucksacks(
+    lines: impl Iterator<Item = String>,
+) -> impl Iterator<Item = Result<String, ParseError>> {
+    lines
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            if let Some(column) = line.find(|x: char| !x.is_ascii_alphabetic()) {
+                let column = line[..column].chars().count() + 1;
+                Err(ParseError::new(i + 1, "an item isn't a letter").with_column(column))
+            } else if line.len() % 2 != 0 {
+                Err(ParseError::new(
+                    i + 1,
+                    format!("'{line}' can't be split into two compartments"),
+                ))
+            } else {
+                Ok(line)
+            }
+        })
 }
 
-fn solve_part_two(input: String) -> i32 {
+fn priorities_part_one(rucksacks: impl Iterator<Item = String>) -> Result<u64, String> {
     let mut total_sum = 0;
+    for (i, line) in rucksacks.enumerate() {
+        // the parser only lets ASCII letters through, so a byte is an item
+        let (first, second) = line.split_at(line.len() / 2);
+        let in_both = find_chars_shared_by_strings(&[first, second]);
+        let item = single_item(in_both)
+            .map_err(|err| format!("rucksack {}: {err} in both compartments", i + 1))?;
+        total_sum = checked_sum([total_sum, calc_priority(item)]).map_err(|err| err.to_string())?;
+    }
 
-    let mut tripleter = input.lines().filter(|x| x.len() > 0).tuples();
-    for (one, two, three) in tripleter.by_ref() {
-        let mut shared_chars = find_chars_shared_by_strings(&[one, two, three]).into_iter();
+    Ok(total_sum)
+}
 
-        if let Some(item) = shared_chars.next() {
-            total_sum += calc_priority(&item);
+fn priorities_part_two(rucksacks: impl Iterator<Item = String>) -> Result<u64, String> {
+    let mut total_sum = 0;
 
-            if shared_chars.next() != None {
-                println!("WARNING found more than one char shared in triplet");
-            }
-        } else {
-            println!("WARNING not found any char shared between triplet");
-        }
+    let mut tripleter = rucksacks.tuples();
+    for (i, (one, two, three)) in tripleter.by_ref().enumerate() {
+        let shared_chars = find_chars_shared_by_strings(&[&one, &two, &three]);
+        let item = single_item(shared_chars)
+            .map_err(|err| format!("group {}: {err} in the three rucksacks", i + 1))?;
+        total_sum = checked_sum([total_sum, calc_priority(item)]).map_err(|err| err.to_string())?;
     }
-    if tripleter.into_buffer().len() > 0 {
-        println!("WARNING the number of gropus were not divisible by 3");
+    if tripleter.into_buffer().len() != 0 {
+        return Err("the number of rucksacks isn't a multiple of 3".into());
     }
 
-    total_sum as i32
+    Ok(total_sum)
+}
+
+/// The puzzle promises exactly one shared item.
+fn single_item(items: HashSet<char>) -> Result<char, String> {
+    match items.len() {
+        1 => Ok(items.into_iter().next().unwrap_or_default()),
+        0 => Err("no item is found".into()),
+        _ => Err("more than one item is found".into()),
+    }
 }
 
 fn find_chars_shared_by_strings(texts: &[&str]) -> HashSet<char> {
-    if texts.len() == 0 {
+    if texts.is_empty() {
         return HashSet::<char>::new();
     }
     let mut shared_chars: HashSet<char> = texts[0].chars().collect();
     for text in texts.iter().skip(1) {
         let second_set: HashSet<char> = text.chars().collect();
         let intersection = shared_chars.intersection(&second_set);
-        shared_chars = intersection.into_iter().map(|x| *x).collect();
+        shared_chars = intersection.into_iter().copied().collect();
     }
     shared_chars
 }
 
-fn calc_priority(item: &char) -> u32 {
-    if !item.is_ascii_alphabetic() {
-        println!("item is not alphabetic");
+/// `item` is a letter, the parser lets nothing else through.
+fn calc_priority(item: char) -> u64 {
+    if item.is_ascii_lowercase() {
+        item as u64 - 'a' as u64 + 1
+    } else {
+        item as u64 - 'A' as u64 + 27
     }
+}
 
-    let value = *item as u32;
+impl Parsed for Rucksacks {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
 
-    if item.is_lowercase() {
-        value - 'a' as u32 + 1
-    } else if item.is_uppercase() {
-        value - 'A' as u32 + 27
-    } else {
-        print!("WARNING this shouldn't be possible to run");
-        0
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        result.map(|x| x.to_string())
     }
 }
 
@@ -82,20 +173,25 @@ fn calc_priority(item: &char) -> u32 {
 mod tests {
     use super::*;
 
-    const INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
-
-    #[test]
-    fn test_example_part_one() {
-        assert_eq!(solve_part_one(INPUT.into()), 157);
-    }
-
     #[test]
-    fn test_example_part_two() {
-        assert_eq!(solve_part_two(INPUT.into()), 70);
+    fn test_malformed_rucksacks() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp\n\njqHRNq-GDGLrsFMfFZSrLrFZsSL\n";
+        assert_eq!(
+            parse(input),
+            Err("line 3, column 7: an item isn't a letter".into())
+        );
+        assert_eq!(
+            answer(input.into(), true),
+            parse(input).map(|_| String::new())
+        );
+        assert_eq!(
+            answer("abcd\n".into(), true),
+            Err("rucksack 1: no item is found in both compartments".into())
+        );
+        assert_eq!(
+            answer("aa\nab\nac\nbb\n".into(), false),
+            Err("the number of rucksacks isn't a multiple of 3".into())
+        );
+        assert_eq!(answer("abca\n".into(), true), Ok("1".into()));
     }
 }
@@ -1,76 +1,144 @@
+use crate::parsing::{integers, parse_lines_with, read_lines, ParseError, ReadError};
+use crate::registry::Parsed;
+use std::io::BufRead;
+
 pub fn solve(input: String, is_part_one: bool) {
+    solve_stream(input.as_bytes(), is_part_one);
+}
+
+pub fn solve_stream(input: impl BufRead, is_part_one: bool) {
+    match answer_stream(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    answer_stream(input.as_bytes(), is_part_one)
+}
+
+pub fn answer_stream(input: impl BufRead, is_part_one: bool) -> Result<String, String> {
+    let mut read_error = ReadError::default();
+    let lines = read_lines(input, &mut read_error);
+    let mut parse_error = None;
+    let pairs =
+        parse_input(lines).map_while(|pair| pair.map_err(|err| parse_error = Some(err)).ok());
     let result = if is_part_one {
-        solve_part_one(input)
+        count_part_one(pairs)
     } else {
-        solve_part_two(input)
+        count_part_two(pairs)
     };
-    println!("output: {result}");
+    read_error.check()?;
+    match parse_error {
+        Some(err) => Err(err.to_string()),
+        None => Ok(result.to_string()),
+    }
+}
+
+/// Inclusive range of section ids assigned to one elf.
+pub type Sections = (i64, i64);
+
+/// Section assignments of every pair of elves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignments {
+    pairs: Vec<(Sections, Sections)>,
+}
+
+impl Assignments {
+    pub fn pairs(&self) -> &[(Sections, Sections)] {
+        &self.pairs
+    }
 }
 
-fn solve_part_one(input: String) -> i32 {
+/// Parses one pair of ranges like `2-4,6-8` per line, the first malformed
+/// line is an error.
+pub fn parse(input: &str) -> Result<Assignments, String> {
+    let pairs = parse_lines_with(input, parse_pair)
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    Ok(Assignments { pairs })
+}
+
+/// Number of pairs where one range fully contains the other.
+pub fn part_one(assignments: &Assignments) -> u64 {
+    count_part_one(assignments.pairs.iter().copied())
+}
+
+/// Number of pairs with overlapping ranges.
+pub fn part_two(assignments: &Assignments) -> u64 {
+    count_part_two(assignments.pairs.iter().copied())
+}
+
+fn count_part_one(pairs: impl Iterator<Item = (Sections, Sections)>) -> u64 {
     let mut total_count = 0;
-    let mut pair_count = 0;
-    for ((l1, u1), (l2, u2)) in parse_input(&input) {
-        pair_count += 1;
-        if l1 <= l2 && u1 >= u2 {
-            total_count += 1;
-        } else if l2 <= l1 && u2 >= u1 {
+    for ((l1, u1), (l2, u2)) in pairs {
+        if (l1 <= l2 && u1 >= u2) || (l2 <= l1 && u2 >= u1) {
             total_count += 1;
         }
     }
-    println!("pair count: {pair_count}");
     total_count
 }
 
-fn solve_part_two(input: String) -> i32 {
+fn count_part_two(pairs: impl Iterator<Item = (Sections, Sections)>) -> u64 {
     let mut total_count = 0;
-    let mut pair_count = 0;
-    for ((l1, u1), (l2, u2)) in parse_input(&input) {
-        pair_count += 1;
+    for ((l1, u1), (l2, u2)) in pairs {
         if !(u1 < l2 || u2 < l1) {
             total_count += 1;
         }
     }
-    println!("pair count: {pair_count}");
     total_count
 }
 
-fn parse_section(text: &str) -> Option<(i32, i32)> {
-    let mut split_itr = text.split('-');
-    let one = split_itr.next();
-    let two = split_itr.next();
-    let (left, right) = match (one, two) {
-        (Some(left), Some(right)) => (left, right),
-        _ => return None,
-    };
-
-    let lower_bound: Result<i32, _> = left.parse();
-    let upper_bound: Result<i32, _> = right.parse();
+fn parse_input(
+    lines: impl Iterator<Item = String>,
+) -> impl Iterator<Item = Result<(Sections, Sections), ParseError>> {
+    lines
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_pair(&line).map_err(|err| ParseError::new(i + 1, format!("{err} in '{line}'")))
+        })
+}
 
-    match (lower_bound, upper_bound) {
-        (Ok(lower_bound), Ok(upper_bound)) => Some((lower_bound, upper_bound)),
-        _ => None,
+fn parse_pair(line: &str) -> Result<(Sections, Sections), &'static str> {
+    let bounds: Vec<i64> = integers(line).collect();
+    match bounds[..] {
+        [l1, u1, l2, u2] => Ok(((l1, u1), (l2, u2))),
+        _ => Err("couldn't parse section pair"),
     }
 }
 
-fn split_sections(text: &str) -> Option<(&str, &str)> {
-    let mut itr = text.split(',');
-    let left = itr.next();
-    let right = itr.next();
-    match (left, right) {
-        (Some(left), Some(right)) => Some((left, right)),
-        _ => None,
+impl Parsed for Assignments {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        Ok(result.to_string())
     }
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = ((i32, i32), (i32, i32))> + '_ {
-    let parse_both_sections = |(left, right)| match (parse_section(left), parse_section(right)) {
-        (Some(left), Some(right)) => Some((left, right)),
-        _ => None,
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    input
-        .lines()
-        .flat_map(split_sections)
-        .flat_map(parse_both_sections)
+    #[test]
+    fn test_malformed_pair() {
+        let input = "2-4,6-8\n\n2-3,x\n5-7,7-9\n";
+        assert_eq!(
+            parse(input),
+            Err("line 3: couldn't parse section pair in '2-3,x'".into())
+        );
+        assert_eq!(
+            answer(input.into(), true),
+            parse(input).map(|_| String::new())
+        );
+        assert_eq!(answer("2-4,6-8\n\n2-8,3-7\n".into(), true), Ok("1".into()));
+    }
 }
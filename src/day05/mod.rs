@@ -1,112 +1,190 @@
+use crate::parsing::{paragraphs, ParseError, Pattern};
+use crate::registry::Parsed;
+use crate::visualize::{Frame, FrameSink};
+
 pub fn solve(input: String, is_part_one: bool) {
-    let (stacks_repr, moves) = match split_input(&input) {
-        Some((stacks, moves)) => (stacks, moves),
-        _ => {
-            println!("WARNING couldn't find stacks or moves in the input");
-            return;
-        }
-    };
+    match answer(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    parse(&input)?.part(is_part_one)
+}
+
+/// One line of the rearrangement procedure, stacks are numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The starting stacks and the moves of the crane.
+#[derive(Debug, Clone)]
+pub struct Rearrangement {
+    stacks: Stacks,
+    moves: Vec<Move>,
+}
+
+impl Rearrangement {
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+}
+
+/// Parses the drawing of the stacks and the moves below it, the first
+/// malformed move is an error.
+pub fn parse(input: &str) -> Result<Rearrangement, String> {
+    let (stacks_repr, moves, moves_line) =
+        split_input(input).ok_or("couldn't find stacks or moves in the input")?;
+    let stacks = stacks_repr.parse::<Stacks>()?;
+    let moves = parse_moves(moves).map_err(|err| {
+        let line = err.line + moves_line;
+        err.at_line(line).to_string()
+    })?;
+    Ok(Rearrangement { stacks, moves })
+}
+
+/// Crates on top of the stacks when the crane moves one crate at a time.
+pub fn part_one(rearrangement: &Rearrangement) -> Result<String, String> {
+    solve_part_one(
+        rearrangement.stacks.clone(),
+        &rearrangement.moves,
+        &mut |_, _| {},
+    )
+}
+
+/// Crates on top of the stacks when the crane moves many crates at once.
+pub fn part_two(rearrangement: &Rearrangement) -> Result<String, String> {
+    solve_part_two(
+        rearrangement.stacks.clone(),
+        &rearrangement.moves,
+        &mut |_, _| {},
+    )
+}
 
-    let stacks = match stacks_repr.parse::<Stacks>() {
-        Ok(stacks) => stacks,
-        Err(_) => {
-            println!("WARNING couldn't parse stacks' data");
+/// Draws the stacks after every move.
+pub fn visualize(
+    input: &str,
+    is_part_one: bool,
+    frames: &mut dyn FrameSink,
+) -> Result<String, String> {
+    let rearrangement = parse(input)?;
+    let total = rearrangement.moves.len();
+    let mut on_move = |stacks: &Stacks, move_nr: usize| {
+        if move_nr != total && !frames.wants_frames() {
             return;
         }
+        let caption = match rearrangement.moves.get(move_nr.wrapping_sub(1)) {
+            Some(Move { count, from, to }) => {
+                format!("move {move_nr}/{total}: {count} from {from} to {to}")
+            }
+            None => "starting stacks".to_string(),
+        };
+        frames.show(stacks.frame(caption, rearrangement.moves.get(move_nr.wrapping_sub(1))));
     };
-
-    let result = if is_part_one {
-        solve_part_one(stacks, moves)
+    on_move(&rearrangement.stacks, 0);
+    let stacks = rearrangement.stacks.clone();
+    if is_part_one {
+        solve_part_one(stacks, &rearrangement.moves, &mut on_move)
     } else {
-        solve_part_two(stacks, moves)
-    };
-
-    println!("output: {result}");
+        solve_part_two(stacks, &rearrangement.moves, &mut on_move)
+    }
 }
 
-fn solve_part_one(mut stacks: Stacks, moves: &str) -> String {
-    for (count, from, to) in parse_moves(moves) {
+/// `on_move` gets the stacks after every move and the 1-based number of the move.
+fn solve_part_one(
+    mut stacks: Stacks,
+    moves: &[Move],
+    on_move: &mut dyn FnMut(&Stacks, usize),
+) -> Result<String, String> {
+    for (i, &Move { count, from, to }) in moves.iter().enumerate() {
+        stacks.check_id(to)?;
+        if from == to {
+            // moving crates one by one onto the same stack changes nothing
+            on_move(&stacks, i + 1);
+            continue;
+        }
         for _ in 0..count {
-            if let Some(item) = stacks.pop_from(from) {
-                stacks.push_on(to, item);
-            } else {
-                println!("WARNING trying to pop from empty stack");
-            }
+            let item = stacks.pop_from(from)?.ok_or_else(|| empty_stack(i, from))?;
+            stacks.push_on(to, item)?;
         }
+        on_move(&stacks, i + 1);
     }
 
-    stacks
-        .stacks_slice()
-        .iter()
-        .flat_map(|x| x.last())
-        .collect()
+    Ok(stacks.tops())
 }
 
-fn solve_part_two(mut stacks: Stacks, moves: &str) -> String {
+fn solve_part_two(
+    mut stacks: Stacks,
+    moves: &[Move],
+    on_move: &mut dyn FnMut(&Stacks, usize),
+) -> Result<String, String> {
     let mut counter_stack = Vec::new();
-    for (count, from, to) in parse_moves(moves) {
+    for (i, &Move { count, from, to }) in moves.iter().enumerate() {
+        stacks.check_id(to)?;
         for _ in 0..count {
-            if let Some(item) = stacks.pop_from(from) {
-                counter_stack.push(item);
-            } else {
-                println!("WARNING trying to pop from empty stack");
-            }
+            let item = stacks.pop_from(from)?.ok_or_else(|| empty_stack(i, from))?;
+            counter_stack.push(item);
         }
-        for _ in 0..count {
-            if let Some(item) = counter_stack.pop() {
-                stacks.push_on(to, item);
-            } else {
-                println!(
-                    "WARNING this should never happen; otherwise I have no idea what I'm doing"
-                );
-            }
+        while let Some(item) = counter_stack.pop() {
+            stacks.push_on(to, item)?;
         }
+        on_move(&stacks, i + 1);
     }
 
-    stacks
-        .stacks_slice()
-        .iter()
-        .flat_map(|x| x.last())
-        .collect()
+    Ok(stacks.tops())
 }
 
-fn split_input(input: &str) -> Option<(&str, &str)> {
-    let mut input_splitter = input.split("\n\n");
+/// `i` is the 0-based index of the move.
+fn empty_stack(i: usize, stack_id: usize) -> String {
+    format!("move {}: stack {stack_id} runs out of crates", i + 1)
+}
+
+/// The drawing, the moves and the number of lines in front of the moves.
+fn split_input(input: &str) -> Option<(&str, &str, usize)> {
+    let mut input_splitter = paragraphs(input);
 
     let input_stacks = input_splitter.next();
     let input_rearangements = input_splitter.next();
     match (input_stacks, input_rearangements) {
-        (Some(stacks), Some(rearangements)) => Some((stacks, rearangements)),
-        _ => None,
-    }
-}
-
-fn parse_num<'a>(num_iter: &mut impl Iterator<Item = &'a str>) -> Option<usize> {
-    if let Some(text) = num_iter.next() {
-        if let Ok(number) = text.parse::<usize>() {
-            return Some(number);
+        (Some(stacks), Some(rearangements)) => {
+            // the paragraphs are slices of the input
+            let start = rearangements.as_ptr() as usize - input.as_ptr() as usize;
+            Some((stacks, rearangements, input[..start].lines().count()))
         }
+        _ => None,
     }
-    None
 }
 
-fn parse_moves(moves_repr: &str) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
-    moves_repr.lines().flat_map(|line| {
-        let mut num_iter = line.split_whitespace().skip(1).step_by(2);
-        let a = parse_num(&mut num_iter);
-        let b = parse_num(&mut num_iter);
-        let c = parse_num(&mut num_iter);
-        if let (Some(a), Some(b), Some(c)) = (a, b, c) {
-            Some((a, b, c))
-        } else {
-            println!("WARNING not every move number could be parsed");
-            None
-        }
-    })
+/// Line numbers of the errors count from the first move.
+fn parse_moves(moves_repr: &str) -> Result<Vec<Move>, ParseError> {
+    let pattern = Pattern::new("move {} from {} to {}");
+    pattern
+        .parse_lines::<usize>(moves_repr)
+        .map(|fields| {
+            // the pattern has three fields
+            let fields = fields?;
+            Ok(Move {
+                count: fields[0],
+                from: fields[1],
+                to: fields[2],
+            })
+        })
+        .collect()
 }
 
-struct Stacks {
-    pub stacks: Vec<Vec<char>>,
+/// Stacks of crates, the last element of every stack is the crate on top.
+#[derive(Debug, Clone)]
+pub struct Stacks {
+    stacks: Vec<Vec<char>>,
 }
 
 impl Stacks {
@@ -120,43 +198,203 @@ impl Stacks {
         &self.stacks
     }
 
-    pub fn get_stack_mut(&mut self, stack_id: usize) -> &mut Vec<char> {
-        &mut self.stacks[stack_id - 1]
+    pub fn len(&self) -> usize {
+        self.stacks.len()
     }
 
-    pub fn push_on(&mut self, stack_id: usize, value: char) {
-        self.get_stack_mut(stack_id).push(value);
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
     }
 
-    pub fn pop_from(&mut self, stack_id: usize) -> Option<char> {
-        self.get_stack_mut(stack_id).pop()
+    /// Draws the stacks like the puzzle does, the destination of `last_move`
+    /// is kept in view.
+    pub fn frame(&self, caption: String, last_move: Option<&Move>) -> Frame {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let mut lines: Vec<String> = (0..height)
+            .rev()
+            .map(|level| {
+                let cells: Vec<String> = self
+                    .stacks
+                    .iter()
+                    .map(|stack| match stack.get(level) {
+                        Some(item) => format!("[{item}]"),
+                        None => "   ".to_string(),
+                    })
+                    .collect();
+                cells.join(" ").trim_end().to_string()
+            })
+            .collect();
+        let numbers: Vec<String> = (1..=self.stacks.len()).map(|x| format!(" {x} ")).collect();
+        lines.push(numbers.join(" "));
+
+        let frame = Frame::new(caption, lines);
+        match last_move.and_then(|x| Some((x.to, self.stacks.get(x.to.checked_sub(1)?)?))) {
+            Some((to, stack)) => frame.with_focus(height - stack.len(), (to - 1) * 4 + 1),
+            None => frame,
+        }
+    }
+
+    /// Crates on top of the stacks, empty stacks are skipped.
+    pub fn tops(&self) -> String {
+        self.stacks.iter().flat_map(|x| x.last()).collect()
+    }
+
+    /// Stacks are numbered from 1 like in the puzzle drawing.
+    pub fn get_stack_mut(&mut self, stack_id: usize) -> Result<&mut Vec<char>, String> {
+        let idx = stack_id.checked_sub(1);
+        match idx.and_then(|idx| self.stacks.get_mut(idx)) {
+            Some(stack) => Ok(stack),
+            None => Err(format!("there is no stack number {stack_id}")),
+        }
+    }
+
+    pub fn check_id(&mut self, stack_id: usize) -> Result<(), String> {
+        self.get_stack_mut(stack_id).map(|_| ())
+    }
+
+    pub fn push_on(&mut self, stack_id: usize, value: char) -> Result<(), String> {
+        self.get_stack_mut(stack_id)?.push(value);
+        Ok(())
+    }
+
+    pub fn pop_from(&mut self, stack_id: usize) -> Result<Option<char>, String> {
+        Ok(self.get_stack_mut(stack_id)?.pop())
     }
 }
 
 impl std::str::FromStr for Stacks {
-    type Err = ();
+    type Err = String;
 
+    /// Parses the drawing, the labels of the stacks are on its last line.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut line_iter = s.lines().rev();
-
-        let stack_count = if let Some(number_line) = line_iter.next() {
-            (number_line.len() + 1) / 4
-        } else {
-            return Err(());
+        let lines: Vec<&str> = s.lines().collect();
+        let Some((number_line, drawing)) = lines.split_last() else {
+            return Err("couldn't find the drawing of the stacks".into());
         };
+        // the labels, the line may have lost its trailing blank
+        let stack_count = number_line.split_whitespace().count();
 
         let mut stacks = Stacks::new(stack_count);
-        for line in line_iter {
+        for (line_nr, line) in drawing.iter().enumerate().rev() {
             for (i, symbol) in line.chars().skip(1).step_by(4).enumerate() {
-                if i >= stack_count {
-                    println!("WARNING more stacks than expected while parsing stacks");
-                    break;
-                } else if symbol.is_alphabetic() {
-                    stacks.push_on(i + 1, symbol);
+                if !symbol.is_alphabetic() {
+                    continue;
                 }
+                stacks.push_on(i + 1, symbol).map_err(|_| {
+                    ParseError::new(line_nr + 1, format!("crate '{symbol}' has no stack"))
+                        .with_column(i * 4 + 2)
+                        .to_string()
+                })?;
             }
         }
 
         Ok(stacks)
     }
 }
+
+impl Parsed for Rearrangement {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        }
+    }
+
+    fn show(&self) -> String {
+        let mut text = self.stacks.frame(String::new(), None).lines.join("\n");
+        text.push_str(&format!("\n\n{} moves", self.moves.len()));
+        text
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        match (command, args) {
+            ("stack", [nr]) => Some(
+                nr.parse::<usize>()
+                    .ok()
+                    .and_then(|nr| self.stacks.stacks.get(nr.checked_sub(1)?))
+                    .map(|stack| stack.iter().collect::<String>())
+                    .ok_or_else(|| format!("there is no stack number {nr}")),
+            ),
+            ("move", [nr]) => Some(
+                nr.parse::<usize>()
+                    .ok()
+                    .and_then(|nr| self.moves.get(nr.checked_sub(1)?))
+                    .map(|x| format!("move {} from {} to {}", x.count, x.from, x.to))
+                    .ok_or_else(|| format!("there is no move number {nr}")),
+            ),
+            _ => None,
+        }
+    }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &[
+            "stack <N>: crates of the N-th starting stack, bottom first",
+            "move <N>: the N-th move",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visualize() {
+        let mut frames = Vec::new();
+        let input = include_str!("../../examples/day05/1.txt");
+        let answer = visualize(input, false, &mut |frame| frames.push(frame));
+        assert_eq!(answer, Ok("MCD".to_string()));
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            frames[0].lines,
+            ["    [D]", "[N] [C]", "[Z] [M] [P]", " 1   2   3 "]
+        );
+        let last = frames.last().unwrap();
+        assert_eq!(last.caption, "move 4/4: 1 from 1 to 2");
+        assert_eq!(
+            last.lines,
+            [
+                "        [D]",
+                "        [N]",
+                "        [Z]",
+                "[M] [C] [P]",
+                " 1   2   3 "
+            ]
+        );
+        assert_eq!(last.focus, Some((3, 5)));
+    }
+
+    #[test]
+    fn test_drawing_without_trailing_blanks() {
+        let input = include_str!("../../examples/day05/1.txt");
+        let trimmed: Vec<&str> = input.lines().map(str::trim_end).collect();
+        let rearrangement = parse(&trimmed.join("\n")).unwrap();
+        assert_eq!(rearrangement.stacks().len(), 3);
+        assert_eq!(part_one(&rearrangement), Ok("CMZ".to_string()));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let input = include_str!("../../examples/day05/1.txt");
+        let bad_move = input.replace("move 2 from 2 to 1", "move 2 form 2 to 1");
+        assert_eq!(
+            parse(&bad_move).err(),
+            Some("line 8, column 6: line doesn't match the pattern 'move {} from {} to {}'".into())
+        );
+        let too_many = input.replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        assert_eq!(
+            answer(too_many, true),
+            Err("move 2: stack 1 runs out of crates".into())
+        );
+        let extra_crate = input.replace("    [D]    ", "    [D]     [E]");
+        assert_eq!(
+            parse(&extra_crate).err(),
+            Some("line 1, column 14: crate 'E' has no stack".into())
+        );
+    }
+}
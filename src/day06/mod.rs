@@ -1,14 +1,67 @@
+use crate::parsing::{read_chars, ReadError};
+use crate::registry::Parsed;
 use queues::*;
+use std::io::BufRead;
 
 pub fn solve(input: String, is_part_one: bool) {
-    // benchmark();
+    solve_stream(input.as_bytes(), is_part_one);
+}
+
+pub fn solve_stream(input: impl BufRead, is_part_one: bool) {
+    match answer_stream(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    answer_stream(input.as_bytes(), is_part_one)
+}
+
+pub fn answer_stream(input: impl BufRead, is_part_one: bool) -> Result<String, String> {
+    let mut read_error = ReadError::default();
+    let symbols = read_chars(input, &mut read_error);
     let result = if is_part_one {
-        solve_window_size(input, 4)
+        solve_window_size(symbols, 4)
     } else {
-        solve_window_size(input, 14)
+        solve_window_size(symbols, 14)
     };
+    read_error.check()?;
+    Ok(result.to_string())
+}
+
+/// The letters of the datastream, everything else is dropped while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    symbols: String,
+}
 
-    println!("output: {result}");
+impl Signal {
+    pub fn as_str(&self) -> &str {
+        &self.symbols
+    }
+}
+
+pub fn parse(input: &str) -> Result<Signal, String> {
+    let symbols = input.chars().filter(|x| x.is_alphabetic()).collect();
+    Ok(Signal { symbols })
+}
+
+/// Number of characters read until the first start-of-packet marker,
+/// `None` if the signal has no marker.
+pub fn part_one(signal: &Signal) -> Option<u64> {
+    marker_end(signal, 4)
+}
+
+/// Number of characters read until the first start-of-message marker,
+/// `None` if the signal has no marker.
+pub fn part_two(signal: &Signal) -> Option<u64> {
+    marker_end(signal, 14)
+}
+
+fn marker_end(signal: &Signal, window_size: usize) -> Option<u64> {
+    u64::try_from(solve_window_size(signal.as_str().chars(), window_size)).ok()
 }
 
 struct Counter {
@@ -43,106 +96,127 @@ impl Counter {
     }
 }
 
-fn solve_window_size(input: String, window_size: usize) -> i32 {
+fn solve_window_size(symbols: impl Iterator<Item = char>, window_size: usize) -> i64 {
     let mut queue = Queue::<char>::new();
     let mut counter = Counter::new();
+    let mut symbols = symbols.filter(|x| x.is_alphabetic());
 
-    for symbol in input
-        .chars()
-        .filter(|x| x.is_alphabetic())
-        .take(window_size)
-    {
-        if let Err(e) = queue.add(symbol) {
-            println!("WARNING error while adding on queue\n{e}");
-        }
+    // the queue is unbounded, adding to it never fails
+    for symbol in symbols.by_ref().take(window_size) {
+        let _ = queue.add(symbol);
         counter.add(symbol);
     }
 
-    for (i, symbol) in input
-        .chars()
-        .filter(|x| x.is_alphabetic())
-        .skip(window_size)
-        .enumerate()
-    {
+    let mut consumed = window_size as i64;
+    for symbol in symbols {
         if counter.n_distinct_elements() == window_size {
-            return i as i32 + window_size as i32;
+            return consumed;
         }
 
-        match queue.remove() {
-            Ok(elem) => {
-                counter.sub(elem);
-            }
-            Err(err) => {
-                println!("WARNING error while removing from queue\n{err}");
-            }
+        // the window is full here, so the queue isn't empty
+        if let Ok(elem) = queue.remove() {
+            counter.sub(elem);
         }
         counter.add(symbol);
-        if let Err(err) = queue.add(symbol) {
-            println!("WARNING error while adding on queue\n{err}");
-        };
+        let _ = queue.add(symbol);
+        consumed += 1;
+    }
+
+    // the marker may end with the very last symbol
+    if queue.size() == window_size && counter.n_distinct_elements() == window_size {
+        consumed
+    } else {
+        -1
     }
-    -1
 }
 
 #[allow(dead_code)]
-fn alternative_solve_window_size(input: String, window_size: usize) -> i32 {
-    let input_len = input.len();
-    for i in 0..(input_len - window_size) {
-        let window = &input[i..i + window_size];
-        let set: std::collections::HashSet<_> = window.chars().collect();
+fn alternative_solve_window_size(input: String, window_size: usize) -> i64 {
+    let symbols: Vec<char> = input.chars().collect();
+    let last_start = match symbols.len().checked_sub(window_size) {
+        Some(last_start) => last_start,
+        None => return -1,
+    };
+    for i in 0..=last_start {
+        let window = &symbols[i..i + window_size];
+        let set: std::collections::HashSet<_> = window.iter().collect();
         if set.len() == window_size {
-            return (i + window_size) as i32;
+            return (i + window_size) as i64;
         }
     }
     -1
 }
 
-#[allow(dead_code)]
-const SIZE: usize = 10_000_000;
-#[allow(dead_code)]
-fn benchmark() {
-    let input: String = ['a'; SIZE].iter().collect();
-    let window_size = 40;
+impl Parsed for Signal {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
 
-    let start = std::time::SystemTime::now();
-    solve_window_size(input, window_size);
-    let duration = start.elapsed().expect("sth went wrong");
-    println!("counter: {}", duration.as_millis());
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        Ok(result.map_or(-1, |x| x as i64).to_string())
+    }
 
-    let input: String = ['a'; SIZE].iter().collect();
-    let start = std::time::SystemTime::now();
-    alternative_solve_window_size(input, window_size);
-    let duration = start.elapsed().expect("sth went wrong");
-    println!("window : {}", duration.as_millis());
+    fn show(&self) -> String {
+        format!(
+            "{} ({} characters)",
+            self.symbols,
+            self.symbols.chars().count()
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::check_property;
 
     #[test]
-    fn test_example_part_one() {
-        let t1 = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let t2 = "nppdvjthqldpwncqszvftbrmjlhg";
-        let t3 = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let t4 = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvj";
+    fn test_example_alternative() {
+        let examples = [
+            include_str!("../../examples/day06/1.txt"),
+            include_str!("../../examples/day06/2.txt"),
+            include_str!("../../examples/day06/3.txt"),
+            include_str!("../../examples/day06/4.txt"),
+        ];
+
+        for (example, expected) in examples.iter().zip([5, 6, 10, 11]) {
+            assert_eq!(
+                alternative_solve_window_size(example.trim().into(), 4),
+                expected
+            );
+        }
+    }
 
-        assert_eq!(solve_window_size(t1.into(), 4), 5);
-        assert_eq!(solve_window_size(t2.into(), 4), 6);
-        assert_eq!(solve_window_size(t3.into(), 4), 10);
-        assert_eq!(solve_window_size(t4.into(), 4), 11);
+    #[test]
+    fn test_marker_at_the_end() {
+        assert_eq!(solve_window_size("aaab".chars(), 2), 4);
+        assert_eq!(alternative_solve_window_size("aaab".into(), 2), 4);
+        assert_eq!(solve_window_size("abcd".chars(), 4), 4);
+        assert_eq!(alternative_solve_window_size("abc".into(), 4), -1);
     }
 
     #[test]
-    fn test_example_alternative() {
-        let t1 = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let t2 = "nppdvjthqldpwncqszvftbrmjlhg";
-        let t3 = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let t4 = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvj";
-
-        assert_eq!(alternative_solve_window_size(t1.into(), 4), 5);
-        assert_eq!(alternative_solve_window_size(t2.into(), 4), 6);
-        assert_eq!(alternative_solve_window_size(t3.into(), 4), 10);
-        assert_eq!(alternative_solve_window_size(t4.into(), 4), 11);
+    fn test_property_implementations_agree() {
+        check_property("both window algorithms find the same marker", |rng| {
+            let alphabet = &b"abcdefghijklmnop"[..rng.range(1, 16) as usize];
+            let window_size = rng.range(1, 14) as usize;
+            let signal: String = (0..rng.range(0, 60))
+                .map(|_| *rng.choose(alphabet) as char)
+                .collect();
+
+            let queue = solve_window_size(signal.chars(), window_size);
+            let window = alternative_solve_window_size(signal.clone(), window_size);
+            if queue != window {
+                return Err(format!(
+                    "'{signal}' with window {window_size}: queue {queue}, window {window}"
+                ));
+            }
+            Ok(())
+        });
     }
 }
@@ -1,82 +1,87 @@
+use crate::overflow::{checked_sum, Overflow};
+use crate::parsing::ParseError;
+use crate::registry::Parsed;
 use std::collections::HashMap;
 
 pub fn solve(input: String, is_part_one: bool) {
-    let fs = parse_input(&input);
-    let result = if is_part_one {
-        solve_part_one(fs)
-    } else {
-        solve_part_two(fs)
-    };
+    match answer(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
 
-    println!("output: {result}");
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    parse(&input)?.part(is_part_one)
 }
 
-fn solve_part_one(fs: FileSystem) -> i32 {
-    let mut dir_sizes = Vec::<u32>::new();
-    fs.dfs_dir_sizes(fs.root_id, &mut dir_sizes);
-    dir_sizes.iter().filter(|&&x| x <= 100_000).sum::<u32>() as i32
+/// Sum of the sizes of the directories with at most 100000 bytes.
+pub fn part_one(fs: &FileSystem) -> Result<u64, Overflow> {
+    let mut dir_sizes = Vec::<u64>::new();
+    fs.dfs_dir_sizes(fs.root_id, &mut dir_sizes)?;
+    checked_sum(dir_sizes.into_iter().filter(|&x| x <= 100_000))
 }
 
-fn solve_part_two(fs: FileSystem) -> i32 {
-    let mut dir_sizes = Vec::<u32>::new();
-    fs.dfs_dir_sizes(fs.root_id, &mut dir_sizes);
-    let root_size = match dir_sizes.last() {
-        Some(&value) => value,
-        None => {
-            println!("WARNING empty dir_sizes");
-            return -1;
-        }
+/// Size of the smallest directory that frees enough space for the update,
+/// 0 if there is enough space already and -1 if no directory is big enough.
+pub fn part_two(fs: &FileSystem) -> Result<i64, Overflow> {
+    let mut dir_sizes = Vec::<u64>::new();
+    fs.dfs_dir_sizes(fs.root_id, &mut dir_sizes)?;
+    let Some(&root_size) = dir_sizes.last() else {
+        return Ok(-1);
     };
-
-    println!("root size: {root_size}");
-
-    let avaiable_memory = DISK_SPACE - root_size;
+    let avaiable_memory = DISK_SPACE - i64::try_from(root_size).map_err(|_| Overflow)?;
     let memory_to_free = NEEDED_MEMORY - avaiable_memory;
 
     if memory_to_free <= 0 {
-        return 0;
+        return Ok(0);
     }
 
-    println!("memory to free: {memory_to_free}");
-
     dir_sizes.sort();
 
-    match dir_sizes.iter().find(|&&x| x >= memory_to_free) {
-        Some(&x) => x as i32,
-        None => {
-            println!("couldn't find a directory big enought");
-            return -1;
-        }
+    match dir_sizes.iter().find(|&&x| x >= memory_to_free as u64) {
+        Some(&x) => i64::try_from(x).map_err(|_| Overflow),
+        None => Ok(-1),
     }
 }
 
-const DISK_SPACE: u32 = 70_000_000;
-const NEEDED_MEMORY: u32 = 30_000_000;
+const DISK_SPACE: i64 = 70_000_000;
+const NEEDED_MEMORY: i64 = 30_000_000;
 
-fn parse_input(input: &str) -> FileSystem {
+/// Replays the `cd` and `ls` commands of a terminal transcript, the first
+/// line that doesn't fit is an error.
+pub fn parse(input: &str) -> Result<FileSystem, String> {
     let mut fs = FileSystem::new();
-    for line in input.split('$') {
-        if line.len() >= 3 {
-            match &line[1..3] {
-                "ls" => {
-                    parse_ls(line, &mut fs);
-                }
-                "cd" => {
-                    parse_cd(line, &mut fs);
-                }
-                _ => {
-                    println!("WARNING command not recognized");
+    let mut in_ls = false;
+    for (i, line) in input.lines().enumerate() {
+        let result = if let Some(command) = line.strip_prefix('$') {
+            in_ls = false;
+            match command.split_whitespace().next() {
+                Some("ls") => {
+                    in_ls = true;
+                    Ok(())
                 }
+                Some("cd") => parse_cd(command, &mut fs),
+                _ => Err(format!("command not recognized in '{line}'")),
             }
-        }
+        } else if line.trim().is_empty() {
+            Ok(())
+        } else if in_ls {
+            parse_ls(line, &mut fs)
+        } else {
+            Err(format!("'{line}' isn't the output of '$ ls'"))
+        };
+        result.map_err(|err| ParseError::new(i + 1, err).to_string())?;
     }
 
-    fs
+    Ok(fs)
 }
 
-struct Directory {
+/// Subdirectories are kept as ids of the owning [`FileSystem`].
+#[derive(Debug, Clone, Default)]
+pub struct Directory {
     pub subdirs: HashMap<String, usize>,
-    pub files: HashMap<String, u32>,
+    pub files: HashMap<String, u64>,
 }
 
 impl Directory {
@@ -92,13 +97,21 @@ impl Directory {
     }
 }
 
-struct FileSystem {
+/// Directory tree built from a transcript, directories are addressed by ids.
+#[derive(Debug, Clone)]
+pub struct FileSystem {
     directories: Vec<Directory>,
     root_id: usize,
     cwd_id: usize,
     absolute_path: Vec<usize>,
 }
 
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FileSystem {
     pub fn new() -> Self {
         FileSystem {
@@ -150,7 +163,7 @@ impl FileSystem {
     }
 
     pub fn mkdir(&mut self, dir_name: &str) -> Result<(), &str> {
-        if let Some(_) = self.cwd().get_subdir_id(dir_name) {
+        if self.cwd().get_subdir_id(dir_name).is_some() {
             return Err("directory already exists");
         }
 
@@ -161,8 +174,8 @@ impl FileSystem {
         Ok(())
     }
 
-    pub fn mkfile(&mut self, file_name: &str, file_size: u32) -> Result<(), &str> {
-        if let Some(_) = self.cwd().files.get(file_name) {
+    pub fn mkfile(&mut self, file_name: &str, file_size: u64) -> Result<(), &str> {
+        if self.cwd().files.contains_key(file_name) {
             return Err("file already exists");
         }
         self.cwd_mut().files.insert(file_name.into(), file_size);
@@ -173,56 +186,155 @@ impl FileSystem {
         &self.directories[dir_id]
     }
 
-    pub fn dfs_dir_sizes(&self, dir_id: usize, dir_sizes: &mut Vec<u32>) -> u32 {
-        let dir = self.dir(dir_id);
-        let mut size: u32 = dir.files.values().sum();
-        for subdir_id in dir.subdirs.values() {
-            size += self.dfs_dir_sizes(*subdir_id, dir_sizes)
-        }
-        dir_sizes.push(size);
-        size
+    pub fn root_id(&self) -> usize {
+        self.root_id
+    }
+
+    pub fn dir_count(&self) -> usize {
+        self.directories.len()
     }
-}
 
-fn parse_ls(text: &str, fs: &mut FileSystem) {
-    for line in text.lines().skip(1) {
-        let x: Vec<&str> = line.split(" ").take(2).collect();
-        if x.len() < 2 {
-            println!("WARNING couldn't parse ls line");
-            return;
+    /// Finds a directory by an absolute path like `/a/e`.
+    pub fn find(&self, path: &str) -> Option<usize> {
+        let mut dir_id = self.root_id;
+        for name in path.split('/').filter(|x| !x.is_empty()) {
+            dir_id = *self.dir(dir_id).get_subdir_id(name)?;
         }
+        Some(dir_id)
+    }
 
-        match x[0] {
-            "dir" => {
-                if let Err(err) = fs.mkdir(x[1]) {
-                    println!("WARNING {err}");
-                }
+    /// Total size of the files in `dir_id` and all the directories below it.
+    pub fn dir_size(&self, dir_id: usize) -> Result<u64, Overflow> {
+        self.dfs_dir_sizes(dir_id, &mut Vec::new())
+    }
+
+    /// Pushes the total size of every directory below `dir_id` in post-order,
+    /// so the size of `dir_id` itself comes last.
+    pub fn dfs_dir_sizes(&self, dir_id: usize, dir_sizes: &mut Vec<u64>) -> Result<u64, Overflow> {
+        // explicit stack, a deeply nested transcript would overflow the call stack
+        let mut sizes = vec![0; self.directories.len()];
+        let mut stack = vec![(dir_id, false)];
+        while let Some((id, children_done)) = stack.pop() {
+            let dir = self.dir(id);
+            if !children_done {
+                stack.push((id, true));
+                stack.extend(dir.subdirs.values().map(|&subdir_id| (subdir_id, false)));
+                continue;
             }
-            file_size_str => match file_size_str.parse() {
-                Ok(file_size) => {
-                    if let Err(err) = fs.mkfile(x[1], file_size) {
-                        println!("WARNING {err}");
-                    }
-                }
-                Err(err) => {
-                    println!("WARNING {err}");
-                }
-            },
+            let mut size = checked_sum(dir.files.values().copied())?;
+            for subdir_id in dir.subdirs.values() {
+                size = checked_sum([size, sizes[*subdir_id]])?;
+            }
+            sizes[id] = size;
+            dir_sizes.push(size);
         }
+        Ok(sizes[dir_id])
+    }
+}
+
+/// A line listed by `ls`, either `dir <name>` or `<size> <name>`.
+fn parse_ls(line: &str, fs: &mut FileSystem) -> Result<(), String> {
+    let Some((kind, name)) = line.split_once(' ') else {
+        return Err(format!("couldn't parse ls line '{line}'"));
+    };
+    if kind == "dir" {
+        return fs.mkdir(name).map_err(String::from);
     }
+    let file_size = kind
+        .parse()
+        .map_err(|err| format!("{err} in the size of '{line}'"))?;
+    fs.mkfile(name, file_size).map_err(String::from)
 }
 
-fn parse_cd(text: &str, fs: &mut FileSystem) {
-    let cd_argument = &text[4..text.len() - 1]; // strip new line
+fn parse_cd(command: &str, fs: &mut FileSystem) -> Result<(), String> {
+    let cd_argument = command.trim().strip_prefix("cd").unwrap_or_default().trim();
     match cd_argument {
+        "" => return Err(format!("missing directory name in '${command}'")),
         "/" => fs.cd_root(),
         ".." => fs.cd_parent(),
-        dir_name => {
-            if let Err(err) = fs.cd(dir_name) {
-                println!("WARNING {err}");
+        dir_name => fs
+            .cd(dir_name)
+            .map_err(|err| format!("{err}: '{dir_name}'"))?,
+    };
+    Ok(())
+}
+
+impl Parsed for FileSystem {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self).map(|x| x.to_string())
+        } else {
+            part_two(self).map(|x| x.to_string())
+        };
+        result.map_err(|err| err.to_string())
+    }
+
+    /// The tree the way the puzzle draws it.
+    fn show(&self) -> String {
+        let mut text = String::new();
+        let mut stack = vec![(self.root_id, "/".to_string(), 0)];
+        while let Some((dir_id, name, depth)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+            text.push_str(&format!("{indent}- {name} (dir)\n"));
+            let dir = self.dir(dir_id);
+            let mut files: Vec<_> = dir.files.iter().collect();
+            files.sort();
+            for (file_name, size) in files {
+                text.push_str(&format!("{indent}  - {file_name} (file, size={size})\n"));
+            }
+            let mut subdirs: Vec<_> = dir.subdirs.iter().collect();
+            subdirs.sort();
+            for (subdir_name, &subdir_id) in subdirs.into_iter().rev() {
+                stack.push((subdir_id, subdir_name.clone(), depth + 1));
             }
         }
-    };
+        text.trim_end().to_string()
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        let path = match args {
+            [] => "/",
+            [path] => path,
+            _ => return None,
+        };
+        let dir_id = self
+            .find(path)
+            .ok_or(format!("there is no directory '{path}'"));
+        match command {
+            "size" => Some(
+                dir_id
+                    .and_then(|x| self.dir_size(x).map_err(|err| err.to_string()))
+                    .map(|x| x.to_string()),
+            ),
+            "ls" => Some(dir_id.map(|x| {
+                let dir = self.dir(x);
+                let mut entries: Vec<String> = dir
+                    .subdirs
+                    .keys()
+                    .map(|name| format!("dir {name}"))
+                    .chain(
+                        dir.files
+                            .iter()
+                            .map(|(name, size)| format!("{size} {name}")),
+                    )
+                    .collect();
+                entries.sort();
+                entries.join("\n")
+            })),
+            _ => None,
+        }
+    }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &[
+            "size <PATH>: total size of the directory, like 'size /a/e'",
+            "ls <PATH>: content of the directory",
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -230,37 +342,64 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example_part_one() {
-        let fs = parse_input(INPUT);
-        assert_eq!(95437, solve_part_one(fs));
+    fn test_enough_free_space() {
+        let fs = parse("$ cd /\n$ ls\n100 a.txt\n").unwrap();
+        assert_eq!(Ok(0), part_two(&fs));
     }
+
+    #[test]
+    fn test_malformed_cd() {
+        assert!(parse("$ cd").is_err());
+        assert!(parse("$ cd \n$ ls\n").is_err());
+        assert!(parse("$ cdé\n$ é").is_err());
+    }
+
     #[test]
-    fn test_example_part_two() {
-        let fs = parse_input(INPUT);
-        assert_eq!(24933642, solve_part_two(fs));
-    }
-
-    const INPUT: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
+    fn test_malformed_listing() {
+        let input = "$ cd /\n$ ls\ndir a\n12x b.txt\n";
+        assert_eq!(
+            parse(input).err(),
+            Some("line 4: invalid digit found in string in the size of '12x b.txt'".into())
+        );
+        assert_eq!(
+            parse("$ cd /\n$ cd a\n").err(),
+            Some("line 2: directory does not exist: 'a'".into())
+        );
+        assert_eq!(
+            parse("$ cd /\n100 a.txt\n").err(),
+            Some("line 2: '100 a.txt' isn't the output of '$ ls'".into())
+        );
+        assert_eq!(
+            parse("$ cd /\n$ ls\ndir a\ndir a\n").err(),
+            Some("line 4: directory already exists".into())
+        );
+    }
+
+    #[test]
+    fn test_deeply_nested_directories() {
+        let mut input = String::from("$ cd /\n");
+        for _ in 0..100_000 {
+            input.push_str("$ ls\ndir a\n1 f\n$ cd a\n");
+        }
+        let fs = parse(&input).unwrap();
+        // every directory holds one byte per level below it, all of them are small enough
+        assert_eq!(Ok((1..=100_000).sum()), part_one(&fs));
+    }
+
+    #[test]
+    fn test_find_directory() {
+        let fs =
+            parse("$ cd /\n$ ls\ndir a\n5 b\n$ cd a\n$ ls\ndir e\n$ cd e\n$ ls\n7 i\n").unwrap();
+        let e = fs.find("/a/e").unwrap();
+        assert_eq!(Some(e), fs.find("a/e/"));
+        assert_eq!(Ok(7), fs.dir_size(e));
+        assert_eq!(Ok(12), fs.dir_size(fs.root_id()));
+        assert_eq!(None, fs.find("/e"));
+    }
+
+    #[test]
+    fn test_overflowing_sizes() {
+        let fs = parse("$ cd /\n$ ls\n18446744073709551615 a\n1 b\n").unwrap();
+        assert_eq!(Err(Overflow), part_one(&fs));
+    }
 }
@@ -1,62 +1,95 @@
-use std::ops::Index;
+use crate::overflow::Overflow;
+use crate::parsing::ParseError;
+use crate::registry::Parsed;
 
 pub fn solve(input: String, is_part_one: bool) {
-    let result = if is_part_one {
-        solve_part_one(parse_input(input))
-    } else {
-        solve_part_two(parse_input(input))
-    };
+    match answer(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
 
-    println!("output: {result}");
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    parse(&input)?.part(is_part_one)
 }
 
-fn solve_part_one(forest: Forest) -> i32 {
-    let mut count_visible = 0;
+/// Number of trees visible from outside the grid.
+pub fn part_one(forest: &Forest) -> Result<u64, Overflow> {
+    let mut count_visible: u64 = 0;
     for x in 1..forest.width() - 1 {
         for y in 1..forest.height() - 1 {
-            if forest.is_tree_visible((x, y)) {
+            if forest.visible((x, y)) {
                 count_visible += 1;
             }
         }
     }
-    count_visible + forest.width() as i32 * 2 + forest.height() as i32 * 2 - 4
+    // every tree on the edge is visible, the inner ones were counted above
+    let (width, height) = (forest.width() as u64, forest.height() as u64);
+    let count_edge = width * height - width.saturating_sub(2) * height.saturating_sub(2);
+    Ok(count_visible + count_edge)
 }
 
-fn solve_part_two(forest: Forest) -> i32 {
+/// Highest scenic score of any tree.
+pub fn part_two(forest: &Forest) -> Result<u64, Overflow> {
     let mut max_scenic_score = 0;
     for x in 0..forest.width() {
         for y in 0..forest.height() {
-            let score = forest.calc_tree_scenic_score((x, y));
+            let score = forest.scenic_score((x, y))?;
             if score > max_scenic_score {
                 max_scenic_score = score;
             }
         }
     }
-    max_scenic_score
+    Ok(max_scenic_score)
 }
 
-fn parse_input(input: String) -> Forest {
+/// Parses a rectangular grid of digits, one row per line.
+pub fn parse(input: &str) -> Result<Forest, String> {
     let mut memory = Vec::<u8>::new();
+    let mut width = None;
     let mut height = 0;
-    for line in input.lines() {
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
         height += 1;
-        for digit in line.chars() {
-            match digit.to_digit(10) {
-                Some(tree_height) => memory.push(tree_height as u8),
-                None => println!("WARNING couldn't convert '{digit}' to a tree height"),
-            }
+        let row_start = memory.len();
+        for (column, digit) in line.chars().enumerate() {
+            let Some(tree_height) = digit.to_digit(10) else {
+                let message = format!("couldn't convert '{digit}' to a tree height");
+                return Err(ParseError::new(i + 1, message)
+                    .with_column(column + 1)
+                    .to_string());
+            };
+            memory.push(tree_height as u8);
+        }
+        let row_width = memory.len() - row_start;
+        if *width.get_or_insert(row_width) != row_width {
+            return Err(format!(
+                "row {height} has a different width than the first one"
+            ));
         }
     }
-    let width = memory.len() / height;
-    Forest {
-        shape: (width as u32, height as u32),
-        memory,
+    let width = match width {
+        Some(width) if width > 0 => width,
+        _ => return Err("there are no trees in the forest".into()),
+    };
+    let shape = (u32::try_from(width), u32::try_from(height));
+    match shape {
+        (Ok(width), Ok(height)) => Ok(Forest {
+            shape: (width, height),
+            memory,
+        }),
+        _ => Err("the forest is too big".into()),
     }
 }
 
-struct Forest {
-    pub shape: (u32, u32),
-    pub memory: Vec<u8>,
+/// Heights of the trees, addressed by `(x, y)` with `(0, 0)` in the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forest {
+    shape: (u32, u32),
+    memory: Vec<u8>,
 }
 
 impl Forest {
@@ -68,44 +101,65 @@ impl Forest {
         self.shape.1
     }
 
-    pub fn calc_idx(&self, point: (u32, u32)) -> usize {
+    /// Height of the tree at `point` or `None` outside of the forest.
+    pub fn get(&self, point: (u32, u32)) -> Option<u8> {
+        self.contains(point).then(|| self.at(point))
+    }
+
+    /// Whether the tree at `point` can be seen from outside the grid,
+    /// `None` outside of the forest.
+    pub fn is_tree_visible(&self, point: (u32, u32)) -> Option<bool> {
+        self.contains(point).then(|| self.visible(point))
+    }
+
+    /// Product of the viewing distances in the four directions,
+    /// `None` outside of the forest.
+    pub fn calc_tree_scenic_score(&self, point: (u32, u32)) -> Option<Result<u64, Overflow>> {
+        self.contains(point).then(|| self.scenic_score(point))
+    }
+
+    fn contains(&self, point: (u32, u32)) -> bool {
+        let (x, y) = point;
+        x < self.width() && y < self.height()
+    }
+
+    /// Height of a tree known to be in the forest.
+    fn at(&self, point: (u32, u32)) -> u8 {
+        self.memory[self.calc_idx(point)]
+    }
+
+    fn calc_idx(&self, point: (u32, u32)) -> usize {
         let (x, y) = point;
         x as usize + y as usize * self.width() as usize
     }
 
-    pub fn is_tree_visible(&self, point: (u32, u32)) -> bool {
+    fn visible(&self, point: (u32, u32)) -> bool {
         let (x, y) = point;
-        let height = self[point];
-
-        if (0..x).all(|xx| self[(xx, y)] < height) {
-            true // left visibility
-        } else if (x + 1..self.width()).all(|xx| self[(xx, y)] < height) {
-            true // right visibility
-        } else if (0..y).all(|yy| self[(x, yy)] < height) {
-            true // top visibility
-        } else if (y + 1..self.height()).all(|yy| self[(x, yy)] < height) {
-            true // bottom visibility
-        } else {
-            false
-        }
+        let height = self.at(point);
+
+        // left, right, top and bottom
+        (0..x).all(|xx| self.at((xx, y)) < height)
+            || (x + 1..self.width()).all(|xx| self.at((xx, y)) < height)
+            || (0..y).all(|yy| self.at((x, yy)) < height)
+            || (y + 1..self.height()).all(|yy| self.at((x, yy)) < height)
     }
 
-    pub fn calc_tree_scenic_score(&self, point: (u32, u32)) -> i32 {
+    fn scenic_score(&self, point: (u32, u32)) -> Result<u64, Overflow> {
         let (x, y) = point;
-        let height = self[point];
+        let height = self.at(point);
         let mut left_count = (0..x)
             .rev()
-            .take_while(|&xx| self[(xx, y)] < height)
+            .take_while(|&xx| self.at((xx, y)) < height)
             .count();
         let mut top_count = (0..y)
             .rev()
-            .take_while(|&yy| self[(x, yy)] < height)
+            .take_while(|&yy| self.at((x, yy)) < height)
             .count();
         let mut right_count = (x + 1..self.width())
-            .take_while(|&xx| self[(xx, y)] < height)
+            .take_while(|&xx| self.at((xx, y)) < height)
             .count();
         let mut bottom_count = (y + 1..self.height())
-            .take_while(|&yy| self[(x, yy)] < height)
+            .take_while(|&yy| self.at((x, yy)) < height)
             .count();
         left_count += if left_count == x as usize { 0 } else { 1 };
         right_count += if right_count == (self.width() - x - 1) as usize {
@@ -119,34 +173,164 @@ impl Forest {
         } else {
             1
         };
-        (left_count * top_count * right_count * bottom_count) as i32
+        [left_count, top_count, right_count, bottom_count]
+            .into_iter()
+            .try_fold(1u64, |score, count| score.checked_mul(count as u64))
+            .ok_or(Overflow)
     }
 }
 
-impl Index<(u32, u32)> for Forest {
-    type Output = u8;
+impl Parsed for Forest {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        result.map(|x| x.to_string()).map_err(|err| err.to_string())
+    }
+
+    fn show(&self) -> String {
+        let rows: Vec<String> = (0..self.height())
+            .map(|y| {
+                (0..self.width())
+                    .map(|x| self.at((x, y)).to_string())
+                    .collect()
+            })
+            .collect();
+        rows.join("\n")
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        let ("tree", [x, y]) = (command, args) else {
+            return None;
+        };
+        let point = match (x.parse::<u32>(), y.parse::<u32>()) {
+            (Ok(x), Ok(y)) if self.contains((x, y)) => (x, y),
+            _ => return Some(Err(format!("there is no tree at ({x}, {y})"))),
+        };
+        Some(
+            self.scenic_score(point)
+                .map_err(|err| err.to_string())
+                .map(|score| {
+                    format!(
+                        "height {}, visible: {}, scenic score: {score}",
+                        self.at(point),
+                        self.visible(point)
+                    )
+                }),
+        )
+    }
 
-    fn index(&self, point: (u32, u32)) -> &Self::Output {
-        &self.memory[self.calc_idx(point)]
+    fn queries(&self) -> &'static [&'static str] {
+        &["tree <X> <Y>: height, visibility and scenic score, (0, 0) is the top left tree"]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::check_property;
 
     #[test]
-    fn test_example_part_one() {
-        assert_eq!(solve_part_one(parse_input(INPUT.into())), 21);
+    fn test_malformed_forest() {
+        assert!(parse("").is_err());
+        assert!(parse("\n\nxyz\n").is_err());
+        assert!(parse("123\n45\n").is_err());
+        assert!(parse("1\n").is_ok());
+        assert_eq!(
+            parse("123\n4x6\n").err(),
+            Some("line 2, column 2: couldn't convert 'x' to a tree height".into())
+        );
     }
+
     #[test]
-    fn test_example_part_two() {
-        assert_eq!(solve_part_two(parse_input(INPUT.into())), 8);
+    fn test_get_tree() {
+        let forest = parse("123\n456\n").unwrap();
+        assert_eq!(Some(6), forest.get((2, 1)));
+        assert_eq!(None, forest.get((3, 0)));
+        assert_eq!(None, forest.get((0, 2)));
+        assert_eq!(Some(true), forest.is_tree_visible((1, 0)));
+        assert_eq!(None, forest.is_tree_visible((3, 1)));
+        assert_eq!(Some(Ok(0)), forest.calc_tree_scenic_score((0, 1)));
+        assert_eq!(None, forest.calc_tree_scenic_score((u32::MAX, u32::MAX)));
     }
 
-    const INPUT: &str = "30373
-25512
-65332
-33549
-35390";
+    fn brute_force_visible(rows: &[Vec<u8>]) -> u64 {
+        let (height, width) = (rows.len(), rows[0].len());
+        let mut count = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let tree = rows[y][x];
+                let left = (0..x).all(|xx| rows[y][xx] < tree);
+                let right = (x + 1..width).all(|xx| rows[y][xx] < tree);
+                let top = (0..y).all(|yy| rows[yy][x] < tree);
+                let bottom = (y + 1..height).all(|yy| rows[yy][x] < tree);
+                if left || right || top || bottom {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn brute_force_scenic(rows: &[Vec<u8>]) -> u64 {
+        let (height, width) = (rows.len() as i64, rows[0].len() as i64);
+        let mut best = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let tree = rows[y as usize][x as usize];
+                let mut score = 1;
+                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let (mut xx, mut yy, mut seen) = (x + dx, y + dy, 0);
+                    while (0..width).contains(&xx) && (0..height).contains(&yy) {
+                        seen += 1;
+                        if rows[yy as usize][xx as usize] >= tree {
+                            break;
+                        }
+                        xx += dx;
+                        yy += dy;
+                    }
+                    score *= seen;
+                }
+                best = best.max(score);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_property_matches_brute_force() {
+        check_property("forest answers equal the brute force", |rng| {
+            let (width, height) = (rng.range(1, 9) as usize, rng.range(1, 9) as usize);
+            let max_height = rng.range(0, 9);
+            let rows: Vec<Vec<u8>> = (0..height)
+                .map(|_| (0..width).map(|_| rng.range(0, max_height) as u8).collect())
+                .collect();
+            let input: String = rows
+                .iter()
+                .map(|row| row.iter().map(|x| x.to_string()).collect::<String>() + "\n")
+                .collect();
+
+            let visible = part_one(&parse(&input).unwrap());
+            let expected = brute_force_visible(&rows);
+            if visible != Ok(expected) {
+                return Err(format!(
+                    "{input:?}: {visible:?} visible instead of {expected}"
+                ));
+            }
+            let scenic = part_two(&parse(&input).unwrap());
+            let expected = brute_force_scenic(&rows);
+            if scenic != Ok(expected) {
+                return Err(format!(
+                    "{input:?}: scenic score {scenic:?} instead of {expected}"
+                ));
+            }
+            Ok(())
+        });
+    }
 }
@@ -1,6 +1,11 @@
+use crate::overflow::checked_sum_signed;
+use crate::parsing::{read_lines, ParseError, ReadError};
+use crate::registry::Parsed;
+use crate::visualize::{Frame, FrameSink};
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::hash::Hash;
+use std::io::BufRead;
 use std::ops::Add;
 
 /*
@@ -14,42 +19,127 @@ what did I learn:
 */
 
 pub fn solve(input: String, is_part_one: bool) {
+    solve_stream(input.as_bytes(), is_part_one);
+}
+
+pub fn solve_stream(input: impl BufRead, is_part_one: bool) {
+    match answer_stream(input, is_part_one) {
+        Ok(result) => println!("output: {result}"),
+        Err(err) => println!("WARNING {err}"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    answer_stream(input.as_bytes(), is_part_one)
+}
+
+pub fn answer_stream(input: impl BufRead, is_part_one: bool) -> Result<String, String> {
+    let mut read_error = ReadError::default();
+    let lines = read_lines(input, &mut read_error);
+    let mut parse_error = None;
+    let motions =
+        parse_input(lines).map_while(|motion| motion.map_err(|err| parse_error = Some(err)).ok());
     let result = if is_part_one {
-        solve_part_one(input)
+        visited_part_one(motions, &mut |_, _| {})
     } else {
-        solve_part_two(input)
+        visited_part_two(motions, &mut |_, _| {})
     };
+    read_error.check()?;
+    match parse_error {
+        Some(err) => Err(err.to_string()),
+        None => Ok(result.to_string()),
+    }
+}
 
-    println!("output: {result}");
+/// The motions of the head, every one is a direction and a number of steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Motions {
+    motions: Vec<(Direction, i64)>,
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = (Direction, i32)> + '_ {
-    input
-        .lines()
-        .map(|line| {
-            let mut word_iter = line.split(" ");
+impl Motions {
+    pub fn motions(&self) -> &[(Direction, i64)] {
+        &self.motions
+    }
+}
 
-            let dir_text = word_iter.next().ok_or("no 1st element")?;
-            let steps_text = word_iter.next().ok_or("no 2nd element")?;
+/// Parses one motion like `R 4` per line, the first malformed line is an error.
+pub fn parse(input: &str) -> Result<Motions, String> {
+    let motions = parse_input(input.lines().map(String::from))
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    Ok(Motions { motions })
+}
 
-            let dir: Direction = dir_text.try_into().ok().ok_or("direction parsing error")?;
-            let steps: i32 = steps_text.parse().ok().ok_or("steps parsing error")?;
+/// Number of positions visited by the tail of a rope with two knots.
+pub fn part_one(motions: &Motions) -> u64 {
+    visited_part_one(motions.motions.iter().copied(), &mut |_, _| {})
+}
 
-            Ok((dir, steps))
+/// Number of positions visited by the tail of a rope with ten knots.
+pub fn part_two(motions: &Motions) -> u64 {
+    visited_part_two(motions.motions.iter().copied(), &mut |_, _| {})
+}
+
+/// Draws the knots and the positions visited by the tail after every step.
+pub fn visualize(
+    input: &str,
+    is_part_one: bool,
+    frames: &mut dyn FrameSink,
+) -> Result<String, String> {
+    let motions = parse(input)?;
+    let last_step = checked_sum_signed(motions.motions.iter().map(|(_, steps)| *steps))
+        .map_err(|err| err.to_string())?;
+    let mut step = 0;
+    let mut on_step = |knots: &[Point], visited: &HashSet<Point>| {
+        // a frame covers the whole path, only build the ones that are drawn
+        if step == last_step || frames.wants_frames() {
+            let caption = format!("step {step}, the tail visited {} positions", visited.len());
+            frames.show(path_frame(caption, knots, visited));
+        }
+        step += 1;
+    };
+    let motions = motions.motions.iter().copied();
+    let visited = if is_part_one {
+        visited_part_one(motions, &mut on_step)
+    } else {
+        visited_part_two(motions, &mut on_step)
+    };
+    Ok(visited.to_string())
+}
+
+fn parse_input(
+    lines: impl Iterator<Item = String>,
+) -> impl Iterator<Item = Result<(Direction, i64), ParseError>> {
+    lines
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_motion(&line).map_err(|err| ParseError::new(i + 1, format!("{err} in '{line}'")))
         })
-        .flat_map(
-            |proxy: Result<(Direction, i32), &'static str>| match proxy {
-                Err(text) => {
-                    println!("warning! {text}");
-                    None
-                }
-                Ok(good_case) => Some(good_case),
-            },
-        )
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Direction {
+fn parse_motion(line: &str) -> Result<(Direction, i64), &'static str> {
+    let mut word_iter = line.split(' ');
+
+    let dir_text = word_iter.next().ok_or("no 1st element")?;
+    let steps_text = word_iter.next().ok_or("no 2nd element")?;
+    if word_iter.next().is_some() {
+        return Err("more than two elements");
+    }
+
+    let dir: Direction = dir_text.try_into().ok().ok_or("direction parsing error")?;
+    let steps: i64 = steps_text.parse().ok().ok_or("steps parsing error")?;
+    if steps < 0 {
+        return Err("negative number of steps");
+    }
+
+    Ok((dir, steps))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
     Up,
     Down,
     Right,
@@ -57,7 +147,7 @@ enum Direction {
 }
 
 impl Direction {
-    fn vector(&self) -> Point {
+    pub fn vector(&self) -> Point {
         match &self {
             Direction::Down => Point::new(0, -1),
             Direction::Up => Point::new(0, 1),
@@ -81,10 +171,11 @@ impl TryFrom<&str> for Direction {
     }
 }
 
+/// Position on the grid, `y` grows upwards.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
-struct Point {
-    x: i32,
-    y: i32,
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
 }
 
 impl Add for Point {
@@ -96,7 +187,7 @@ impl Add for Point {
 }
 
 impl Point {
-    fn new(x: i32, y: i32) -> Point {
+    pub fn new(x: i64, y: i64) -> Point {
         Point { x, y }
     }
 }
@@ -131,31 +222,45 @@ impl Rope {
     }
 }
 
-fn solve_part_one(input: String) -> i32 {
+/// `on_step` gets the knots and the visited positions at the start and after every step.
+fn visited_part_one(
+    motions: impl Iterator<Item = (Direction, i64)>,
+    on_step: &mut dyn FnMut(&[Point], &HashSet<Point>),
+) -> u64 {
     let mut visited_positions = HashSet::new();
     let mut rope = Rope::new(Point::new(0, 0), Point::new(0, 0));
     visited_positions.insert(rope.tail);
-    for (dir, steps) in parse_input(&input) {
+    on_step(&[rope.head, rope.tail], &visited_positions);
+    for (dir, steps) in motions {
         for _ in 0..steps as usize {
             rope.step(dir);
             visited_positions.insert(rope.tail);
+            on_step(&[rope.head, rope.tail], &visited_positions);
         }
     }
-    visited_positions.len() as i32
+    visited_positions.len() as u64
 }
 
-struct ChainRope {
+/// Rope made of `length` knots, the first one is the head.
+#[derive(Debug, Clone)]
+pub struct ChainRope {
     chain: Vec<Point>,
 }
 
 impl ChainRope {
-    fn new(length: usize) -> ChainRope {
+    /// All the knots start at the origin.
+    pub fn new(length: usize) -> ChainRope {
         ChainRope {
             chain: vec![Point::new(0, 0); length],
         }
     }
 
-    fn step(&mut self, dir: Direction) {
+    pub fn knots(&self) -> &[Point] {
+        &self.chain
+    }
+
+    /// Moves the head by one step and lets the other knots follow.
+    pub fn step(&mut self, dir: Direction) {
         self.update_head(dir);
         self.update_tail();
     }
@@ -192,88 +297,161 @@ impl ChainRope {
             pt.x = new_pt.x;
             pt.y = new_pt.y;
 
-            prev = new_pt.clone();
+            prev = new_pt;
         }
     }
 
-    fn tail(&self) -> Option<Point> {
+    pub fn tail(&self) -> Option<Point> {
         self.chain.last().copied()
     }
 }
 
-fn solve_part_two(input: String) -> i32 {
+fn visited_part_two(
+    motions: impl Iterator<Item = (Direction, i64)>,
+    on_step: &mut dyn FnMut(&[Point], &HashSet<Point>),
+) -> u64 {
     let mut visited_positions = HashSet::new();
     let mut rope = ChainRope::new(10);
     if let Some(last) = rope.tail() {
         visited_positions.insert(last);
     }
-    for (dir, steps) in parse_input(&input) {
+    on_step(rope.knots(), &visited_positions);
+    for (dir, steps) in motions {
         for _ in 0..steps as usize {
             rope.step(dir);
             if let Some(last) = rope.tail() {
                 visited_positions.insert(last);
             }
+            on_step(rope.knots(), &visited_positions);
         }
     }
-    // print_path(&visited_positions);
-    visited_positions.len() as i32
+    visited_positions.len() as u64
 }
 
-#[allow(dead_code)]
-fn print_path(points: &HashSet<Point>) -> Option<()> {
-    let left_most = points.iter().map(|p| p.x).min()?;
-    let right_most = points.iter().map(|p| p.x).max()?;
-    let top_most = points.iter().map(|p| p.y).min()?;
-    let bottom_most = points.iter().map(|p| p.y).max()?;
-
-    for y in top_most..bottom_most + 1 {
-        for x in left_most..right_most + 1 {
-            if x == 0 && y == 0 {
-                print!("S");
-            } else if points.contains(&Point::new(x, y)) {
-                print!("#");
-            } else {
-                print!(".");
-            }
+/// Draws the visited positions as `#` and the start as `s`, the head is `H`
+/// and the other knots are numbered. Up is at the top, the head is kept in view.
+fn path_frame(caption: String, knots: &[Point], visited: &HashSet<Point>) -> Frame {
+    let points = || {
+        visited
+            .iter()
+            .chain(knots)
+            .copied()
+            .chain([Point::new(0, 0)])
+    };
+    let left_most = points().map(|p| p.x).min().unwrap_or(0);
+    let right_most = points().map(|p| p.x).max().unwrap_or(0);
+    let bottom_most = points().map(|p| p.y).min().unwrap_or(0);
+    let top_most = points().map(|p| p.y).max().unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for y in (bottom_most..=top_most).rev() {
+        let mut line = String::new();
+        for x in left_most..=right_most {
+            let point = Point::new(x, y);
+            let symbol = match knots.iter().position(|&knot| knot == point) {
+                Some(0) => 'H',
+                Some(i) => char::from_digit(i as u32 % 10, 10).unwrap_or('T'),
+                None if x == 0 && y == 0 => 's',
+                None if visited.contains(&point) => '#',
+                None => '.',
+            };
+            line.push(symbol);
         }
-        println!("");
+        lines.push(line);
+    }
+
+    let frame = Frame::new(caption, lines);
+    match knots.first() {
+        Some(head) => frame.with_focus((top_most - head.y) as usize, (head.x - left_most) as usize),
+        None => frame,
+    }
+}
+
+impl Parsed for Motions {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        Ok(result.to_string())
     }
-    Some(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &'static str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-
-    const INPUT2: &'static str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
+    #[test]
+    fn test_visualize() {
+        let mut frames = Vec::new();
+        let answer = visualize("R 2\nU 1\n", true, &mut |frame| frames.push(frame));
+        assert_eq!(answer, Ok("2".to_string()));
+        // the start and one frame per step
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].lines, ["H"]);
+        let last = frames.last().unwrap();
+        assert_eq!(last.lines, ["..H", "s1."]);
+        assert_eq!(last.focus, Some((0, 2)));
+        assert_eq!(last.caption, "step 3, the tail visited 2 positions");
+    }
 
     #[test]
-    fn test_example() {
-        assert_eq!(solve_part_one(INPUT.into()), 13);
+    fn test_visualize_huge_steps() {
+        let input = format!("R {}\nU {}\n", i64::MAX, i64::MAX);
+        assert_eq!(
+            visualize(&input, true, &mut |_| {}),
+            Err(crate::overflow::Overflow.to_string())
+        );
     }
 
     #[test]
-    fn test_example_1_part_2() {
-        assert_eq!(solve_part_two(INPUT.into()), 1);
+    fn test_malformed_motion() {
+        let input = "R 4\n\nU x\nL 3\n";
+        assert_eq!(
+            parse(input),
+            Err("line 3: steps parsing error in 'U x'".into())
+        );
+        assert_eq!(
+            answer(input.into(), true),
+            parse(input).map(|_| String::new())
+        );
+        assert_eq!(
+            parse("R -2\n"),
+            Err("line 1: negative number of steps in 'R -2'".into())
+        );
+        assert_eq!(answer("R 4\n\nU 4\n".into(), true), Ok("7".into()));
     }
+
+    /// Stops wanting frames after the first one, like a skipped animation.
+    struct Skipping(Vec<Frame>);
+
+    impl FrameSink for Skipping {
+        fn show(&mut self, frame: Frame) {
+            self.0.push(frame);
+        }
+
+        fn wants_frames(&self) -> bool {
+            self.0.is_empty()
+        }
+    }
+
     #[test]
-    fn test_example_2_part_2() {
-        assert_eq!(solve_part_two(INPUT2.into()), 36);
+    fn test_visualize_skipped() {
+        let mut sink = Skipping(Vec::new());
+        let answer = visualize("R 2\nU 1\n", false, &mut sink);
+        assert_eq!(answer, Ok("1".to_string()));
+        let captions: Vec<_> = sink.0.iter().map(|x| x.caption.as_str()).collect();
+        assert_eq!(
+            captions,
+            [
+                "step 0, the tail visited 1 positions",
+                "step 3, the tail visited 1 positions"
+            ]
+        );
     }
 }
@@ -0,0 +1,392 @@
+//! Example fixtures of the puzzles and the `extract` command that saves them.
+//!
+//! `build.rs` turns every fixture into a test, [`test_stub`] and
+//! [`insert_test`] write one into the day's own `tests` module instead.
+
+use crate::html::{self, Element};
+use std::path::{Path, PathBuf};
+
+/// Directory with the puzzle examples, `examples/dayNN/<name>.txt` holds an
+/// input and `examples/dayNN/<name>.answers` the answers it should produce.
+pub const EXAMPLES_DIR: &str = "examples";
+
+/// Answers listed in a sidecar file. A part that is missing isn't checked,
+/// some examples only come with an answer for one of the parts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl Expected {
+    pub fn part(&self, is_part_one: bool) -> Option<&str> {
+        if is_part_one {
+            self.part_one.as_deref()
+        } else {
+            self.part_two.as_deref()
+        }
+    }
+}
+
+/// Parses a sidecar made of `part1: <answer>` and `part2: <answer>` lines,
+/// blank lines and `#` comments are skipped.
+pub fn parse_expected(text: &str) -> Result<Expected, String> {
+    let mut expected = Expected::default();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("line {}: expected 'partN: answer'", i + 1))?;
+        let value = Some(value.trim().to_string());
+        match key.trim() {
+            "part1" => expected.part_one = value,
+            "part2" => expected.part_two = value,
+            other => return Err(format!("line {}: unknown key '{other}'", i + 1)),
+        }
+    }
+    Ok(expected)
+}
+
+pub fn example_dir(day: i32) -> PathBuf {
+    Path::new(EXAMPLES_DIR).join(format!("day{day:0>2}"))
+}
+
+pub fn example_input_path(day: i32, name: &str) -> PathBuf {
+    example_dir(day).join(format!("{name}.txt"))
+}
+
+pub fn example_answers_path(day: i32, name: &str) -> PathBuf {
+    example_dir(day).join(format!("{name}.answers"))
+}
+
+pub fn read_expected(day: i32, name: &str) -> Result<Expected, String> {
+    let path = example_answers_path(day, name);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("couldn't read '{}': {e}", path.display()))?;
+    parse_expected(&text)
+}
+
+/// Runs both parts of an example through a day's `answer` function and
+/// panics on the first mismatch. Used by the tests generated from the fixtures.
+pub fn check_example(
+    answer: fn(String, bool) -> Result<String, String>,
+    input: &str,
+    expected: &str,
+) {
+    let expected = parse_expected(expected).expect("malformed answers file");
+    for (is_part_one, part_nr) in [(true, 1), (false, 2)] {
+        if let Some(expected) = expected.part(is_part_one) {
+            let found = answer(input.to_string(), is_part_one);
+            assert_eq!(Ok(expected), found.as_deref(), "part {part_nr}");
+        }
+    }
+}
+
+/// Writes answers in the format read by [`parse_expected`].
+pub fn format_expected(expected: &Expected) -> String {
+    let mut text = String::new();
+    if let Some(answer) = &expected.part_one {
+        text.push_str(&format!("part1: {answer}\n"));
+    }
+    if let Some(answer) = &expected.part_two {
+        text.push_str(&format!("part2: {answer}\n"));
+    }
+    text
+}
+
+/// What [`extract_from_html`] guessed from a puzzle page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extracted {
+    pub input: String,
+    pub expected: Expected,
+    /// Code blocks of the page, the example input is one of them.
+    pub block_count: usize,
+}
+
+/// Guesses the example input and its answers from a saved puzzle page.
+///
+/// The input is the first code block announced by a paragraph mentioning an
+/// example. The answer of a part is the first emphasised `<code>` after the
+/// input in its description, part two only exists on pages saved after part
+/// one was solved.
+pub fn extract_from_html(page: &str) -> Result<Extracted, String> {
+    let articles: Vec<Element> = html::elements(page, "article")
+        .into_iter()
+        .filter(|x| x.attributes.contains("day-desc"))
+        .collect();
+    let Some(part_one) = articles.first() else {
+        return Err("no puzzle description found in the page".into());
+    };
+
+    let blocks = html::elements(part_one.inner, "pre");
+    let mut previous_end = 0;
+    let mut chosen = None;
+    for block in &blocks {
+        let before = html::text(&part_one.inner[previous_end..block.start]);
+        if before.to_lowercase().contains("example") {
+            chosen = Some(block);
+            break;
+        }
+        previous_end = block.end;
+    }
+    let Some(block) = chosen.or(blocks.first()) else {
+        return Err("no code block found in the first part".into());
+    };
+    let mut input = html::text(block.inner);
+    if !input.ends_with('\n') {
+        input.push('\n');
+    }
+
+    let expected = Expected {
+        part_one: emphasised_code(&part_one.inner[block.end..]),
+        part_two: articles.get(1).and_then(|x| emphasised_code(x.inner)),
+    };
+    Ok(Extracted {
+        input,
+        expected,
+        block_count: articles
+            .iter()
+            .map(|x| html::elements(x.inner, "pre").len())
+            .sum(),
+    })
+}
+
+/// Text of the first `<code><em>…</em></code>` or `<em><code>…</code></em>`.
+fn emphasised_code(article: &str) -> Option<String> {
+    html::elements(article, "em").into_iter().find_map(|em| {
+        let in_code =
+            article[..em.start].ends_with("<code>") && article[em.end..].starts_with("</code>");
+        let inner = em.inner.trim();
+        let around_code = inner.starts_with("<code>") && inner.ends_with("</code>");
+        (in_code || around_code).then(|| html::text(inner))
+    })
+}
+
+/// Test of a day's `answer` for a fixture, indented to sit in a `tests` module.
+pub fn test_stub(day: i32, name: &str, expected: &Expected) -> String {
+    let mut stub = format!(
+        "    #[test]\n    fn test_example_{}() {{\n        let input = include_str!(\"../../examples/day{day:0>2}/{name}.txt\");\n",
+        name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+    );
+    for is_part_one in [true, false] {
+        if let Some(answer) = expected.part(is_part_one) {
+            stub.push_str(&format!(
+                "        assert_eq!(answer(input.into(), {is_part_one}), Ok({answer:?}.into()));\n"
+            ));
+        }
+    }
+    stub.push_str("    }\n");
+    stub
+}
+
+/// Adds a test to the `tests` module of a day's source, the module is created
+/// when the source has none. A test with the same name is replaced, so
+/// extracting a fixture again doesn't define it twice.
+pub fn insert_test(source: &str, stub: &str) -> String {
+    let name = stub
+        .split_once("fn ")
+        .and_then(|(_, rest)| rest.split_once('('))
+        .map_or("", |(name, _)| name);
+    if let Some(fn_start) = source
+        .find(&format!("fn {name}("))
+        .filter(|_| !name.is_empty())
+    {
+        // from the line of its attribute to the line of its closing brace
+        let attribute = source[..fn_start].rfind("#[test]").unwrap_or(fn_start);
+        let start = source[..attribute].rfind('\n').map_or(0, |i| i + 1);
+        let end = source[fn_start..]
+            .find('{')
+            .and_then(|open| closing_brace(source, fn_start + open))
+            .map(|close| {
+                source[close..]
+                    .find('\n')
+                    .map_or(source.len(), |i| close + i + 1)
+            });
+        if let Some(end) = end {
+            return format!("{}{stub}{}", &source[..start], &source[end..]);
+        }
+    }
+
+    let module_end = source
+        .find("mod tests {")
+        .and_then(|start| closing_brace(source, start + "mod tests ".len()));
+    match module_end {
+        Some(end) => format!("{}\n{stub}{}", &source[..end], &source[end..]),
+        None => format!("{source}\n#[cfg(test)]\nmod tests {{\n    use super::*;\n\n{stub}}}\n"),
+    }
+}
+
+/// Position of the brace closing the one at `open`, braces in comments and
+/// in string and char literals don't count.
+fn closing_brace(source: &str, open: usize) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i += source[i..].find('\n')?;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += source[i..].find("*/")? + 1;
+            }
+            b'r' if matches!(bytes.get(i + 1), Some(b'"' | b'#'))
+                && !bytes[i - 1].is_ascii_alphanumeric() =>
+            {
+                let hashes = bytes[i + 1..].iter().take_while(|&&x| x == b'#').count();
+                let quote = i + 1 + hashes;
+                if bytes.get(quote) == Some(&b'"') {
+                    let terminator = format!("\"{}", "#".repeat(hashes));
+                    i = quote + 1 + source[quote + 1..].find(&terminator)? + hashes;
+                }
+            }
+            b'"' => {
+                i += 1;
+                while bytes.get(i)? != &b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            // a char literal, a lifetime has no closing quote
+            b'\'' => {
+                if bytes.get(i + 1) == Some(&b'\\') {
+                    // the escaped character may be a quote itself
+                    i += 3 + source[i + 3..].find('\'')?;
+                } else if let Some(c) = source[i + 1..].chars().next() {
+                    if bytes.get(i + 1 + c.len_utf8()) == Some(&b'\'') {
+                        i += 1 + c.len_utf8();
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expected() {
+        let expected = parse_expected("# example 2\npart2: 36\n").unwrap();
+        assert_eq!(expected.part(true), None);
+        assert_eq!(expected.part(false), Some("36"));
+
+        assert!(parse_expected("part3: 1").is_err());
+        assert!(parse_expected("36").is_err());
+    }
+
+    const PAGE: &str = "<main>\n<article class=\"day-desc\"><h2>--- Day 6: Tuning Trouble ---</h2>\
+        <p>The device needs a <em>start-of-packet marker</em>, like <code>abcd</code>:</p>\
+        <pre><code>abcd</code></pre>\
+        <p>For example, suppose you receive the following datastream buffer:</p>\
+        <pre><code><em>mjq</em>jpqm&lt;gb\n</code></pre>\
+        <p>This is <em>done</em> after <code><em>7</em></code> characters.</p>\
+        <p>Here are a few more examples:</p><ul>\
+        <li><code>bvwbjplbgvbhsrlpgdmjqwftvncz</code>: first marker after character <code><em>5</em></code></li></ul>\
+        </article>\
+        <p>Your puzzle answer was <code>1042</code>.</p>\
+        <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2>\
+        <p>Now <code>mjq</code>: first marker after character <em><code>19</code></em></p></article>";
+
+    #[test]
+    fn test_extract_from_html() {
+        let extracted = extract_from_html(PAGE).unwrap();
+        assert_eq!(extracted.input, "mjqjpqm<gb\n");
+        assert_eq!(extracted.expected.part(true), Some("7"));
+        assert_eq!(extracted.expected.part(false), Some("19"));
+        assert_eq!(extracted.block_count, 2);
+
+        // saved before part one was solved
+        let part_one_only = &PAGE[..PAGE.find("<p>Your puzzle").unwrap()];
+        let extracted = extract_from_html(part_one_only).unwrap();
+        assert_eq!(extracted.expected.part(false), None);
+
+        assert!(extract_from_html("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_expected_round_trip() {
+        let expected = Expected {
+            part_one: Some("CMZ".into()),
+            part_two: None,
+        };
+        assert_eq!(
+            parse_expected(&format_expected(&expected)),
+            Ok(expected.clone())
+        );
+    }
+
+    const DAY_SOURCE: &str =
+        "pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    Ok(format!(\"{{{}\", input.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_braces() {
+        // }
+        assert_eq!(answer(\"}\".into(), true), Ok(r#\"{1\"#.into()));
+        assert_ne!('}', '\\'');
+    }
+}
+";
+
+    #[test]
+    fn test_insert_stub() {
+        let mut expected = Expected {
+            part_one: Some("CMZ".into()),
+            part_two: None,
+        };
+        let inserted = insert_test(DAY_SOURCE, &test_stub(5, "1", &expected));
+        let tests_end = DAY_SOURCE.rfind("}\n").unwrap();
+        assert_eq!(
+            inserted,
+            format!(
+                "{}\n    #[test]\n    fn test_example_1() {{\n        \
+                 let input = include_str!(\"../../examples/day05/1.txt\");\n        \
+                 assert_eq!(answer(input.into(), true), Ok(\"CMZ\".into()));\n    }}\n}}\n",
+                &DAY_SOURCE[..tests_end]
+            )
+        );
+
+        // extracted again with --force
+        expected.part_two = Some("MCD".into());
+        let stub = test_stub(5, "1", &expected);
+        let replaced = insert_test(&inserted, &stub);
+        assert_eq!(
+            replaced,
+            format!("{}\n{stub}}}\n", &DAY_SOURCE[..tests_end])
+        );
+        assert_eq!(replaced.matches("fn test_example_1(").count(), 1);
+    }
+
+    #[test]
+    fn test_insert_test() {
+        let stub = "    #[test]\n    fn test_new() {}\n";
+        let source = "fn f() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn test_old() {\n        f();\n    }\n}\n";
+        assert_eq!(
+            insert_test(source, stub),
+            "fn f() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn test_old() {\n        f();\n    }\n\n    #[test]\n    fn test_new() {}\n}\n"
+        );
+        assert_eq!(
+            insert_test("fn f() {}\n", stub),
+            "fn f() {}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn test_new() {}\n}\n"
+        );
+    }
+}
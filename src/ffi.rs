@@ -0,0 +1,203 @@
+//! C interface of the solvers, built into the `cdylib` next to the rust library.
+//!
+//! `include/aoc2022.h` is generated from this file by the build script, the
+//! declarations below are the only source of truth. `ffi/test_aoc2022.c`
+//! shows how to call the library.
+
+use crate::registry::{self, Answer};
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// The call succeeded.
+pub const AOC_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const AOC_NULL_POINTER: i32 = 1;
+/// The input is not valid UTF-8.
+pub const AOC_INVALID_UTF8: i32 = 2;
+/// The day doesn't exist or was compiled out.
+pub const AOC_UNKNOWN_DAY: i32 = 3;
+/// The part is neither 1 nor 2.
+pub const AOC_INVALID_PART: i32 = 4;
+/// The solver rejected the input, the result holds the error message.
+pub const AOC_SOLVE_FAILED: i32 = 5;
+/// The solver panicked.
+pub const AOC_PANIC: i32 = 6;
+
+/// Writes up to `capacity` compiled day numbers into `days` and returns the
+/// number of compiled days, call it with a null `days` to get the count only.
+///
+/// # Safety
+///
+/// `days` has to be null or point to `capacity` writable integers.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_days(days: *mut i32, capacity: usize) -> usize {
+    if !days.is_null() {
+        for (i, day) in registry::DAYS.iter().take(capacity).enumerate() {
+            // SAFETY: the caller guarantees room for `capacity` integers
+            unsafe { *days.add(i) = day.number };
+        }
+    }
+    registry::DAYS.len()
+}
+
+/// Solves `part` (1 or 2) of `day` for the `input_len` bytes at `input`.
+/// CRLF line ends, a UTF-8 BOM and trailing blanks are dropped from the input
+/// first, like the runner does.
+///
+/// On `AOC_OK` the answer and on `AOC_SOLVE_FAILED` the error message is
+/// stored in `*result`, the string has to be released with `aoc_free_string`.
+/// For any other code `*result` is set to null.
+///
+/// # Safety
+///
+/// `input` has to point to `input_len` readable bytes and `result` to a
+/// writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: i32,
+    part: i32,
+    input: *const u8,
+    input_len: usize,
+    result: *mut *mut c_char,
+) -> i32 {
+    let answer = registry::find(day).map(|x| x.answer);
+    // SAFETY: same contract as this function
+    unsafe { solve_with(answer, part, input, input_len, result) }
+}
+
+/// The calling convention of `aoc_solve` around any answer function, `None`
+/// is an unknown day. The plugins export their solvers through it.
+///
+/// # Safety
+///
+/// Same as `aoc_solve`.
+#[doc(hidden)]
+pub unsafe fn solve_with(
+    answer: Option<Answer>,
+    part: i32,
+    input: *const u8,
+    input_len: usize,
+    result: *mut *mut c_char,
+) -> i32 {
+    if result.is_null() {
+        return AOC_NULL_POINTER;
+    }
+    // SAFETY: checked for null above, the caller guarantees it's writable
+    unsafe { *result = std::ptr::null_mut() };
+    if input.is_null() && input_len > 0 {
+        return AOC_NULL_POINTER;
+    }
+    let bytes = if input_len == 0 {
+        &[][..]
+    } else {
+        // SAFETY: the caller guarantees `input_len` readable bytes
+        unsafe { std::slice::from_raw_parts(input, input_len) }
+    };
+
+    let (code, text) = solve(answer, part, bytes);
+    if let Some(text) = text {
+        // answers and messages never contain NUL, but don't trust that blindly
+        let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+        // SAFETY: checked for null above
+        unsafe { *result = text.into_raw() };
+    }
+    code
+}
+
+/// Releases a string returned by `aoc_solve`, null is ignored.
+///
+/// # Safety
+///
+/// `text` has to come from `aoc_solve` and can't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: the string was created by `CString::into_raw` in `aoc_solve`
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
+
+fn solve(answer: Option<Answer>, part: i32, bytes: &[u8]) -> (i32, Option<String>) {
+    let Some(answer) = answer else {
+        return (AOC_UNKNOWN_DAY, None);
+    };
+    let is_part_one = match part {
+        1 => true,
+        2 => false,
+        _ => return (AOC_INVALID_PART, None),
+    };
+    let Ok(input) = std::str::from_utf8(bytes) else {
+        return (AOC_INVALID_UTF8, None);
+    };
+
+    // CRLF, a BOM and trailing blanks are dropped like for the runner
+    let (input, _) = crate::normalize::normalize(input);
+    // unwinding into C is undefined behaviour
+    let answer = catch_unwind(AssertUnwindSafe(|| answer(input, is_part_one)));
+    match answer {
+        Ok(Ok(answer)) => (AOC_OK, Some(answer)),
+        Ok(Err(message)) => (AOC_SOLVE_FAILED, Some(message)),
+        Err(_) => (AOC_PANIC, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn call(day: i32, part: i32, input: &[u8]) -> (i32, Option<String>) {
+        let mut result = std::ptr::null_mut();
+        let code = unsafe { aoc_solve(day, part, input.as_ptr(), input.len(), &mut result) };
+        let text = (!result.is_null()).then(|| {
+            let text = unsafe { CStr::from_ptr(result) }
+                .to_string_lossy()
+                .into_owned();
+            unsafe { aoc_free_string(result) };
+            text
+        });
+        (code, text)
+    }
+
+    #[test]
+    fn test_list_days() {
+        let count = unsafe { aoc_days(std::ptr::null_mut(), 0) };
+        let mut days = vec![0; count];
+        assert_eq!(count, unsafe { aoc_days(days.as_mut_ptr(), days.len()) });
+        let expected: Vec<i32> = registry::DAYS.iter().map(|x| x.number).collect();
+        assert_eq!(days, expected);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(call(0, 1, b""), (AOC_UNKNOWN_DAY, None));
+        assert_eq!(
+            unsafe { aoc_solve(1, 1, std::ptr::null(), 0, std::ptr::null_mut()) },
+            AOC_NULL_POINTER
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_solve() {
+        assert_eq!(call(1, 2, b"1\n2\n\n3\n\n4\n"), (AOC_OK, Some("10".into())));
+        assert_eq!(call(1, 3, b""), (AOC_INVALID_PART, None));
+        assert_eq!(call(1, 1, b"\xff"), (AOC_INVALID_UTF8, None));
+    }
+
+    #[test]
+    #[cfg(feature = "day08")]
+    fn test_solve_crlf() {
+        let input = include_str!("../examples/day08/1.txt").replace('\n', "\r\n");
+        let input = format!("\u{feff}{input}  \r\n\r\n");
+        assert_eq!(call(8, 1, input.as_bytes()), (AOC_OK, Some("21".into())));
+    }
+
+    #[test]
+    #[cfg(feature = "day07")]
+    fn test_solve_failed() {
+        let (code, message) = call(7, 1, b"$ cd");
+        assert_eq!(code, AOC_SOLVE_FAILED);
+        assert!(message.unwrap().contains("missing directory name"));
+    }
+}
@@ -0,0 +1,397 @@
+use super::{Generated, Params, Rng};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+fn answers(input: String, part_one: impl ToString, part_two: impl ToString) -> Generated {
+    Generated {
+        input,
+        part_one: Some(part_one.to_string()),
+        part_two: Some(part_two.to_string()),
+    }
+}
+
+/// `size` elves carrying 1 to 10 snacks each.
+pub fn day01(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let mut input = String::new();
+    let mut totals = Vec::with_capacity(params.size);
+    for elf in 0..params.size {
+        if elf > 0 {
+            input.push('\n');
+        }
+        let mut total = 0;
+        for _ in 0..rng.range(1, 10) {
+            let calories = rng.range(1000, 60_000);
+            total += calories;
+            let _ = writeln!(input, "{calories}");
+        }
+        totals.push(total);
+    }
+
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    let part_one = totals.first().copied().unwrap_or(0);
+    let part_two: u64 = totals.iter().take(3).sum();
+    Ok(answers(input, part_one, part_two))
+}
+
+/// `size` rounds of the strategy guide.
+pub fn day02(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let mut input = String::new();
+    let (mut part_one, mut part_two) = (0, 0);
+    for _ in 0..params.size {
+        let oponent = rng.below(3);
+        let right = rng.below(3);
+        let _ = writeln!(
+            input,
+            "{} {}",
+            (b'A' + oponent as u8) as char,
+            (b'X' + right as u8) as char
+        );
+
+        // as a shape: 0 draw, 1 win, 2 loss
+        let outcome = (right + 3 - oponent) % 3;
+        part_one += right + 1 + [3, 6, 0][outcome as usize];
+        // as a result: 0 loss, 1 draw, 2 win
+        let shape = (oponent + right + 2) % 3;
+        part_two += shape + 1 + 3 * right;
+    }
+    Ok(answers(input, part_one, part_two))
+}
+
+fn priority(item: u8) -> u64 {
+    if item.is_ascii_lowercase() {
+        (item - b'a' + 1) as u64
+    } else {
+        (item - b'A' + 27) as u64
+    }
+}
+
+/// `size` rucksacks rounded up to whole groups of three. Every rucksack has
+/// exactly one item in both compartments and every group exactly one badge.
+pub fn day03(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let letters: Vec<u8> = (b'a'..=b'z').chain(b'A'..=b'Z').collect();
+    let mut input = String::new();
+    let (mut part_one, mut part_two) = (0, 0);
+
+    for _ in 0..params.size.div_ceil(3) {
+        let badge = *rng.choose(&letters);
+        part_two += priority(badge);
+
+        // 51 remaining letters split into private pools, so nothing but the
+        // badge can be shared by the whole group
+        let mut pool: Vec<u8> = letters.iter().copied().filter(|&x| x != badge).collect();
+        rng.shuffle(&mut pool);
+        for own in pool.chunks(17) {
+            let shared = own[0];
+            part_one += priority(shared);
+            let (left_fill, right_fill) = own[1..].split_at(8);
+
+            let half = rng.range(3, 16) as usize;
+            let mut left = vec![shared];
+            let mut right = vec![shared];
+            if rng.chance(1, 2) {
+                left.push(badge);
+            } else {
+                right.push(badge);
+            }
+            while left.len() < half {
+                left.push(*rng.choose(left_fill));
+            }
+            while right.len() < half {
+                right.push(*rng.choose(right_fill));
+            }
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+            input.extend(left.iter().chain(right.iter()).map(|&x| x as char));
+            input.push('\n');
+        }
+    }
+    Ok(answers(input, part_one, part_two))
+}
+
+/// `size` pairs of section ranges within 1-99.
+pub fn day04(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let mut input = String::new();
+    let (mut part_one, mut part_two) = (0, 0);
+    let section = |rng: &mut Rng| {
+        let low = rng.range(1, 99);
+        (low, rng.range(low, 99))
+    };
+    for _ in 0..params.size {
+        let (l1, u1) = section(rng);
+        let (l2, u2) = section(rng);
+        let _ = writeln!(input, "{l1}-{u1},{l2}-{u2}");
+        if (l1 <= l2 && u2 <= u1) || (l2 <= l1 && u1 <= u2) {
+            part_one += 1;
+        }
+        if l1.max(l2) <= u1.min(u2) {
+            part_two += 1;
+        }
+    }
+    Ok(answers(input, part_one, part_two))
+}
+
+/// Up to nine stacks and `size` valid moves.
+pub fn day05(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let stack_count = rng.range(3, 9) as usize;
+    let mut stacks: Vec<Vec<char>> = (0..stack_count)
+        .map(|_| {
+            (0..rng.range(1, 8))
+                .map(|_| (b'A' + rng.below(26) as u8) as char)
+                .collect()
+        })
+        .collect();
+
+    let tallest = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut input = String::new();
+    for level in (0..tallest).rev() {
+        let row: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(item) => format!("[{item}]"),
+                None => "   ".into(),
+            })
+            .collect();
+        let _ = writeln!(input, "{}", row.join(" "));
+    }
+    let labels: Vec<String> = (1..=stack_count).map(|i| format!(" {i} ")).collect();
+    let _ = writeln!(input, "{}\n", labels.join(" "));
+
+    // both cranes move the same number of crates, only their order differs
+    let mut part_two_stacks = stacks.clone();
+    for _ in 0..params.size {
+        let non_empty: Vec<usize> = (0..stack_count)
+            .filter(|&i| !stacks[i].is_empty())
+            .collect();
+        let from = *rng.choose(&non_empty);
+        let mut to = rng.index(stack_count - 1);
+        if to >= from {
+            to += 1;
+        }
+        let count = rng.range(1, stacks[from].len() as u64) as usize;
+        let _ = writeln!(input, "move {count} from {} to {}", from + 1, to + 1);
+
+        for _ in 0..count {
+            let item = stacks[from].pop().unwrap_or(' ');
+            stacks[to].push(item);
+        }
+        let split_at = part_two_stacks[from].len() - count;
+        let moved = part_two_stacks[from].split_off(split_at);
+        part_two_stacks[to].extend(moved);
+    }
+
+    let tops = |stacks: &[Vec<char>]| -> String { stacks.iter().flat_map(|x| x.last()).collect() };
+    Ok(answers(input, tops(&stacks), tops(&part_two_stacks)))
+}
+
+fn first_marker(signal: &[u8], window_size: usize) -> Option<usize> {
+    signal
+        .windows(window_size)
+        .position(|window| window.iter().collect::<HashSet<_>>().len() == window_size)
+        .map(|i| i + window_size)
+}
+
+/// A signal of `size` characters with 14 distinct characters placed at
+/// `params.marker`. Everything before them uses only the three letters the
+/// block starts with, so no window reaching into the prefix is a marker: the
+/// start-of-packet marker ends 4 and the start-of-message marker 14
+/// characters into the block.
+pub fn day06(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    const MARKER_LEN: usize = 14;
+    let marker = params
+        .marker
+        .unwrap_or(params.size.saturating_sub(MARKER_LEN) / 2);
+    if marker + MARKER_LEN > params.size {
+        return Err(format!(
+            "the marker at {marker} doesn't fit into a signal of {} characters",
+            params.size
+        ));
+    }
+
+    let (prefix_letters, other_letters) = LOWERCASE.split_at(3);
+    let mut signal: Vec<u8> = (0..marker).map(|_| *rng.choose(prefix_letters)).collect();
+    let mut block = prefix_letters.to_vec();
+    rng.shuffle(&mut block);
+    let mut others = other_letters.to_vec();
+    rng.shuffle(&mut others);
+    block.extend_from_slice(&others[..MARKER_LEN - block.len()]);
+    signal.extend_from_slice(&block);
+    while signal.len() < params.size {
+        signal.push(*rng.choose(LOWERCASE));
+    }
+
+    let part_one = first_marker(&signal, 4).ok_or("no start-of-packet marker")?;
+    let part_two = first_marker(&signal, MARKER_LEN).ok_or("no start-of-message marker")?;
+    let mut input = String::from_utf8_lossy(&signal).into_owned();
+    input.push('\n');
+    Ok(answers(input, part_one, part_two))
+}
+
+struct GenDir {
+    name: String,
+    files: Vec<(String, u64)>,
+    subdirs: Vec<usize>,
+}
+
+fn random_name(rng: &mut Rng, taken: &HashSet<String>) -> String {
+    loop {
+        let name: String = (0..rng.range(1, 8))
+            .map(|_| *rng.choose(LOWERCASE) as char)
+            .collect();
+        if !taken.contains(&name) {
+            return name;
+        }
+    }
+}
+
+/// A file system with `size` directories explored depth first by `cd`/`ls`.
+pub fn day07(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let mut dirs = vec![GenDir {
+        name: "/".into(),
+        files: Vec::new(),
+        subdirs: Vec::new(),
+    }];
+    let mut names: Vec<HashSet<String>> = vec![HashSet::new()];
+    for id in 1..params.size.max(1) {
+        let parent = rng.index(id);
+        let name = random_name(rng, &names[parent]);
+        names[parent].insert(name.clone());
+        dirs[parent].subdirs.push(id);
+        dirs.push(GenDir {
+            name,
+            files: Vec::new(),
+            subdirs: Vec::new(),
+        });
+        names.push(HashSet::new());
+    }
+    for (dir, taken) in dirs.iter_mut().zip(names.iter_mut()) {
+        for _ in 0..rng.range(0, 4) {
+            let name = format!(
+                "{}.{}",
+                random_name(rng, taken),
+                *rng.choose(&["txt", "dat", "log", "lst"])
+            );
+            taken.insert(name.clone());
+            dir.files.push((name, rng.range(1, 300_000)));
+        }
+    }
+
+    let mut input = String::from("$ cd /\n");
+    let mut sizes = vec![0; dirs.len()];
+    // explicit stack instead of recursion, deep random trees are common
+    let mut stack = vec![(0, false)];
+    while let Some((id, listed)) = stack.pop() {
+        let dir = &dirs[id];
+        if listed {
+            sizes[id] += dir.files.iter().map(|(_, size)| size).sum::<u64>();
+            for &sub in &dir.subdirs {
+                sizes[id] += sizes[sub];
+            }
+            if id != 0 {
+                input.push_str("$ cd ..\n");
+            }
+            continue;
+        }
+        if id != 0 {
+            let _ = writeln!(input, "$ cd {}", dir.name);
+        }
+        input.push_str("$ ls\n");
+        for &sub in &dir.subdirs {
+            let _ = writeln!(input, "dir {}", dirs[sub].name);
+        }
+        for (name, size) in &dir.files {
+            let _ = writeln!(input, "{size} {name}");
+        }
+        stack.push((id, true));
+        stack.extend(dir.subdirs.iter().rev().map(|&sub| (sub, false)));
+    }
+
+    let part_one: u64 = sizes.iter().filter(|&&x| x <= 100_000).sum();
+    let needed = (sizes[0] + 30_000_000).saturating_sub(70_000_000);
+    let part_two = if needed == 0 {
+        0
+    } else {
+        sizes
+            .iter()
+            .copied()
+            .filter(|&x| x >= needed)
+            .min()
+            .unwrap_or(0)
+    };
+    Ok(answers(input, part_one, part_two))
+}
+
+/// A `size` x `size` forest.
+pub fn day08(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let size = params.size;
+    let heights: Vec<Vec<u8>> = (0..size)
+        .map(|_| (0..size).map(|_| rng.below(10) as u8).collect())
+        .collect();
+    let mut input = String::with_capacity((size + 1) * size);
+    for row in &heights {
+        input.extend(row.iter().map(|&height| (b'0' + height) as char));
+        input.push('\n');
+    }
+
+    // brute force, every tree looks in the four directions
+    let (mut part_one, mut part_two) = (0, 0);
+    for y in 0..size {
+        for x in 0..size {
+            let height = heights[y][x];
+            let views: [Vec<u8>; 4] = [
+                (0..x).rev().map(|i| heights[y][i]).collect(),
+                (x + 1..size).map(|i| heights[y][i]).collect(),
+                (0..y).rev().map(|j| heights[j][x]).collect(),
+                (y + 1..size).map(|j| heights[j][x]).collect(),
+            ];
+            if views.iter().any(|view| view.iter().all(|&h| h < height)) {
+                part_one += 1;
+            }
+            let score: u64 = views
+                .iter()
+                .map(|view| match view.iter().position(|&h| h >= height) {
+                    Some(i) => i as u64 + 1,
+                    None => view.len() as u64,
+                })
+                .product();
+            part_two = part_two.max(score);
+        }
+    }
+    Ok(answers(input, part_one, part_two))
+}
+
+/// `size` head motions of 1 to 20 steps.
+pub fn day09(rng: &mut Rng, params: Params) -> Result<Generated, String> {
+    let mut input = String::new();
+    let mut knots = [(0i64, 0i64); 10];
+    let mut visited = [HashSet::from([(0, 0)]), HashSet::from([(0, 0)])];
+    for _ in 0..params.size {
+        let direction = *rng.choose(&["U", "D", "L", "R"]);
+        let steps = rng.range(1, 20);
+        let _ = writeln!(input, "{direction} {steps}");
+
+        // naive simulation, one step of the whole rope at a time
+        let (dx, dy) = match direction {
+            "U" => (0, 1),
+            "D" => (0, -1),
+            "L" => (-1, 0),
+            _ => (1, 0),
+        };
+        for _ in 0..steps {
+            knots[0].0 += dx;
+            knots[0].1 += dy;
+            for i in 1..knots.len() {
+                let (hx, hy) = knots[i - 1];
+                let (tx, ty) = &mut knots[i];
+                if (hx - *tx).abs() > 1 || (hy - *ty).abs() > 1 {
+                    *tx += (hx - *tx).signum();
+                    *ty += (hy - *ty).signum();
+                }
+            }
+            visited[0].insert(knots[1]);
+            visited[1].insert(knots[9]);
+        }
+    }
+    Ok(answers(input, visited[0].len(), visited[1].len()))
+}
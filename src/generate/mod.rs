@@ -0,0 +1,197 @@
+mod days;
+
+/// A generated puzzle input together with the answers known from its construction.
+pub struct Generated {
+    pub input: String,
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+/// Knobs shared by all the generators. `size` means "number of entries"
+/// for the day at hand: elves, rounds, rucksacks, moves, directories, …
+#[derive(Debug, Clone, Copy)]
+pub struct Params {
+    pub seed: u64,
+    pub size: usize,
+    /// day06 only, 0-based position of the block of 14 distinct characters;
+    /// `None` puts it in the middle of the signal
+    pub marker: Option<usize>,
+}
+
+/// Generates an input for the given day or `None` if there is no generator for it.
+pub fn generate(day: i32, params: Params) -> Option<Result<Generated, String>> {
+    let mut rng = Rng::new(params.seed);
+    let generated = match day {
+        1 => days::day01(&mut rng, params),
+        2 => days::day02(&mut rng, params),
+        3 => days::day03(&mut rng, params),
+        4 => days::day04(&mut rng, params),
+        5 => days::day05(&mut rng, params),
+        6 => days::day06(&mut rng, params),
+        7 => days::day07(&mut rng, params),
+        8 => days::day08(&mut rng, params),
+        9 => days::day09(&mut rng, params),
+        _ => return None,
+    };
+    Some(generated)
+}
+
+/// Input of a moderate size for the tests of the day modules.
+#[cfg(test)]
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+pub(crate) fn sample(day: i32, seed: u64) -> Generated {
+    let params = Params {
+        seed,
+        size: 60,
+        marker: None,
+    };
+    match generate(day, params) {
+        Some(Ok(generated)) => generated,
+        _ => panic!("no sample for day {day}"),
+    }
+}
+
+/// Runs a randomized property for many seeds and panics with the failing seed.
+///
+/// `AOC_PROP_CASES` changes the number of cases and `AOC_PROP_SEED` reruns a
+/// single reported seed.
+#[cfg(test)]
+pub(crate) fn check_property(name: &str, mut property: impl FnMut(&mut Rng) -> Result<(), String>) {
+    let env_number = |key: &str| std::env::var(key).ok().and_then(|x| x.parse::<u64>().ok());
+    let seeds = match env_number("AOC_PROP_SEED") {
+        Some(seed) => seed..seed + 1,
+        None => 0..env_number("AOC_PROP_CASES").unwrap_or(500),
+    };
+    for seed in seeds {
+        let mut rng = Rng::new(seed);
+        if let Err(message) = property(&mut rng) {
+            panic!("property '{name}' failed for AOC_PROP_SEED={seed}: {message}");
+        }
+    }
+}
+
+/// Small deterministic pseudo random generator (SplitMix64).
+///
+/// Not suitable for anything but test data, but the same seed gives the same
+/// input on every machine and every version of the dependencies.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform number in `0..bound`, `bound` must be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "empty range");
+        // multiply-shift maps the 64 random bits onto the range without modulo bias worth caring about
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    /// Uniform number in `low..=high`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.below(high - low + 1)
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        for _ in 0..1000 {
+            let x = a.range(3, 5);
+            assert!((3..=5).contains(&x));
+        }
+    }
+
+    /// The answers known from the construction of the sample inputs through
+    /// every entry point of the days.
+    #[test]
+    fn test_generated_answers() {
+        for entry in crate::registry::DAYS {
+            for seed in 0..10 {
+                let generated = sample(entry.number, seed);
+                let input = generated.input;
+                let parts = [(true, generated.part_one), (false, generated.part_two)];
+                for (is_part_one, expected) in parts {
+                    let Some(expected) = expected else {
+                        continue;
+                    };
+                    let expected = Ok(expected);
+                    let context =
+                        format!("day {}, part one {is_part_one}, seed {seed}", entry.number);
+                    let found = (entry.answer)(input.clone(), is_part_one);
+                    assert_eq!(found, expected, "{context}");
+                    if let Some(answer_stream) = entry.answer_stream {
+                        let reader = std::io::Cursor::new(input.clone().into_bytes());
+                        let found = answer_stream(Box::new(reader), is_part_one);
+                        assert_eq!(found, expected, "streamed {context}");
+                    }
+                    if let Some(parse) = entry.parse {
+                        let found = parse(&input).and_then(|x| x.part(is_part_one));
+                        assert_eq!(found, expected, "parsed {context}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day06_marker_position() {
+        for (size, marker) in [(14, 0), (20, 6), (60, 3), (100, 86)] {
+            for seed in 0..20 {
+                let params = Params {
+                    seed,
+                    size,
+                    marker: Some(marker),
+                };
+                let generated = generate(6, params).unwrap().unwrap();
+                assert_eq!(generated.part_one, Some((marker + 4).to_string()));
+                assert_eq!(generated.part_two, Some((marker + 14).to_string()));
+            }
+        }
+        let params = Params {
+            seed: 0,
+            size: 20,
+            marker: Some(7),
+        };
+        assert!(generate(6, params).unwrap().is_err());
+    }
+}
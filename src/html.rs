@@ -0,0 +1,397 @@
+//! Just enough HTML for the puzzle pages saved from adventofcode.com.
+//!
+//! The pages are machine written and regular: no comments or scripts inside
+//! the puzzle text and no element nested in one of the same name. This is not
+//! a general parser and doesn't try to be one.
+
+/// One `<tag attributes>inner</tag>` of the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Element<'a> {
+    pub attributes: &'a str,
+    pub inner: &'a str,
+    /// Byte offsets of the opening `<` and of the end of the closing tag.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Every `tag` element in document order, an element without its closing
+/// tag runs to the end of the page.
+pub fn elements<'a>(html: &'a str, tag: &str) -> Vec<Element<'a>> {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}>");
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].find(&open) {
+        let start = pos + offset;
+        let after_name = start + open.len();
+        // `<code` must not match `<codex`
+        let Some(next) = html[after_name..].chars().next() else {
+            break;
+        };
+        if next != '>' && !next.is_whitespace() {
+            pos = after_name;
+            continue;
+        }
+        let Some(tag_end) = html[after_name..].find('>').map(|x| x + after_name) else {
+            break;
+        };
+        let attributes = html[after_name..tag_end].trim();
+        let inner_start = tag_end + 1;
+        let (inner_end, end) = match html[inner_start..].find(&close) {
+            Some(x) => (inner_start + x, inner_start + x + close.len()),
+            None => (html.len(), html.len()),
+        };
+        found.push(Element {
+            attributes,
+            inner: &html[inner_start..inner_end],
+            start,
+            end,
+        });
+        pos = end;
+    }
+    found
+}
+
+/// The text of a piece of HTML, tags removed and entities decoded.
+pub fn text(html: &str) -> String {
+    let mut stripped = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        stripped.push_str(&rest[..open]);
+        rest = match rest[open..].find('>') {
+            Some(close) => &rest[open + close + 1..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    decode_entities(&stripped)
+}
+
+/// Replaces the named entities used on the pages and numeric references,
+/// unknown ones are kept as they are.
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest
+            .find(';')
+            .filter(|&x| x <= 10)
+            .and_then(|x| Some((decode_entity(&rest[1..x])?, x)));
+        match entity {
+            Some((c, semicolon)) => {
+                decoded.push(c);
+                rest = &rest[semicolon + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    emphasis: bool,
+    code: bool,
+    heading: bool,
+}
+
+impl Style {
+    fn escape(&self) -> &'static str {
+        if self.heading || self.emphasis {
+            "\x1b[1m"
+        } else if self.code {
+            "\x1b[36m"
+        } else {
+            ""
+        }
+    }
+}
+
+/// A word made of differently styled pieces, like `<code>x</code>,`.
+type Word = Vec<(String, Style)>;
+
+/// Text of the page laid out for a terminal.
+///
+/// Headings, paragraphs and list items are wrapped to `width` columns, code
+/// blocks are indented and kept as they are. With `color` headings and
+/// emphasis are bold and code is cyan, without it emphasis is put between
+/// `*` so that the text still reads well in a file.
+pub fn render(html: &str, width: usize, color: bool) -> String {
+    let mut renderer = Renderer {
+        out: String::new(),
+        width: width.max(20),
+        color,
+        style: Style::default(),
+        in_pre: false,
+        pre: Vec::new(),
+        words: Vec::new(),
+        word: Vec::new(),
+        prefix: "",
+    };
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            renderer.text(&decode_entities(rest));
+            break;
+        };
+        renderer.text(&decode_entities(&rest[..open]));
+        let close = rest[open..].find('>').map_or(rest.len(), |x| open + x + 1);
+        let tag = rest[open + 1..close].trim_end_matches('>');
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        renderer.tag(&name.to_ascii_lowercase(), closing);
+        rest = &rest[close..];
+    }
+    renderer.end_block();
+    let mut out = renderer.out.trim_end().to_string();
+    out.push('\n');
+    out
+}
+
+struct Renderer {
+    out: String,
+    width: usize,
+    color: bool,
+    style: Style,
+    in_pre: bool,
+    pre: Vec<(String, Style)>,
+    words: Vec<Word>,
+    word: Word,
+    /// Put in front of the first line of the block, the others are indented as much.
+    prefix: &'static str,
+}
+
+impl Renderer {
+    fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.in_pre {
+            self.pre.push((text.to_string(), self.style));
+            return;
+        }
+        for (i, piece) in text.split(char::is_whitespace).enumerate() {
+            if i > 0 && !self.word.is_empty() {
+                self.words.push(std::mem::take(&mut self.word));
+            }
+            if !piece.is_empty() {
+                self.word.push((piece.to_string(), self.style));
+            }
+        }
+    }
+
+    fn tag(&mut self, name: &str, closing: bool) {
+        match (name, closing) {
+            ("em", _) => self.style.emphasis = !closing,
+            ("code", _) => self.style.code = !closing,
+            ("h1" | "h2" | "h3", _) => {
+                self.end_block();
+                self.style.heading = !closing;
+            }
+            ("ul" | "ol", true) => {
+                self.end_block();
+                if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+                    self.out.push('\n');
+                }
+            }
+            ("p" | "ul" | "ol" | "article", _) => {
+                self.end_block();
+                self.prefix = "";
+            }
+            ("li", false) => {
+                self.end_block();
+                self.prefix = "  - ";
+            }
+            ("li", true) => self.end_item(),
+            ("pre", false) => {
+                self.end_block();
+                self.in_pre = true;
+            }
+            ("pre", true) => {
+                self.in_pre = false;
+                self.end_pre();
+            }
+            ("br", _) => self.end_block(),
+            _ => {}
+        }
+    }
+
+    fn styled(&self, text: &str, style: Style) -> String {
+        if self.color && style != Style::default() {
+            format!("{}{text}\x1b[0m", style.escape())
+        } else if style.emphasis && !style.heading {
+            format!("*{text}*")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn render_word(&self, word: &Word) -> (String, usize) {
+        let mut text = String::new();
+        let mut width = 0;
+        // neighbouring pieces of the same style are drawn as one
+        for (i, (piece, style)) in word.iter().enumerate() {
+            let same_as_previous = i > 0 && word[i - 1].1 == *style;
+            let same_as_next = word.get(i + 1).is_some_and(|x| x.1 == *style);
+            let mut styled = self.styled(piece, *style);
+            if !self.color && style.emphasis && !style.heading {
+                if same_as_previous {
+                    styled.remove(0);
+                }
+                if same_as_next {
+                    styled.pop();
+                }
+            }
+            width += styled.chars().count();
+            if self.color {
+                width -= styled.chars().count() - piece.chars().count();
+            }
+            text.push_str(&styled);
+        }
+        (text, width)
+    }
+
+    /// Wraps the words of the block, the block ends with `\n` only.
+    fn flush_words(&mut self) -> bool {
+        if !self.word.is_empty() {
+            self.words.push(std::mem::take(&mut self.word));
+        }
+        if self.words.is_empty() {
+            return false;
+        }
+        let indent = " ".repeat(self.prefix.chars().count());
+        let mut line = self.prefix.to_string();
+        let mut line_width = line.chars().count();
+        let mut line_empty = true;
+        for word in std::mem::take(&mut self.words) {
+            let (text, width) = self.render_word(&word);
+            if !line_empty && line_width + 1 + width > self.width {
+                self.out.push_str(&line);
+                self.out.push('\n');
+                line = indent.clone();
+                line_width = indent.len();
+                line_empty = true;
+            }
+            if !line_empty {
+                line.push(' ');
+                line_width += 1;
+            }
+            line.push_str(&text);
+            line_width += width;
+            line_empty = false;
+        }
+        self.out.push_str(&line);
+        self.out.push('\n');
+        true
+    }
+
+    fn end_block(&mut self) {
+        if self.flush_words() {
+            self.out.push('\n');
+        }
+    }
+
+    fn end_item(&mut self) {
+        self.flush_words();
+        self.prefix = "";
+    }
+
+    fn end_pre(&mut self) {
+        let mut block = String::new();
+        for (text, style) in std::mem::take(&mut self.pre) {
+            // styles are applied line by line so that the indent stays plain
+            let lines: Vec<String> = text
+                .split('\n')
+                .map(|line| {
+                    if line.is_empty() || !self.color {
+                        line.to_string()
+                    } else {
+                        self.styled(line, style)
+                    }
+                })
+                .collect();
+            block.push_str(&lines.join("\n"));
+        }
+        for line in block.trim_end_matches('\n').split('\n') {
+            if line.is_empty() {
+                self.out.push('\n');
+            } else {
+                self.out.push_str(&format!("    {line}\n"));
+            }
+        }
+        self.out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements() {
+        let html = "<p>a <code>x</code> <codex>y</codex> <code class=\"z\"><em>1</em></code>";
+        let codes = elements(html, "code");
+        assert_eq!(codes.len(), 2);
+        assert_eq!(codes[0].inner, "x");
+        assert_eq!(&html[codes[0].start..codes[0].end], "<code>x</code>");
+        assert_eq!(codes[1].attributes, "class=\"z\"");
+        assert_eq!(codes[1].inner, "<em>1</em>");
+        assert_eq!(elements("<pre>open", "pre")[0].inner, "open");
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(text("<em>a</em> &lt;b&gt; &amp;&#39;&#x41;"), "a <b> &'A");
+        assert_eq!(text("R &amp; D & co &unknown;"), "R & D & co &unknown;");
+    }
+
+    #[test]
+    fn test_render() {
+        let html = "<article><h2>--- Day 1: Test ---</h2><p>The Elves take <em>inventory</em> \
+            of the <code>Calories</code>, <em>one</em> <em>per</em> line:</p>\
+            <pre><code>1000\n<em>2000</em>\n\n3000\n</code></pre>\
+            <ul><li>The first Elf is carrying food with <code><em>6000</em></code> Calories.</li>\
+            <li>Short.</li></ul><p>Done?</p></article>";
+        assert_eq!(
+            render(html, 30, false),
+            "--- Day 1: Test ---\n\n\
+             The Elves take *inventory* of\nthe Calories, *one* *per*\nline:\n\n\
+             \x20   1000\n    2000\n\n    3000\n\n\
+             \x20 - The first Elf is carrying\n    food with *6000* Calories.\n  - Short.\n\n\
+             Done?\n"
+        );
+
+        let colored = render(html, 80, true);
+        assert!(colored.starts_with("\x1b[1m---\x1b[0m \x1b[1mDay\x1b[0m"));
+        assert!(colored.contains("\x1b[36mCalories\x1b[0m,"));
+        assert!(colored.contains("    \x1b[1m2000\x1b[0m\n"));
+    }
+}
@@ -0,0 +1,397 @@
+//! Named inputs of a day, for cross-checking the solvers on several accounts.
+//!
+//! Next to the usual `inputs/dayNN.txt`, every member of the team can add
+//! `inputs/dayNN/<name>.txt` with its answers in `inputs/dayNN/<name>.answers`.
+//! The runner and `verify` solve all of them and print a matrix of the results.
+
+use crate::bench;
+use crate::examples::{parse_expected, Expected};
+use crate::json;
+use crate::normalize;
+use crate::registry::{Day, Solver};
+use std::fmt::{self, Display, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of `inputs/dayNN.txt` in the matrix.
+pub const DEFAULT_NAME: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedInput {
+    pub name: String,
+    pub input: PathBuf,
+    pub answers: PathBuf,
+}
+
+pub fn input_dir_of_day(input_dir: &Path, day: i32) -> PathBuf {
+    input_dir.join(format!("day{day:0>2}"))
+}
+
+/// The named inputs of the day sorted by name, `inputs/dayNN.txt` first.
+pub fn named_inputs(input_dir: &Path, day: i32) -> Vec<NamedInput> {
+    let mut inputs = Vec::new();
+    let default = input_dir.join(format!("day{day:0>2}.txt"));
+    if default.is_file() {
+        inputs.push(NamedInput {
+            name: DEFAULT_NAME.into(),
+            input: default,
+            answers: crate::answers::answers_path(input_dir, day),
+        });
+    }
+    let dir = input_dir_of_day(input_dir, day);
+    let mut named: Vec<NamedInput> = std::fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|x| {
+            let path = x.ok()?.path();
+            if path.extension()? != "txt" || !path.is_file() {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some(NamedInput {
+                answers: dir.join(format!("{name}.answers")),
+                input: path,
+                name,
+            })
+        })
+        .collect();
+    named.sort_by(|a, b| a.name.cmp(&b.name));
+    inputs.extend(named);
+    inputs
+}
+
+/// Whether the day has inputs beyond `inputs/dayNN.txt`.
+pub fn has_named_inputs(input_dir: &Path, day: i32) -> bool {
+    named_inputs(input_dir, day)
+        .iter()
+        .any(|x| x.input.parent() != Some(input_dir))
+}
+
+/// Answers of the input, `None` if none were recorded.
+pub fn read_expected(input: &NamedInput) -> Result<Option<Expected>, String> {
+    if !input.answers.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&input.answers)
+        .map_err(|e| format!("couldn't read '{}': {e}", input.answers.display()))?;
+    parse_expected(&text)
+        .map(Some)
+        .map_err(|e| format!("'{}' {e}", input.answers.display()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    /// No answer recorded for the part.
+    Unchecked,
+    /// The input couldn't be read or solved.
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub answer: Result<String, String>,
+    pub expected: Option<String>,
+}
+
+impl Cell {
+    pub fn verdict(&self) -> Verdict {
+        match (&self.answer, &self.expected) {
+            (Err(_), _) => Verdict::Failed,
+            (Ok(_), None) => Verdict::Unchecked,
+            (Ok(answer), Some(expected)) if answer == expected => Verdict::Correct,
+            (Ok(_), Some(_)) => Verdict::Wrong,
+        }
+    }
+}
+
+/// The results of one input, one cell per part that was run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub name: String,
+    /// The solver of a plugin the input was solved with, `None` for the
+    /// built-in one.
+    pub variant: Option<String>,
+    pub parts: Vec<(u8, Cell)>,
+}
+
+impl Row {
+    /// Name of the input, followed by the variant of a plugin.
+    pub fn label(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{} ({variant})", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Solves the parts of one input, both on a single parse when the day can.
+pub fn solve(day: &Day, input: &NamedInput, parts: &[bool]) -> Row {
+    solve_text(input, parts, |text| match day.parse {
+        Some(parse) => {
+            let phases = bench::run_phases(parse, &text);
+            parts
+                .iter()
+                .map(|&is_part_one| match &phases.parts {
+                    Ok([one, two]) => if is_part_one { one } else { two }.answer.clone(),
+                    Err(err) => Err(err.clone()),
+                })
+                .collect()
+        }
+        None => parts
+            .iter()
+            .map(|&is_part_one| (day.answer)(text.clone(), is_part_one))
+            .collect(),
+    })
+}
+
+/// Solves the parts of one input with a solver of a plugin.
+pub fn solve_variant(solver: &dyn Solver, input: &NamedInput, parts: &[bool]) -> Row {
+    let row = solve_text(input, parts, |text| {
+        parts
+            .iter()
+            .map(|&is_part_one| solver.solve(text.clone(), is_part_one))
+            .collect()
+    });
+    Row {
+        variant: Some(solver.variant().into()),
+        ..row
+    }
+}
+
+/// Reads and normalises the input, `answers` gives one answer per part.
+fn solve_text(
+    input: &NamedInput,
+    parts: &[bool],
+    answers: impl FnOnce(String) -> Vec<Result<String, String>>,
+) -> Row {
+    let expected = read_expected(input);
+    let answers = match std::fs::read_to_string(&input.input) {
+        Err(e) => {
+            let err = format!("couldn't read '{}': {e}", input.input.display());
+            parts.iter().map(|_| Err(err.clone())).collect()
+        }
+        Ok(text) => answers(normalize::normalize(&text).0),
+    };
+    let cells = parts.iter().zip(answers).map(|(&is_part_one, answer)| {
+        let cell = match &expected {
+            Ok(expected) => Cell {
+                answer,
+                expected: expected
+                    .as_ref()
+                    .and_then(|x| x.part(is_part_one))
+                    .map(String::from),
+            },
+            Err(err) => Cell {
+                answer: answer.and(Err(err.clone())),
+                expected: None,
+            },
+        };
+        (if is_part_one { 1 } else { 2 }, cell)
+    });
+    Row {
+        name: input.name.clone(),
+        variant: None,
+        parts: cells.collect(),
+    }
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.answer, &self.expected) {
+            (Err(err), _) => write!(f, "WARNING {err}"),
+            (Ok(answer), None) => write!(f, "{answer} ?"),
+            (Ok(answer), Some(expected)) if answer == expected => write!(f, "{answer} ok"),
+            (Ok(answer), Some(expected)) => write!(f, "{answer} WRONG, expected {expected}"),
+        }
+    }
+}
+
+/// Name of the file in the inputs directory that keeps the results of the
+/// last `verify` run, `serve` hands it out.
+pub const LATEST_FILE: &str = "verify.json";
+
+/// The rows of every day as a JSON array with one item per input and part.
+pub fn json(days: &[(i32, Vec<Row>)]) -> String {
+    let items: Vec<String> = days
+        .iter()
+        .flat_map(|(day, rows)| rows.iter().map(move |row| (day, row)))
+        .flat_map(|(day, row)| row.parts.iter().map(move |part| (day, row, part)))
+        .map(|(day, row, (part, cell))| {
+            let verdict = match cell.verdict() {
+                Verdict::Correct => "correct",
+                Verdict::Wrong => "wrong",
+                Verdict::Unchecked => "unchecked",
+                Verdict::Failed => "failed",
+            };
+            let mut item = format!(
+                "{{\"day\": {day}, \"input\": {}, \"part\": {part}, \"verdict\": \"{verdict}\"",
+                json::quote(&row.name)
+            );
+            if let Some(variant) = &row.variant {
+                let _ = write!(item, ", \"variant\": {}", json::quote(variant));
+            }
+            match &cell.answer {
+                Ok(answer) => write!(item, ", \"answer\": {}", json::quote(answer)),
+                Err(err) => write!(item, ", \"error\": {}", json::quote(err)),
+            }
+            .unwrap();
+            if let Some(expected) = &cell.expected {
+                let _ = write!(item, ", \"expected\": {}", json::quote(expected));
+            }
+            item.push('}');
+            item
+        })
+        .collect();
+    format!("[\n  {}\n]\n", items.join(",\n  "))
+}
+
+/// Inputs down, parts across.
+pub fn matrix(rows: &[Row]) -> String {
+    let Some(first) = rows.first() else {
+        return String::new();
+    };
+    let texts: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.parts.iter().map(|(_, x)| x.to_string()).collect())
+        .collect();
+    let labels: Vec<String> = rows.iter().map(Row::label).collect();
+    let name_width = labels
+        .iter()
+        .map(|x| x.chars().count())
+        .chain([5])
+        .max()
+        .unwrap_or(5);
+    let widths: Vec<usize> = (0..first.parts.len())
+        .map(|i| {
+            texts
+                .iter()
+                .filter_map(|x| x.get(i))
+                .map(|x| x.chars().count())
+                .chain([6])
+                .max()
+                .unwrap_or(6)
+        })
+        .collect();
+
+    let mut text = format!("{:name_width$}", "input");
+    for ((part, _), width) in first.parts.iter().zip(&widths) {
+        let _ = write!(text, "  {:width$}", format!("part {part}"));
+    }
+    text = text.trim_end().to_string();
+    text.push('\n');
+    for (label, cells) in labels.iter().zip(&texts) {
+        let mut line = format!("{label:name_width$}");
+        for (cell, width) in cells.iter().zip(&widths) {
+            let _ = write!(line, "  {cell:width$}");
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    #[test]
+    fn test_named_inputs() {
+        let dir = temp_dir("inputs-named");
+        assert!(named_inputs(&dir, 1).is_empty());
+        std::fs::write(dir.join("day01.txt"), "1\n").unwrap();
+        assert!(!has_named_inputs(&dir, 1));
+        let day_dir = input_dir_of_day(&dir, 1);
+        std::fs::create_dir_all(&day_dir).unwrap();
+        std::fs::write(day_dir.join("zoe.txt"), "1\n").unwrap();
+        std::fs::write(day_dir.join("ada.txt"), "1\n").unwrap();
+        std::fs::write(day_dir.join("ada.answers"), "part1: 1\n").unwrap();
+        let inputs = named_inputs(&dir, 1);
+        let names: Vec<&str> = inputs.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, ["default", "ada", "zoe"]);
+        assert_eq!(inputs[0].answers, dir.join("day01.answers"));
+        assert_eq!(inputs[1].answers, day_dir.join("ada.answers"));
+        assert!(has_named_inputs(&dir, 1));
+        assert_eq!(
+            read_expected(&inputs[1])
+                .unwrap()
+                .unwrap()
+                .part_one
+                .as_deref(),
+            Some("1")
+        );
+        assert_eq!(read_expected(&inputs[2]), Ok(None));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "day01")]
+    #[test]
+    fn test_solve() {
+        let dir = temp_dir("inputs-solve");
+        let day_dir = input_dir_of_day(&dir, 1);
+        std::fs::create_dir_all(&day_dir).unwrap();
+        std::fs::write(day_dir.join("ada.txt"), "1000\n2000\n\n4000\n").unwrap();
+        std::fs::write(day_dir.join("ada.answers"), "part1: 4000\npart2: 7001\n").unwrap();
+        let day = crate::registry::find(1).unwrap();
+        let inputs = named_inputs(&dir, 1);
+        let row = solve(day, &inputs[0], &[true, false]);
+        assert_eq!(row.parts[0].1.verdict(), Verdict::Correct);
+        assert_eq!(row.parts[1].1.verdict(), Verdict::Wrong);
+
+        let missing = NamedInput {
+            name: "bob".into(),
+            input: day_dir.join("bob.txt"),
+            answers: day_dir.join("bob.answers"),
+        };
+        let row = solve(day, &missing, &[false]);
+        assert_eq!(row.parts.len(), 1);
+        assert_eq!(row.parts[0].0, 2);
+        assert_eq!(row.parts[0].1.verdict(), Verdict::Failed);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_matrix() {
+        let cell = |answer: Result<&str, &str>, expected: Option<&str>| Cell {
+            answer: answer.map(String::from).map_err(String::from),
+            expected: expected.map(String::from),
+        };
+        let rows = [
+            Row {
+                name: "default".into(),
+                variant: None,
+                parts: vec![
+                    (1, cell(Ok("24000"), Some("24000"))),
+                    (2, cell(Ok("45000"), None)),
+                ],
+            },
+            Row {
+                name: "ada".into(),
+                variant: Some("sorted".into()),
+                parts: vec![
+                    (1, cell(Ok("3"), Some("4"))),
+                    (2, cell(Err("no elves"), None)),
+                ],
+            },
+        ];
+        assert_eq!(
+            matrix(&rows),
+            "input         part 1               part 2\n\
+             default       24000 ok             45000 ?\n\
+             ada (sorted)  3 WRONG, expected 4  WARNING no elves\n"
+        );
+        assert_eq!(matrix(&[]), "");
+
+        let json = json(&[(1, rows.to_vec())]);
+        let items = crate::json::parse(&json).unwrap();
+        let items = items.as_array().unwrap();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].get("verdict").unwrap().as_str(), Some("correct"));
+        assert_eq!(items[2].get("input").unwrap().as_str(), Some("ada"));
+        assert_eq!(items[2].get("variant").unwrap().as_str(), Some("sorted"));
+        assert_eq!(items[0].get("variant"), None);
+        assert_eq!(items[2].get("expected").unwrap().as_str(), Some("4"));
+        assert_eq!(items[3].get("error").unwrap().as_str(), Some("no elves"));
+    }
+}
@@ -0,0 +1,328 @@
+//! Statistics of a private leaderboard, behind the `leaderboard` command.
+//!
+//! The input is the JSON document adventofcode.com serves for a private
+//! leaderboard, saved from the site or piped in: the runner has no client to
+//! fetch it with a session cookie. Times of a day are counted from the unlock
+//! of its puzzle at midnight EST, the daily ranks from the local score of every
+//! part done up to that day.
+
+use crate::json::{self, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: i64,
+    /// Members without a public name are shown as `anonymous user #id`.
+    pub name: String,
+    pub stars: i64,
+    pub local_score: i64,
+    /// Unix time of every star by (day, part).
+    pub stars_at: BTreeMap<(u32, u32), i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub year: i32,
+    pub members: Vec<Member>,
+}
+
+pub fn parse(text: &str) -> Result<Leaderboard, String> {
+    let document = json::parse(text).map_err(|e| e.to_string())?;
+    let year = document
+        .get("event")
+        .and_then(|x| x.as_str()?.parse().ok())
+        .ok_or("the leaderboard has no event year")?;
+    let members = document
+        .get("members")
+        .and_then(Value::as_object)
+        .ok_or("the leaderboard has no members")?;
+
+    let mut parsed = Vec::new();
+    for (key, member) in members {
+        let number = |field: &str| member.get(field).and_then(Value::as_i64);
+        let id = number("id").ok_or_else(|| format!("member '{key}' has no id"))?;
+        let name = member
+            .get("name")
+            .and_then(Value::as_str)
+            .map_or_else(|| format!("anonymous user #{id}"), String::from);
+        let mut stars_at = BTreeMap::new();
+        let days = member
+            .get("completion_day_level")
+            .and_then(Value::as_object)
+            .unwrap_or_default();
+        for (day, parts) in days {
+            for (part, star) in parts.as_object().unwrap_or_default() {
+                let (Ok(day), Ok(part)) = (day.parse(), part.parse()) else {
+                    return Err(format!(
+                        "member '{name}' has a star of day '{day}' part '{part}'"
+                    ));
+                };
+                // older leaderboards store the time as a string
+                let time = star.get("get_star_ts").and_then(|x| {
+                    x.as_i64()
+                        .or_else(|| x.as_str().and_then(|x| x.parse().ok()))
+                });
+                let time = time.ok_or_else(|| {
+                    format!("member '{name}' has no time for day {day} part {part}")
+                })?;
+                stars_at.insert((day, part), time);
+            }
+        }
+        parsed.push(Member {
+            id,
+            name,
+            stars: number("stars").unwrap_or(stars_at.len() as i64),
+            local_score: number("local_score").unwrap_or(0),
+            stars_at,
+        });
+    }
+    parsed.sort_by_key(|x| (-x.local_score, -x.stars, x.id));
+    Ok(Leaderboard {
+        year,
+        members: parsed,
+    })
+}
+
+/// Unix time of the unlock of a puzzle, midnight EST is 05:00 UTC.
+pub fn unlock_time(year: i32, day: u32) -> i64 {
+    (days_from_civil(year, 12, 1) + day as i64 - 1) * 86400 + 5 * 3600
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year } as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// One member on one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayResult {
+    /// Index into [`Leaderboard::members`].
+    pub member: usize,
+    /// Seconds from the unlock to the first star.
+    pub part_one: Option<i64>,
+    /// Seconds from the first star to the second one.
+    pub part_two_delta: Option<i64>,
+    /// Rank by the local score of the days up to this one.
+    pub rank: usize,
+    /// Places won since the previous day, negative when lost.
+    pub rank_change: Option<i64>,
+}
+
+/// Results of every day someone got a star on, members without any score yet
+/// are left out.
+pub fn days(leaderboard: &Leaderboard) -> Vec<(u32, Vec<DayResult>)> {
+    let members = &leaderboard.members;
+    let last_day = members
+        .iter()
+        .flat_map(|x| x.stars_at.keys())
+        .map(|x| x.0)
+        .max()
+        .unwrap_or(0);
+
+    let mut scores = vec![0; members.len()];
+    let mut previous_ranks: Option<Vec<usize>> = None;
+    let mut days = Vec::new();
+    for day in 1..=last_day {
+        for part in [1, 2] {
+            // the first to finish gets as many points as there are members
+            let mut finished: Vec<(i64, usize)> = members
+                .iter()
+                .enumerate()
+                .filter_map(|(i, x)| Some((*x.stars_at.get(&(day, part))?, i)))
+                .collect();
+            finished.sort();
+            for (place, (_, i)) in finished.into_iter().enumerate() {
+                scores[i] += members.len() - place;
+            }
+        }
+        let ranks: Vec<usize> = scores
+            .iter()
+            .map(|score| 1 + scores.iter().filter(|x| *x > score).count())
+            .collect();
+
+        let unlock = unlock_time(leaderboard.year, day);
+        let mut results: Vec<DayResult> = members
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| scores[*i] > 0)
+            .map(|(i, member)| {
+                let part_one = member.stars_at.get(&(day, 1));
+                let part_two = member.stars_at.get(&(day, 2));
+                DayResult {
+                    member: i,
+                    part_one: part_one.map(|x| x - unlock),
+                    part_two_delta: part_one.zip(part_two).map(|(one, two)| two - one),
+                    rank: ranks[i],
+                    rank_change: previous_ranks
+                        .as_ref()
+                        .map(|previous| previous[i] as i64 - ranks[i] as i64),
+                }
+            })
+            .collect();
+        results.sort_by_key(|x| (x.rank, x.member));
+        let anyone_played = results
+            .iter()
+            .any(|x| x.part_one.is_some() || x.part_two_delta.is_some());
+        if anyone_played {
+            days.push((day, results));
+        }
+        previous_ranks = Some(ranks);
+    }
+    days
+}
+
+/// `H:MM:SS`, hours aren't wrapped into days.
+pub fn duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    format!(
+        "{sign}{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+pub fn report(leaderboard: &Leaderboard) -> String {
+    let members = &leaderboard.members;
+    let width = members
+        .iter()
+        .map(|x| x.name.chars().count())
+        .chain([6])
+        .max()
+        .unwrap_or(6);
+
+    let mut text = format!(
+        "private leaderboard {}, {} members\n\n",
+        leaderboard.year,
+        members.len()
+    );
+    let _ = writeln!(text, "rank  {:width$}  stars  score", "member");
+    for (i, member) in members.iter().enumerate() {
+        let _ = writeln!(
+            text,
+            "{:>4}  {:width$}  {:>5}  {:>5}",
+            i + 1,
+            member.name,
+            member.stars,
+            member.local_score
+        );
+    }
+
+    for (day, results) in days(leaderboard) {
+        let _ = writeln!(text, "\nday {day}");
+        let _ = writeln!(text, "{:width$}     part 1  part 2 after  rank", "member");
+        for result in results {
+            let part_one = result.part_one.map_or("-".into(), duration);
+            let part_two = result
+                .part_two_delta
+                .map_or("-".into(), |x| format!("+{}", duration(x)));
+            let change = match result.rank_change {
+                None | Some(0) => String::new(),
+                Some(x) if x > 0 => format!(" (+{x})"),
+                Some(x) => format!(" ({x})"),
+            };
+            let _ = writeln!(
+                text,
+                "{:width$}  {part_one:>9}  {part_two:>12}  {:>4}{change}",
+                members[result.member].name, result.rank
+            );
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/leaderboard.json");
+
+    #[test]
+    fn test_unlock_time() {
+        // 2022-12-01T05:00:00Z
+        assert_eq!(unlock_time(2022, 1), 1669870800);
+        assert_eq!(unlock_time(2022, 25), 1669870800 + 24 * 86400);
+        assert_eq!(unlock_time(2020, 1), 1606798800);
+    }
+
+    #[test]
+    fn test_parse() {
+        let leaderboard = parse(FIXTURE).unwrap();
+        assert_eq!(leaderboard.year, 2022);
+        let names: Vec<&str> = leaderboard
+            .members
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect();
+        assert_eq!(names, ["Ada", "Brendan", "anonymous user #303"]);
+        assert_eq!(leaderboard.members[1].stars_at.len(), 3);
+        assert!(parse("{\"members\": {}}").is_err());
+        assert!(parse("[").is_err());
+    }
+
+    #[test]
+    fn test_days() {
+        let leaderboard = parse(FIXTURE).unwrap();
+        let days = days(&leaderboard);
+        assert_eq!(days.len(), 2);
+
+        let (day, results) = &days[0];
+        assert_eq!(*day, 1);
+        // Brendan leads after the first day
+        assert_eq!(results[0].member, 1);
+        assert_eq!(results[0].part_one, Some(300));
+        assert_eq!(results[0].part_two_delta, Some(400));
+        assert_eq!(results[0].rank_change, None);
+        assert_eq!(results[2].part_one, Some(9200));
+        assert_eq!(results[2].part_two_delta, None);
+
+        let (_, results) = &days[1];
+        let ranks: Vec<(usize, usize, Option<i64>)> = results
+            .iter()
+            .map(|x| (x.member, x.rank, x.rank_change))
+            .collect();
+        assert_eq!(ranks, [(0, 1, Some(1)), (1, 2, Some(-1)), (2, 3, Some(0))]);
+    }
+
+    #[test]
+    fn test_report() {
+        let report = report(&parse(FIXTURE).unwrap());
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "private leaderboard 2022, 3 members");
+        assert_eq!(lines[2], "rank  member               stars  score");
+        assert_eq!(lines[3], "   1  Ada                      4     10");
+        assert_eq!(
+            lines[8],
+            "member                  part 1  part 2 after  rank"
+        );
+        assert_eq!(
+            lines[9],
+            "Brendan                0:05:00      +0:06:40     1"
+        );
+        assert_eq!(
+            lines[11],
+            "anonymous user #303    2:33:20             -     3"
+        );
+        assert_eq!(
+            lines[15],
+            "Ada                    0:05:00      +0:15:00     1 (+1)"
+        );
+        assert_eq!(
+            lines[16],
+            "Brendan                0:15:00             -     2 (-1)"
+        );
+        assert_eq!(
+            lines[17],
+            "anonymous user #303          -             -     3"
+        );
+    }
+}
@@ -1,9 +1,50 @@
+//! Solutions of Advent of Code 2022.
+//!
+//! Every `dayNN` module has the same shape: `parse` turns the puzzle input
+//! into the day's input type, `part_one` and `part_two` compute typed answers
+//! from it and `answer`/`solve` wrap the three for the runner.
+
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod dashboard;
+#[cfg(feature = "day01")]
 pub mod day01;
+#[cfg(feature = "day02")]
 pub mod day02;
+#[cfg(feature = "day03")]
 pub mod day03;
+#[cfg(feature = "day04")]
 pub mod day04;
+#[cfg(feature = "day05")]
 pub mod day05;
+#[cfg(feature = "day06")]
 pub mod day06;
+#[cfg(feature = "day07")]
 pub mod day07;
+#[cfg(feature = "day08")]
 pub mod day08;
+#[cfg(feature = "day09")]
 pub mod day09;
+pub mod examples;
+pub mod ffi;
+pub mod generate;
+pub mod html;
+pub mod inputs;
+pub mod json;
+pub mod leaderboard;
+pub mod normalize;
+pub mod overflow;
+pub mod parsing;
+pub mod plugin;
+pub mod puzzle;
+pub mod registry;
+pub mod repl;
+#[cfg(feature = "init")]
+pub mod scaffold;
+pub mod serve;
+pub mod status;
+#[cfg(test)]
+mod testing;
+pub mod topk;
+pub mod visualize;
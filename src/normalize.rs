@@ -0,0 +1,236 @@
+//! Preflight cleanup of the puzzle inputs.
+//!
+//! Inputs saved on Windows or by some editors come with a UTF-8 BOM, `\r\n`
+//! line endings or trailing blanks that the solvers would take as part of the
+//! data. The runner passes every input through [`normalize`] or
+//! [`NormalizedReader`] first and tells what was changed.
+
+use std::cell::Cell;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Read};
+use std::rc::Rc;
+
+const BOM: &[u8] = "\u{feff}".as_bytes();
+
+/// What the normalisation changed, all zero for a clean input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub bom: bool,
+    pub crlf_lines: usize,
+    /// Lines that ended with spaces or tabs.
+    pub trimmed_lines: usize,
+    /// Blank lines dropped from the end of the input.
+    pub trailing_blank_lines: usize,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        *self == Changes::default()
+    }
+}
+
+impl Display for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.bom {
+            parts.push("removed the UTF-8 BOM".to_string());
+        }
+        if self.crlf_lines > 0 {
+            parts.push(format!("converted {} CRLF line endings", self.crlf_lines));
+        }
+        if self.trimmed_lines > 0 {
+            parts.push(format!(
+                "trimmed trailing blanks of {} lines",
+                self.trimmed_lines
+            ));
+        }
+        if self.trailing_blank_lines > 0 {
+            parts.push(format!(
+                "dropped {} blank lines at the end",
+                self.trailing_blank_lines
+            ));
+        }
+        if parts.is_empty() {
+            write!(f, "nothing to change")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// Normalised copy of the whole input.
+pub fn normalize(input: &str) -> (String, Changes) {
+    let mut reader = NormalizedReader::new(input.as_bytes());
+    let changes = reader.changes();
+    let mut text = String::with_capacity(input.len());
+    // only ASCII blanks and the BOM are removed, the rest stays valid UTF-8
+    reader
+        .read_to_string(&mut text)
+        .expect("reading from memory doesn't fail");
+    let changes = changes.get();
+    (text, changes)
+}
+
+/// Normalises the lines of `inner` while they are read.
+///
+/// Blank lines are held back until a line with content follows, the ones
+/// still held at the end of the input are dropped.
+pub struct NormalizedReader<R> {
+    inner: R,
+    raw: Vec<u8>,
+    line: Vec<u8>,
+    pos: usize,
+    blank_lines: usize,
+    at_start: bool,
+    changes: Rc<Cell<Changes>>,
+}
+
+impl<R: BufRead> NormalizedReader<R> {
+    pub fn new(inner: R) -> Self {
+        NormalizedReader {
+            inner,
+            raw: Vec::new(),
+            line: Vec::new(),
+            pos: 0,
+            blank_lines: 0,
+            at_start: true,
+            changes: Rc::default(),
+        }
+    }
+
+    /// Handle to the changes, complete once the reader reached the end.
+    pub fn changes(&self) -> Rc<Cell<Changes>> {
+        Rc::clone(&self.changes)
+    }
+
+    /// Reads raw lines until there is something to return, false at the end.
+    fn next_line(&mut self) -> io::Result<bool> {
+        let mut changes = self.changes.get();
+        loop {
+            self.raw.clear();
+            if self.inner.read_until(b'\n', &mut self.raw)? == 0 {
+                changes.trailing_blank_lines += std::mem::take(&mut self.blank_lines);
+                self.changes.set(changes);
+                return Ok(false);
+            }
+            let mut content = &self.raw[..];
+            if std::mem::take(&mut self.at_start) && content.starts_with(BOM) {
+                content = &content[BOM.len()..];
+                changes.bom = true;
+            }
+            let has_newline = content.ends_with(b"\n");
+            if has_newline {
+                content = &content[..content.len() - 1];
+            }
+            if content.ends_with(b"\r") {
+                content = &content[..content.len() - 1];
+                changes.crlf_lines += 1;
+            }
+            let trimmed = content.trim_ascii_end();
+            if trimmed.len() != content.len() {
+                changes.trimmed_lines += 1;
+            }
+            if trimmed.is_empty() {
+                self.blank_lines += 1;
+                continue;
+            }
+
+            self.line.clear();
+            self.pos = 0;
+            self.line.extend(std::iter::repeat_n(
+                b'\n',
+                std::mem::take(&mut self.blank_lines),
+            ));
+            self.line.extend_from_slice(trimmed);
+            if has_newline {
+                self.line.push(b'\n');
+            }
+            self.changes.set(changes);
+            return Ok(true);
+        }
+    }
+}
+
+impl<R: BufRead> Read for NormalizedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl<R: BufRead> BufRead for NormalizedReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.line.len() && !self.next_line()? {
+            return Ok(&[]);
+        }
+        Ok(&self.line[self.pos..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = (self.pos + amount).min(self.line.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_input_is_unchanged() {
+        for input in ["", "a\n", "a\n\nb", "  [D]\n1 2\n"] {
+            assert_eq!(normalize(input), (input.to_string(), Changes::default()));
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let (text, changes) = normalize("\u{feff}A Y\r\nB X \r\n\r\n  \nC Z\t\r\n\r\n \n\n");
+        assert_eq!(text, "A Y\nB X\n\n\nC Z\n");
+        assert_eq!(
+            changes,
+            Changes {
+                bom: true,
+                crlf_lines: 5,
+                trimmed_lines: 4,
+                trailing_blank_lines: 3,
+            }
+        );
+        assert_eq!(
+            changes.to_string(),
+            "removed the UTF-8 BOM, converted 5 CRLF line endings, \
+             trimmed trailing blanks of 4 lines, dropped 3 blank lines at the end"
+        );
+    }
+
+    #[test]
+    fn test_reader_reports_at_the_end() {
+        let mut reader = NormalizedReader::new(&b"1\r\n2\r\n\r\n"[..]);
+        let changes = reader.changes();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "1\n");
+        assert_eq!(changes.get().trailing_blank_lines, 0);
+
+        let rest: Vec<String> = reader.lines().map(Result::unwrap).collect();
+        assert_eq!(rest, ["2"]);
+        assert_eq!(changes.get().crlf_lines, 3);
+        assert_eq!(changes.get().trailing_blank_lines, 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "day02", feature = "day07"))]
+    fn test_windows_inputs_solve_like_unix_ones() {
+        use crate::{day02, day07};
+
+        let guide = "A Y\nB X\nC Z\n";
+        let (text, _) = normalize(&guide.replace('\n', "\r\n"));
+        assert_eq!(day02::answer(text, true), day02::answer(guide.into(), true));
+
+        let transcript = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd a\n$ ls\n10 c\n";
+        let (text, _) = normalize(&format!("\u{feff}{}", transcript.replace('\n', "\r\n")));
+        assert_eq!(day07::answer(text, true), Ok("10".into()));
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt::{self, Display};
+
+/// Error returned instead of a silently wrapped answer when a sum or product
+/// doesn't fit into 64 bits, which only happens for huge generated inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arithmetic overflow, the answer doesn't fit into 64 bits"
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Adds the values up, failing on the first overflow.
+pub fn checked_sum(values: impl IntoIterator<Item = u64>) -> Result<u64, Overflow> {
+    values
+        .into_iter()
+        .try_fold(0u64, |total, value| total.checked_add(value))
+        .ok_or(Overflow)
+}
+
+/// Signed counterpart of [`checked_sum`].
+pub fn checked_sum_signed(values: impl IntoIterator<Item = i64>) -> Result<i64, Overflow> {
+    values
+        .into_iter()
+        .try_fold(0i64, |total, value| total.checked_add(value))
+        .ok_or(Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_sum() {
+        assert_eq!(checked_sum([1, 2, 3]), Ok(6));
+        assert_eq!(checked_sum([u64::MAX, 1]), Err(Overflow));
+        assert_eq!(checked_sum_signed([-5, 2]), Ok(-3));
+        assert_eq!(checked_sum_signed([i64::MIN, -1]), Err(Overflow));
+    }
+}
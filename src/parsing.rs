@@ -0,0 +1,438 @@
+use std::fmt::{self, Display};
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// Error returned when a line of the input couldn't be parsed.
+///
+/// `line` is 1-based and counts every line of the input, blank ones included,
+/// so it can be looked up directly in an editor. `column` is 1-based too and
+/// counts characters, it's set when the error points inside the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// Moves the error to another line, for parsers that only see one line.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {column}: {}", self.line, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Iterates over groups of lines separated by one or more blank lines.
+///
+/// Lines inside a paragraph are returned untouched (leading spaces are kept,
+/// which matters for drawings like the day05 stacks), only the separating
+/// blank lines are dropped.
+pub fn paragraphs(input: &str) -> Paragraphs<'_> {
+    Paragraphs { rest: input }
+}
+
+pub struct Paragraphs<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // skip the blank lines in front of the paragraph
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let line_end = self.rest.find('\n').map_or(self.rest.len(), |i| i + 1);
+            if !self.rest[..line_end].trim().is_empty() {
+                break;
+            }
+            self.rest = &self.rest[line_end..];
+        }
+
+        let mut end = 0;
+        while end < self.rest.len() {
+            let line_end = self.rest[end..]
+                .find('\n')
+                .map_or(self.rest.len(), |i| end + i + 1);
+            if self.rest[end..line_end].trim().is_empty() {
+                break;
+            }
+            end = line_end;
+        }
+
+        let paragraph = self.rest[..end].trim_end_matches('\n');
+        self.rest = &self.rest[end..];
+        Some(paragraph)
+    }
+}
+
+/// Parses every non-blank line of the input with `parser`.
+///
+/// Errors carry the line number, so `.collect::<Result<Vec<_>, _>>()` points
+/// straight at the first offending line.
+pub fn parse_lines_with<'a, T, E, F>(
+    input: &'a str,
+    mut parser: F,
+) -> impl Iterator<Item = Result<T, ParseError>> + 'a
+where
+    E: Display,
+    F: FnMut(&str) -> Result<T, E> + 'a,
+{
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(move |(i, line)| {
+            parser(line).map_err(|err| ParseError::new(i + 1, format!("{err} in '{line}'")))
+        })
+}
+
+/// Extracts every integer found in the text, skipping whatever is between them.
+///
+/// A `-` counts as a sign only when it isn't glued to a preceding letter or
+/// digit, so ranges like `2-4` yield `2` and `4`. Numbers that don't fit
+/// into `T` are skipped.
+pub fn integers<T: FromStr>(text: &str) -> impl Iterator<Item = T> + '_ {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < bytes.len() {
+            let start = pos;
+            let is_sign = bytes[pos] == b'-'
+                && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit)
+                && (pos == 0 || !bytes[pos - 1].is_ascii_alphanumeric());
+            if is_sign {
+                pos += 1;
+            } else if !bytes[pos].is_ascii_digit() {
+                pos += 1;
+                continue;
+            }
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            if let Ok(number) = text[start..pos].parse() {
+                return Some(number);
+            }
+        }
+        None
+    })
+}
+
+/// Keeps the first error of [`read_lines`] or [`read_chars`].
+///
+/// Both readers end at the first error, a streaming solver has to
+/// [`check`](ReadError::check) it once it's done, otherwise a failed read
+/// looks like a shorter input and gives a wrong answer.
+#[derive(Debug, Default)]
+pub struct ReadError(Option<std::io::Error>);
+
+impl ReadError {
+    pub fn check(self) -> Result<(), String> {
+        match self.0 {
+            Some(err) => Err(format!("couldn't read the input: {err}")),
+            None => Ok(()),
+        }
+    }
+
+    fn set(&mut self, err: std::io::Error) {
+        self.0.get_or_insert(err);
+    }
+}
+
+/// Reads the lines one at a time, so the whole input never sits in memory.
+///
+/// A line that isn't valid UTF-8 is an error, like any other I/O error it
+/// ends the iteration and is kept in `error`.
+pub fn read_lines<'a>(
+    reader: impl BufRead + 'a,
+    error: &'a mut ReadError,
+) -> impl Iterator<Item = String> + 'a {
+    reader.lines().map_while(move |line| match line {
+        Ok(line) => Some(line),
+        Err(err) => {
+            error.set(err);
+            None
+        }
+    })
+}
+
+/// Decodes UTF-8 characters straight from the reader, one at a time.
+///
+/// Useful for inputs made of a single huge line. Invalid byte sequences are
+/// replaced with `char::REPLACEMENT_CHARACTER`, the bytes after a bad one are
+/// decoded on their own. I/O errors end the iteration and are kept in `error`.
+pub fn read_chars<'a>(
+    reader: impl BufRead + 'a,
+    error: &'a mut ReadError,
+) -> impl Iterator<Item = char> + 'a {
+    let mut bytes = reader
+        .bytes()
+        .map_while(move |byte| match byte {
+            Ok(byte) => Some(byte),
+            Err(err) => {
+                error.set(err);
+                None
+            }
+        })
+        .peekable();
+    std::iter::from_fn(move || {
+        let first = bytes.next()?;
+        let width = match first {
+            0x00..=0x7f => return Some(first as char),
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return Some(char::REPLACEMENT_CHARACTER),
+        };
+        let mut buffer = [first, 0, 0, 0];
+        for slot in buffer.iter_mut().take(width).skip(1) {
+            // only continuation bytes belong to the character
+            match bytes.next_if(|x| (0x80..=0xbf).contains(x)) {
+                Some(byte) => *slot = byte,
+                None => return Some(char::REPLACEMENT_CHARACTER),
+            }
+        }
+        let decoded = std::str::from_utf8(&buffer[..width]).ok();
+        Some(
+            decoded
+                .and_then(|text| text.chars().next())
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        )
+    })
+}
+
+/// A line template where every `{}` stands for one captured field,
+/// e.g. `Pattern::new("move {} from {} to {}")`.
+///
+/// Each capture stretches up to the first occurrence of the literal text that
+/// follows it, the last one up to the literal suffix of the template.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    literals: Vec<String>,
+}
+
+impl Pattern {
+    pub fn new(template: &str) -> Self {
+        Pattern {
+            literals: template.split("{}").map(String::from).collect(),
+        }
+    }
+
+    pub fn field_count(&self) -> usize {
+        self.literals.len() - 1
+    }
+
+    /// Returns the captured fields or `None` if the line doesn't fit the template.
+    pub fn captures<'a>(&self, line: &'a str) -> Option<Vec<&'a str>> {
+        let fields = self.match_fields(line).ok()?;
+        Some(fields.into_iter().map(|(_, field)| field).collect())
+    }
+
+    /// Captured fields with their byte offsets, or the byte offset where the
+    /// line stops fitting the template.
+    fn match_fields<'a>(&self, line: &'a str) -> Result<Vec<(usize, &'a str)>, usize> {
+        let (first, rest) = self.literals.split_first().ok_or(0_usize)?;
+        let mut text = line.strip_prefix(first.as_str()).ok_or(0_usize)?;
+        let offset = |text: &str| line.len() - text.len();
+        let mut fields = Vec::with_capacity(self.field_count());
+
+        for (i, literal) in rest.iter().enumerate() {
+            if i + 1 == rest.len() {
+                let field = text.strip_suffix(literal.as_str()).ok_or(line.len())?;
+                fields.push((offset(text), field));
+                text = "";
+            } else if literal.is_empty() {
+                // two fields next to each other, the first one stays empty
+                fields.push((offset(text), ""));
+            } else {
+                let end = text.find(literal.as_str()).ok_or(offset(text))?;
+                fields.push((offset(text), &text[..end]));
+                text = &text[end + literal.len()..];
+            }
+        }
+
+        if text.is_empty() {
+            Ok(fields)
+        } else {
+            Err(offset(text))
+        }
+    }
+
+    /// Captures the fields and parses all of them into `T`.
+    ///
+    /// The pattern only sees one line, so errors are reported on line 1 with
+    /// the column of the offending field, see [`ParseError::at_line`].
+    pub fn parse<T>(&self, line: &str) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let column = |offset: usize| line[..offset].chars().count() + 1;
+        let fields = self.match_fields(line).map_err(|offset| {
+            ParseError::new(1, format!("line doesn't match the pattern '{}'", self))
+                .with_column(column(offset))
+        })?;
+        fields
+            .into_iter()
+            .map(|(offset, field)| {
+                field.trim().parse().map_err(|err| {
+                    ParseError::new(1, format!("couldn't parse '{field}': {err}"))
+                        .with_column(column(offset))
+                })
+            })
+            .collect()
+    }
+
+    /// Parses every non-blank line of the input with [`Pattern::parse`].
+    pub fn parse_lines<'a, T>(
+        &'a self,
+        input: &'a str,
+    ) -> impl Iterator<Item = Result<Vec<T>, ParseError>> + 'a
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(move |(i, line)| self.parse(line).map_err(|err| err.at_line(i + 1)))
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.literals.join("{}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraphs() {
+        let input = "1000\n2000\n\n4000\n\n\n5000\n6000\n";
+        let found: Vec<_> = paragraphs(input).collect();
+        assert_eq!(found, vec!["1000\n2000", "4000", "5000\n6000"]);
+
+        let input = "\n    [D]\n[N] [C]\n 1   2 \n\nmove 1 from 2 to 1";
+        let found: Vec<_> = paragraphs(input).collect();
+        assert_eq!(
+            found,
+            vec!["    [D]\n[N] [C]\n 1   2 ", "move 1 from 2 to 1"]
+        );
+
+        assert_eq!(paragraphs("").count(), 0);
+        assert_eq!(paragraphs("\n \n").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_lines_with() {
+        let numbers: Result<Vec<i32>, _> =
+            parse_lines_with("1\n-2\n\n3\n", |line| line.parse()).collect();
+        assert_eq!(numbers, Ok(vec![1, -2, 3]));
+
+        let numbers: Result<Vec<i32>, _> =
+            parse_lines_with("1\n\nx\n3", |line| line.parse::<i32>()).collect();
+        assert_eq!(numbers.unwrap_err().line, 3);
+    }
+
+    #[test]
+    fn test_integers() {
+        let found: Vec<i64> = integers("move 13 from 2 to -1").collect();
+        assert_eq!(found, vec![13, 2, -1]);
+
+        let found: Vec<u32> = integers("2-4,6-8").collect();
+        assert_eq!(found, vec![2, 4, 6, 8]);
+
+        let found: Vec<i32> = integers("x=-3, y=a-5 -").collect();
+        assert_eq!(found, vec![-3, 5]);
+
+        let found: Vec<u8> = integers("300 7").collect();
+        assert_eq!(found, vec![7]);
+    }
+
+    #[test]
+    fn test_read_lines_and_chars() {
+        let mut error = ReadError::default();
+        let lines: Vec<_> = read_lines("a b\n\nc\n".as_bytes(), &mut error).collect();
+        assert_eq!(lines, vec!["a b", "", "c"]);
+        assert_eq!(error.check(), Ok(()));
+
+        let mut error = ReadError::default();
+        let lines: Vec<_> = read_lines(&b"a\nb\xff\nc\n"[..], &mut error).collect();
+        assert_eq!(lines, vec!["a"]);
+        assert!(error.check().is_err());
+
+        let mut error = ReadError::default();
+        let chars: String = read_chars("zażółć\n".as_bytes(), &mut error).collect();
+        assert_eq!(chars, "zażółć\n");
+
+        let chars: String = read_chars(&b"a\xffb"[..], &mut error).collect();
+        assert_eq!(chars, "a\u{fffd}b");
+
+        // the bytes after a cut character aren't swallowed
+        let chars: String = read_chars(&b"\xc5ab\xe2\x82"[..], &mut error).collect();
+        assert_eq!(chars, "\u{fffd}ab\u{fffd}");
+        assert_eq!(error.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern::new("move {} from {} to {}");
+        assert_eq!(pattern.field_count(), 3);
+        assert_eq!(
+            pattern.captures("move 13 from 2 to 1"),
+            Some(vec!["13", "2", "1"])
+        );
+        assert_eq!(
+            pattern.parse::<usize>("move 13 from 2 to 1"),
+            Ok(vec![13, 2, 1])
+        );
+        assert_eq!(pattern.captures("move 13 from 2"), None);
+        let err = pattern.parse::<usize>("move x from 2 to 1").unwrap_err();
+        assert_eq!((err.line, err.column), (1, Some(6)));
+        let err = pattern.parse::<usize>("move 1 to 2").unwrap_err();
+        assert_eq!(err.column, Some(6));
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 6: line doesn't match the pattern 'move {} from {} to {}'"
+        );
+
+        let found: Result<Vec<Vec<usize>>, _> = pattern
+            .parse_lines("move 1 from 2 to 3\n\nmove 1 from 2 to ä\n")
+            .collect();
+        let err = found.unwrap_err();
+        assert_eq!((err.line, err.column), (3, Some(18)));
+
+        let pattern = Pattern::new("{}-{},{}-{}");
+        assert_eq!(pattern.parse::<u32>("2-4,6-8"), Ok(vec![2, 4, 6, 8]));
+    }
+}
@@ -0,0 +1,427 @@
+//! Solvers of other crates, loaded at runtime from shared libraries.
+//!
+//! A plugin is a `cdylib` that depends on this crate and lists its solvers
+//! with [`export_plugin!`](crate::export_plugin):
+//!
+//! ```ignore
+//! aoc2022::export_plugin! {
+//!     (1, "sorted", crate::day01::answer),
+//!     (3, "bitset", crate::day03::answer),
+//! }
+//! ```
+//!
+//! The macro exports the four functions of the interface below. Solving goes
+//! through the calling convention of `aoc_solve` in [`crate::ffi`], so
+//! answers and errors come back as the same C strings and codes, released by
+//! the plugin that allocated them. A plugin built for another
+//! [`ABI_VERSION`] is refused.
+//!
+//! Loading needs the `plugins` feature and only happens when asked for:
+//! `list`, `verify` and `compare` take `--plugins DIR`, the runner solves a day
+//! with a plugin's solver with `--variant NAME`. `plugins/sample.rs` is a
+//! plugin, built with `cargo build --example sample-plugin`.
+
+use crate::ffi;
+use crate::registry::{self, Solver};
+use std::ffi::{c_char, CStr};
+#[cfg(feature = "plugins")]
+use std::path::Path;
+
+/// Version of the plugin interface, bumped on every incompatible change.
+pub const ABI_VERSION: u32 = 1;
+
+/// Directory `compare` and `--variant` load the plugins from by default.
+pub const PLUGIN_DIR: &str = "plugins";
+
+/// A solver as listed by `aoc_plugin_solvers`, the variant is a static NUL
+/// terminated string of the plugin.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SolverInfo {
+    pub day: i32,
+    pub variant: *const c_char,
+}
+
+pub type AbiVersionFn = unsafe extern "C" fn() -> u32;
+pub type SolversFn = unsafe extern "C" fn(*mut SolverInfo, usize) -> usize;
+pub type SolveFn = unsafe extern "C" fn(usize, i32, *const u8, usize, *mut *mut c_char) -> i32;
+pub type FreeStringFn = unsafe extern "C" fn(*mut c_char);
+
+/// The functions a plugin exports.
+#[derive(Debug, Clone, Copy)]
+pub struct Exports {
+    pub abi_version: AbiVersionFn,
+    pub solvers: SolversFn,
+    pub solve: SolveFn,
+    pub free_string: FreeStringFn,
+}
+
+/// Lists the solvers of `export_plugin!` the way `aoc_days` lists the days.
+///
+/// # Safety
+///
+/// `solvers` has to be null or point to `capacity` writable entries.
+#[doc(hidden)]
+pub unsafe fn export_solvers(
+    exported: &[(i32, &'static str, registry::Answer)],
+    solvers: *mut SolverInfo,
+    capacity: usize,
+) -> usize {
+    if !solvers.is_null() {
+        for (i, (day, variant, _)) in exported.iter().take(capacity).enumerate() {
+            // SAFETY: the caller guarantees room for `capacity` entries
+            unsafe {
+                *solvers.add(i) = SolverInfo {
+                    day: *day,
+                    variant: variant.as_ptr().cast(),
+                }
+            };
+        }
+    }
+    exported.len()
+}
+
+/// Exports `(day, "variant", answer function)` solvers as a plugin, see the
+/// [module documentation](crate::plugin).
+#[macro_export]
+macro_rules! export_plugin {
+    ($(($day:expr, $variant:literal, $answer:path)),* $(,)?) => {
+        const AOC_PLUGIN_SOLVERS: &[(i32, &str, $crate::registry::Answer)] =
+            &[$(($day, concat!($variant, "\0"), $answer)),*];
+
+        #[no_mangle]
+        pub extern "C" fn aoc_plugin_abi_version() -> u32 {
+            $crate::plugin::ABI_VERSION
+        }
+
+        /// # Safety
+        ///
+        /// `solvers` has to be null or point to `capacity` writable entries.
+        #[no_mangle]
+        pub unsafe extern "C" fn aoc_plugin_solvers(
+            solvers: *mut $crate::plugin::SolverInfo,
+            capacity: usize,
+        ) -> usize {
+            unsafe { $crate::plugin::export_solvers(AOC_PLUGIN_SOLVERS, solvers, capacity) }
+        }
+
+        /// # Safety
+        ///
+        /// Same as `aoc_solve`, `index` counts the listed solvers.
+        #[no_mangle]
+        pub unsafe extern "C" fn aoc_plugin_solve(
+            index: usize,
+            part: i32,
+            input: *const u8,
+            input_len: usize,
+            result: *mut *mut ::std::ffi::c_char,
+        ) -> i32 {
+            let answer = AOC_PLUGIN_SOLVERS.get(index).map(|x| x.2);
+            unsafe { $crate::ffi::solve_with(answer, part, input, input_len, result) }
+        }
+
+        /// # Safety
+        ///
+        /// Same as `aoc_free_string`.
+        #[no_mangle]
+        pub unsafe extern "C" fn aoc_plugin_free_string(text: *mut ::std::ffi::c_char) {
+            unsafe { $crate::ffi::aoc_free_string(text) }
+        }
+    };
+}
+
+/// One solver of a loaded plugin.
+#[derive(Debug, Clone)]
+pub struct PluginSolver {
+    day: i32,
+    variant: String,
+    index: usize,
+    exports: Exports,
+    /// File name of the plugin.
+    pub origin: String,
+    /// Keeps the library loaded as long as its functions can be called.
+    #[cfg(feature = "plugins")]
+    _library: Option<std::sync::Arc<libloading::Library>>,
+}
+
+impl Solver for PluginSolver {
+    fn day(&self) -> i32 {
+        self.day
+    }
+
+    fn variant(&self) -> &str {
+        &self.variant
+    }
+
+    fn solve(&self, input: String, is_part_one: bool) -> Result<String, String> {
+        let mut result = std::ptr::null_mut();
+        let part = if is_part_one { 1 } else { 2 };
+        // SAFETY: the input and the result pointer are valid for the call
+        let code = unsafe {
+            (self.exports.solve)(self.index, part, input.as_ptr(), input.len(), &mut result)
+        };
+        let text = (!result.is_null()).then(|| {
+            // SAFETY: the plugin returns a NUL terminated string, released by it
+            let text = unsafe { CStr::from_ptr(result) }
+                .to_string_lossy()
+                .into_owned();
+            unsafe { (self.exports.free_string)(result) };
+            text
+        });
+        match (code, text) {
+            (ffi::AOC_OK, Some(answer)) => Ok(answer),
+            (ffi::AOC_SOLVE_FAILED, Some(message)) => Err(message),
+            (ffi::AOC_PANIC, _) => Err(format!("the '{}' solver panicked", self.variant)),
+            (code, _) => Err(format!(
+                "the '{}' solver failed with code {code}",
+                self.variant
+            )),
+        }
+    }
+}
+
+/// The solvers behind the exported functions of a plugin.
+///
+/// # Safety
+///
+/// The functions have to implement the interface of this module and stay
+/// callable as long as the solvers live.
+pub unsafe fn solvers_of(exports: Exports, origin: &str) -> Result<Vec<PluginSolver>, String> {
+    // SAFETY: the caller guarantees the functions
+    let version = unsafe { (exports.abi_version)() };
+    if version != ABI_VERSION {
+        return Err(format!(
+            "'{origin}' was built for the plugin interface {version}, the runner has {ABI_VERSION}"
+        ));
+    }
+    let count = unsafe { (exports.solvers)(std::ptr::null_mut(), 0) };
+    let mut infos = vec![
+        SolverInfo {
+            day: 0,
+            variant: std::ptr::null(),
+        };
+        count
+    ];
+    let count = count.min(unsafe { (exports.solvers)(infos.as_mut_ptr(), infos.len()) });
+    infos
+        .iter()
+        .take(count)
+        .enumerate()
+        .map(|(index, info)| {
+            if info.variant.is_null() {
+                return Err(format!(
+                    "'{origin}' has a solver of day {} without a name",
+                    info.day
+                ));
+            }
+            // SAFETY: the variants are static strings of the plugin
+            let variant = unsafe { CStr::from_ptr(info.variant) }
+                .to_string_lossy()
+                .into_owned();
+            Ok(PluginSolver {
+                day: info.day,
+                variant,
+                index,
+                exports,
+                origin: origin.into(),
+                #[cfg(feature = "plugins")]
+                _library: None,
+            })
+        })
+        .collect()
+}
+
+/// Loads the solvers of one shared library.
+#[cfg(feature = "plugins")]
+pub fn load(path: &Path) -> Result<Vec<PluginSolver>, String> {
+    use libloading::{Library, Symbol};
+    use std::sync::Arc;
+
+    let origin = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |x| x.to_string_lossy().into_owned(),
+    );
+    // SAFETY: loading runs the initialisers of the library, plugins are
+    // trusted as much as the runner itself
+    let library = unsafe { Library::new(path) }
+        .map_err(|e| format!("couldn't load '{}': {e}", path.display()))?;
+    let symbol = |name: &str| -> Result<*const (), String> {
+        // SAFETY: only the address is taken, it is cast to the right type below
+        let symbol: Symbol<*const ()> = unsafe { library.get(name.as_bytes()) }
+            .map_err(|_| format!("'{origin}' isn't a plugin, '{name}' is missing"))?;
+        Ok(*symbol)
+    };
+    // SAFETY: the names are those of `export_plugin!`, the types come with them
+    let exports = unsafe {
+        Exports {
+            abi_version: std::mem::transmute::<*const (), AbiVersionFn>(symbol(
+                "aoc_plugin_abi_version",
+            )?),
+            solvers: std::mem::transmute::<*const (), SolversFn>(symbol("aoc_plugin_solvers")?),
+            solve: std::mem::transmute::<*const (), SolveFn>(symbol("aoc_plugin_solve")?),
+            free_string: std::mem::transmute::<*const (), FreeStringFn>(symbol(
+                "aoc_plugin_free_string",
+            )?),
+        }
+    };
+    let library = Arc::new(library);
+    // SAFETY: every solver keeps the library loaded
+    let mut solvers = unsafe { solvers_of(exports, &origin) }?;
+    for solver in &mut solvers {
+        solver._library = Some(library.clone());
+    }
+    Ok(solvers)
+}
+
+/// Loads every shared library of the directory, a missing directory has no
+/// plugins. A variant that is already taken for its day is skipped with a
+/// warning, the built-in days take [`registry::BUILTIN`].
+#[cfg(feature = "plugins")]
+pub fn load_dir(dir: &Path) -> (Vec<PluginSolver>, Vec<String>) {
+    let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|x| Some(x.ok()?.path()))
+        .filter(|x| {
+            x.extension()
+                .is_some_and(|x| x == std::env::consts::DLL_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    let mut solvers: Vec<PluginSolver> = Vec::new();
+    let mut warnings = Vec::new();
+    for path in paths {
+        match load(&path) {
+            Ok(loaded) => {
+                for solver in loaded {
+                    let taken = solver.variant == registry::BUILTIN
+                        || solvers
+                            .iter()
+                            .any(|x| x.day == solver.day && x.variant == solver.variant);
+                    if taken {
+                        warnings.push(format!(
+                            "'{}' skipped, day {} already has a '{}' solver",
+                            solver.origin, solver.day, solver.variant
+                        ));
+                    } else {
+                        solvers.push(solver);
+                    }
+                }
+            }
+            Err(err) => warnings.push(err),
+        }
+    }
+    (solvers, warnings)
+}
+
+/// The built-in solver of the day if it's compiled in, then the plugins'.
+pub fn solvers_of_day(day: i32, plugins: &[PluginSolver]) -> Vec<&dyn Solver> {
+    let builtin = registry::find(day).map(|x| x as &dyn Solver);
+    builtin
+        .into_iter()
+        .chain(
+            plugins
+                .iter()
+                .filter(|x| x.day == day)
+                .map(|x| x as &dyn Solver),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reversed(input: String, is_part_one: bool) -> Result<String, String> {
+        if input.is_empty() {
+            return Err("empty input".into());
+        }
+        let reversed: String = input.chars().rev().collect();
+        Ok(if is_part_one {
+            reversed
+        } else {
+            reversed.to_uppercase()
+        })
+    }
+
+    fn panics(_: String, _: bool) -> Result<String, String> {
+        panic!("unreachable in a well-behaved plugin")
+    }
+
+    mod exported {
+        crate::export_plugin! {
+            (1, "reversed", super::reversed),
+            (2, "panics", super::panics),
+        }
+    }
+
+    fn exports() -> Exports {
+        Exports {
+            abi_version: exported::aoc_plugin_abi_version,
+            solvers: exported::aoc_plugin_solvers,
+            solve: exported::aoc_plugin_solve,
+            free_string: exported::aoc_plugin_free_string,
+        }
+    }
+
+    #[test]
+    fn test_exported_solvers() {
+        let solvers = unsafe { solvers_of(exports(), "test") }.unwrap();
+        let listed: Vec<(i32, &str)> = solvers.iter().map(|x| (x.day(), x.variant())).collect();
+        assert_eq!(listed, [(1, "reversed"), (2, "panics")]);
+        assert_eq!(solvers[0].solve("abc".into(), true), Ok("cba".into()));
+        assert_eq!(solvers[0].solve("abc".into(), false), Ok("CBA".into()));
+        assert_eq!(
+            solvers[0].solve(String::new(), true),
+            Err("empty input".into())
+        );
+        assert!(solvers[1]
+            .solve("abc".into(), true)
+            .unwrap_err()
+            .contains("panicked"));
+    }
+
+    #[test]
+    fn test_abi_version() {
+        extern "C" fn future_version() -> u32 {
+            ABI_VERSION + 1
+        }
+        let exports = Exports {
+            abi_version: future_version,
+            ..exports()
+        };
+        let err = unsafe { solvers_of(exports, "libnew.so") }.unwrap_err();
+        assert!(err.contains("libnew.so"));
+    }
+
+    #[test]
+    fn test_solvers_of_day() {
+        let plugins = unsafe { solvers_of(exports(), "test") }.unwrap();
+        let variants: Vec<&str> = solvers_of_day(1, &plugins)
+            .iter()
+            .map(|x| x.variant())
+            .collect();
+        let expected: &[&str] = if cfg!(feature = "day01") {
+            &[registry::BUILTIN, "reversed"]
+        } else {
+            &["reversed"]
+        };
+        assert_eq!(variants, expected);
+        assert!(solvers_of_day(25, &plugins).is_empty());
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_load_errors() {
+        let dir = crate::testing::temp_dir("plugins-load-errors");
+        assert_eq!(load_dir(&dir.join("missing")).0.len(), 0);
+        let fake = dir.join(format!("libfake.{}", std::env::consts::DLL_EXTENSION));
+        std::fs::write(&fake, "not a library").unwrap();
+        let (solvers, warnings) = load_dir(&dir);
+        assert!(solvers.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("libfake"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
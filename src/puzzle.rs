@@ -0,0 +1,95 @@
+//! Puzzle descriptions saved from adventofcode.com, behind the `read` command.
+//!
+//! A page saved before part one is solved only has the first part, the
+//! second one shows up in the same page once the first answer is accepted.
+
+use crate::html::{self, Element};
+
+/// Text of both parts of the puzzle and of the answers already given.
+pub fn render_page(page: &str, width: usize, color: bool) -> Result<String, String> {
+    let articles: Vec<Element> = html::elements(page, "article")
+        .into_iter()
+        .filter(|x| x.attributes.contains("day-desc"))
+        .collect();
+    if articles.is_empty() {
+        return Err("no puzzle description found in the page".into());
+    }
+    let answers: Vec<Element> = html::elements(page, "p")
+        .into_iter()
+        .filter(|x| html::text(x.inner).starts_with("Your puzzle answer was"))
+        .collect();
+
+    let mut text = String::new();
+    for (i, article) in articles.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        text.push_str(&html::render(article.inner, width, color));
+        if let Some(answer) = answers.get(i) {
+            text.push('\n');
+            text.push_str(&html::render(answer.inner, width, color));
+        }
+    }
+    if articles.len() == 1 {
+        text.push_str("\n(part two shows up once part one is solved, save the page again then)\n");
+    }
+    Ok(text)
+}
+
+/// The rendered text as stored in the cache, headed by the settings it was
+/// rendered with.
+pub fn cache_entry(text: &str, width: usize, color: bool) -> String {
+    format!("{}{text}", cache_header(width, color))
+}
+
+/// Text of a cache entry, when it was rendered with the same settings.
+pub fn cached_text(entry: &str, width: usize, color: bool) -> Option<&str> {
+    entry.strip_prefix(&cache_header(width, color))
+}
+
+/// Text of a cache entry whatever it was rendered with.
+pub fn any_cached_text(entry: &str) -> &str {
+    entry.split_once('\n').map_or("", |(_, text)| text)
+}
+
+fn cache_header(width: usize, color: bool) -> String {
+    let colors = if color { "with" } else { "without" };
+    format!("rendered {width} columns wide {colors} colors\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PART_ONE: &str =
+        "<main><article class=\"day-desc\"><h2>--- Day 2: Rock Paper Scissors ---</h2>\
+        <p>What would your total score be?</p></article>\n";
+    const PART_TWO: &str = "<p>Your puzzle answer was <code>15</code>.</p>\
+        <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>Now what?</p></article>\
+        <p>Your puzzle answer was <code>12</code>.</p></main>";
+
+    #[test]
+    fn test_render_page() {
+        assert_eq!(
+            render_page(PART_ONE, 80, false).unwrap(),
+            "--- Day 2: Rock Paper Scissors ---\n\nWhat would your total score be?\n\n\
+             (part two shows up once part one is solved, save the page again then)\n"
+        );
+        assert_eq!(
+            render_page(&format!("{PART_ONE}{PART_TWO}"), 80, false).unwrap(),
+            "--- Day 2: Rock Paper Scissors ---\n\nWhat would your total score be?\n\n\
+             Your puzzle answer was 15.\n\n--- Part Two ---\n\nNow what?\n\n\
+             Your puzzle answer was 12.\n"
+        );
+        assert!(render_page("<p>login</p>", 80, false).is_err());
+    }
+
+    #[test]
+    fn test_cache_entry() {
+        let entry = cache_entry("Now what?\n", 80, false);
+        assert_eq!(cached_text(&entry, 80, false), Some("Now what?\n"));
+        assert_eq!(cached_text(&entry, 100, false), None);
+        assert_eq!(cached_text(&entry, 80, true), None);
+        assert_eq!(any_cached_text(&entry), "Now what?\n");
+    }
+}
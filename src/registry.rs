@@ -0,0 +1,180 @@
+//! Days the runner knows about.
+//!
+//! Every day is behind a cargo feature named after its module, `all-days`
+//! turns all of them on. Only the compiled days end up in [`DAYS`], the days
+//! that have a source module but were left out are still listed by
+//! [`source_days`] so that the runner can tell them apart from missing ones.
+
+use crate::visualize::Visualize;
+use std::fmt::Debug;
+use std::io::BufRead;
+
+#[allow(unused_imports)]
+use crate::*;
+
+pub type Answer = fn(String, bool) -> Result<String, String>;
+pub type StreamAnswer = fn(Box<dyn BufRead>, bool) -> Result<String, String>;
+pub type ParseAny = fn(&str) -> Result<Box<dyn Parsed>, String>;
+
+/// A day's parsed input behind a common interface, used by the `repl` command
+/// to inspect the input without knowing its type.
+pub trait Parsed: Debug {
+    fn parse(input: &str) -> Result<Self, String>
+    where
+        Self: Sized;
+
+    fn part(&self, is_part_one: bool) -> Result<String, String>;
+
+    /// Human readable form of the whole input.
+    fn show(&self) -> String {
+        format!("{self:#?}")
+    }
+
+    /// Answers a day-specific command, `None` if the command isn't known.
+    fn query(&self, _command: &str, _args: &[&str]) -> Option<Result<String, String>> {
+        None
+    }
+
+    /// Usage lines of the commands understood by [`Parsed::query`].
+    fn queries(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Something that solves both parts of one day, the built-in days as well as
+/// the variants loaded from plugins, see [`crate::plugin`].
+pub trait Solver {
+    fn day(&self) -> i32;
+
+    /// Tells apart the solvers of the same day, [`BUILTIN`] for this crate's.
+    fn variant(&self) -> &str;
+
+    fn solve(&self, input: String, is_part_one: bool) -> Result<String, String>;
+}
+
+/// Variant name of the days of this crate.
+pub const BUILTIN: &str = "builtin";
+
+impl Solver for Day {
+    fn day(&self) -> i32 {
+        self.number
+    }
+
+    fn variant(&self) -> &str {
+        BUILTIN
+    }
+
+    fn solve(&self, input: String, is_part_one: bool) -> Result<String, String> {
+        (self.answer)(input, is_part_one)
+    }
+}
+
+fn parse_any<T: Parsed + 'static>(input: &str) -> Result<Box<dyn Parsed>, String> {
+    Ok(Box::new(T::parse(input)?))
+}
+
+pub struct Day {
+    pub number: i32,
+    pub answer: Answer,
+    /// Set for the days that can read their input line by line instead of loading it whole.
+    pub answer_stream: Option<StreamAnswer>,
+    /// Set for the days that can animate their solution, see [`crate::visualize`].
+    pub visualize: Option<Visualize>,
+    /// Set for the days whose parsed input implements [`Parsed`].
+    pub parse: Option<ParseAny>,
+}
+
+impl Day {
+    pub const fn new(number: i32, answer: Answer) -> Self {
+        Day {
+            number,
+            answer,
+            answer_stream: None,
+            visualize: None,
+            parse: None,
+        }
+    }
+
+    pub const fn streaming(number: i32, answer: Answer, answer_stream: StreamAnswer) -> Self {
+        Day {
+            number,
+            answer,
+            answer_stream: Some(answer_stream),
+            visualize: None,
+            parse: None,
+        }
+    }
+
+    pub const fn with_visualize(mut self, visualize: Visualize) -> Self {
+        self.visualize = Some(visualize);
+        self
+    }
+
+    pub const fn with_parsed<T: Parsed + 'static>(mut self) -> Self {
+        self.parse = Some(parse_any::<T>);
+        self
+    }
+
+    /// Name of the module and of the cargo feature of the day.
+    pub fn name(&self) -> String {
+        day_name(self.number)
+    }
+}
+
+/// `--init` inserts new days here, every entry starts with its `cfg` line.
+pub static DAYS: &[Day] = &[
+    #[cfg(feature = "day01")]
+    Day::streaming(1, day01::answer, day01::answer_stream).with_parsed::<day01::Inventory>(),
+    #[cfg(feature = "day02")]
+    Day::streaming(2, day02::answer, day02::answer_stream).with_parsed::<day02::StrategyGuide>(),
+    #[cfg(feature = "day03")]
+    Day::streaming(3, day03::answer, day03::answer_stream).with_parsed::<day03::Rucksacks>(),
+    #[cfg(feature = "day04")]
+    Day::streaming(4, day04::answer, day04::answer_stream).with_parsed::<day04::Assignments>(),
+    #[cfg(feature = "day05")]
+    Day::new(5, day05::answer)
+        .with_visualize(day05::visualize)
+        .with_parsed::<day05::Rearrangement>(),
+    #[cfg(feature = "day06")]
+    Day::streaming(6, day06::answer, day06::answer_stream).with_parsed::<day06::Signal>(),
+    #[cfg(feature = "day07")]
+    Day::new(7, day07::answer).with_parsed::<day07::FileSystem>(),
+    #[cfg(feature = "day08")]
+    Day::new(8, day08::answer).with_parsed::<day08::Forest>(),
+    #[cfg(feature = "day09")]
+    Day::streaming(9, day09::answer, day09::answer_stream)
+        .with_visualize(day09::visualize)
+        .with_parsed::<day09::Motions>(),
+];
+
+pub fn day_name(day: i32) -> String {
+    format!("day{day:0>2}")
+}
+
+pub fn find(day: i32) -> Option<&'static Day> {
+    DAYS.iter().find(|x| x.number == day)
+}
+
+/// Days with a `src/dayNN/mod.rs`, whether they were compiled in or not.
+pub fn source_days() -> &'static [i32] {
+    include!(concat!(env!("OUT_DIR"), "/source_days.rs"))
+}
+
+/// The day has a source module but its feature is turned off.
+pub fn is_compiled_out(day: i32) -> bool {
+    find(day).is_none() && source_days().contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted_and_compiled() {
+        assert!(DAYS.windows(2).all(|x| x[0].number < x[1].number));
+        for day in DAYS {
+            assert!(source_days().contains(&day.number), "{}", day.name());
+            assert!(!is_compiled_out(day.number));
+        }
+    }
+}
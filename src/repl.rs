@@ -0,0 +1,155 @@
+//! Interactive inspection of a day's parsed input, behind the `repl` command.
+//!
+//! The input is parsed once, then every line read is a command: run a part,
+//! print the parsed input, ask one of the day's own queries or reload the
+//! input after editing it.
+
+use crate::normalize::normalize;
+use crate::registry::{Day, Parsed};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const HELP: &[&str] = &[
+    "1, part1: run the first part",
+    "2, part2: run the second part",
+    "print: show the parsed input",
+    "reload: read and parse the input file again",
+    "help: show this help",
+    "quit, exit: leave the repl",
+];
+
+pub struct Repl<'a> {
+    day: &'a Day,
+    path: PathBuf,
+    parsed: Option<Box<dyn Parsed>>,
+}
+
+impl<'a> Repl<'a> {
+    /// Fails if the day doesn't expose its parsed input.
+    pub fn new(day: &'a Day, path: &Path) -> Result<Self, String> {
+        if day.parse.is_none() {
+            return Err(format!(
+                "day {} doesn't expose its parsed input",
+                day.number
+            ));
+        }
+        Ok(Repl {
+            day,
+            path: path.to_path_buf(),
+            parsed: None,
+        })
+    }
+
+    /// Reads and parses the input file, a failure keeps the previous input.
+    pub fn reload(&mut self) -> Result<String, String> {
+        let text = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("couldn't read '{}': {e}", self.path.display()))?;
+        let (text, changes) = normalize(&text);
+        let parse = self.day.parse.expect("checked in Repl::new");
+        self.parsed = Some(parse(&text)?);
+        if changes.is_empty() {
+            Ok(format!("loaded '{}'", self.path.display()))
+        } else {
+            Ok(format!(
+                "loaded '{}', normalised: {changes}",
+                self.path.display()
+            ))
+        }
+    }
+
+    /// Runs one command, `None` means the session is over.
+    pub fn execute(&mut self, line: &str) -> Option<Result<String, String>> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Some(Ok(String::new()));
+        };
+        if let "quit" | "exit" = command {
+            return None;
+        }
+        if command == "reload" {
+            return Some(self.reload());
+        }
+        let Some(parsed) = &self.parsed else {
+            return Some(Err("no input is loaded, fix it and 'reload'".into()));
+        };
+        let result = match command {
+            "help" => Ok(HELP
+                .iter()
+                .chain(parsed.queries())
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "1" | "part1" => parsed.part(true),
+            "2" | "part2" => parsed.part(false),
+            "print" => Ok(parsed.show()),
+            _ => parsed
+                .query(command, args)
+                .unwrap_or_else(|| Err(format!("unknown command '{line}', try 'help'"))),
+        };
+        Some(result)
+    }
+
+    /// Reads commands until the end of `input` or `quit`.
+    pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        let prompt = format!("day{:0>2}> ", self.day.number);
+        match self.reload() {
+            Ok(message) => writeln!(out, "{message}")?,
+            Err(err) => writeln!(out, "WARNING {err}")?,
+        }
+        write!(out, "{prompt}")?;
+        out.flush()?;
+        for line in input.lines() {
+            match self.execute(&line?) {
+                None => return Ok(()),
+                Some(Ok(text)) if text.is_empty() => {}
+                Some(Ok(text)) => writeln!(out, "{text}")?,
+                Some(Err(err)) => writeln!(out, "WARNING {err}")?,
+            }
+            write!(out, "{prompt}")?;
+            out.flush()?;
+        }
+        writeln!(out)
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "day07", feature = "day08"))]
+mod tests {
+    use super::*;
+    use crate::examples::example_input_path;
+    use crate::registry;
+
+    fn session(day: i32, commands: &str) -> String {
+        let day = registry::find(day).unwrap();
+        let mut repl = Repl::new(day, &example_input_path(day.number, "1")).unwrap();
+        let mut out = Vec::new();
+        repl.run(commands.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_day07_session() {
+        let out = session(
+            7,
+            "size /a/e\nsize /a\nls /a/e\n1\nsize /x\nfoo\nquit\nprint\n",
+        );
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "loaded 'examples/day07/1.txt'");
+        assert_eq!(lines[1], "day07> 584");
+        assert_eq!(lines[2], "day07> 94853");
+        assert_eq!(lines[3], "day07> 584 i");
+        assert_eq!(lines[4], "day07> 95437");
+        assert_eq!(lines[5], "day07> WARNING there is no directory '/x'");
+        assert_eq!(lines[6], "day07> WARNING unknown command 'foo', try 'help'");
+        // nothing runs after quit
+        assert_eq!(lines.len(), 8);
+    }
+
+    #[test]
+    fn test_day08_session() {
+        let out = session(8, "tree 2 3\ntree 5 0\nprint\n");
+        assert!(out.contains("day08> height 5, visible: true, scenic score: 8\n"));
+        assert!(out.contains("WARNING there is no tree at (5, 0)"));
+        assert!(out.contains("30373\n25512\n"));
+    }
+}
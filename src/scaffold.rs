@@ -0,0 +1,897 @@
+//! Scaffolding of the days behind `--init`, `deinit` and `mv`, the sources of
+//! the runner are rewritten in place.
+//!
+//! `deinit` and `mv` plan their [`Change`]s first, so a dry run can print them
+//! and a failing step can undo the ones already applied.
+
+use crate::examples;
+use regex::Regex;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const REGISTRY_FILE: &str = "src/registry.rs";
+const MANIFEST_FILE: &str = "Cargo.toml";
+const LIB_FILE: &str = "src/lib.rs";
+const ARCHIVE_DIR: &str = "archive";
+
+/// Creates the input and the sources of a new day and registers it.
+pub fn init_day(day: i32, input_dir: &Path) {
+    let input_dir_exists = input_dir.is_dir();
+    let input_file_path = input_dir.join(format!("day{day:0>2}.txt"));
+    let input_file_exists = input_file_path.is_file();
+
+    let day_src_dir = format!("src/day{day:0>2}");
+    let day_src_file = format!("{day_src_dir}/mod.rs");
+    let src_dir_exists = Path::new(&day_src_dir).is_dir();
+    let src_file_exists = Path::new(&day_src_file).is_file();
+
+    if !input_dir_exists {
+        if let Err(e) = std::fs::create_dir(input_dir) {
+            println!("WARNING failed to create input directory");
+            println!("{e}");
+        } else {
+            println!("created input directory at: 'HOME/{}'", input_dir.display());
+        }
+    }
+
+    if !input_file_exists {
+        if let Err(e) = std::fs::File::create(&input_file_path) {
+            println!("WARNING failed to create input file");
+            println!("{e}");
+        } else {
+            println!(
+                "created input file at: 'HOME/{}'",
+                input_file_path.display()
+            )
+        }
+    }
+
+    if !src_dir_exists {
+        if let Err(e) = std::fs::create_dir(&day_src_dir) {
+            println!("WARNING failed to create src directory");
+            println!("{e}");
+        } else {
+            println!("created src directory at: 'HOME/{day_src_dir}'");
+        }
+    }
+
+    if !src_file_exists {
+        match std::fs::File::create(&day_src_file) {
+            Err(e) => {
+                println!("WARNING failed to create src file");
+                println!("{e}");
+            }
+            Ok(mut file) => {
+                println!("created src file at: 'HOME/{day_src_file}'");
+                if file.write_all(SRC_TEMPLATE).is_err() {
+                    println!("WARNING failed to write day snippet");
+                }
+            }
+        }
+    }
+
+    let registry_text = include_str!("registry.rs");
+    let manifest_text = include_str!("../Cargo.toml");
+    let lib_text = include_str!("lib.rs");
+
+    let Some(new_registry) = register_day(registry_text, day) else {
+        return;
+    };
+    let Some(new_manifest) = add_day_feature(manifest_text, day) else {
+        println!("WARNING couldn't find the features of the days in Cargo.toml");
+        return;
+    };
+    let new_lib = format!("{lib_text}#[cfg(feature = \"day{day:0>2}\")]\npub mod day{day:0>2};\n");
+
+    // written one by one, the ones already written are restored if a later one fails
+    let updates = [
+        (REGISTRY_FILE, registry_text, new_registry),
+        (MANIFEST_FILE, manifest_text, new_manifest),
+        (LIB_FILE, lib_text, new_lib),
+    ];
+    for (i, (path, _, new_text)) in updates.iter().enumerate() {
+        if let Err(e) = std::fs::write(path, new_text) {
+            println!("WARNING couldn't update {path}");
+            println!("{e}");
+            for (path, old_text, _) in &updates[..i] {
+                if std::fs::write(path, old_text).is_err() {
+                    println!("something went terribly wrong, I am sorry");
+                    println!("'{path}' may be corrupted");
+                }
+            }
+            return;
+        }
+    }
+
+    println!("I think init has been successful");
+    println!(
+        "remember to pase the input at: {}",
+        input_file_path.display()
+    )
+}
+
+/// Inserts the entry of the day into `DAYS`, keeping the days sorted.
+fn register_day(registry_text: &str, day: i32) -> Option<String> {
+    let re_entry = Regex::new(r"^ *Day::(new|streaming)\((\d+),").unwrap();
+
+    let lines: Vec<&str> = registry_text.lines().collect();
+    let mut idx_to_insert = None;
+    for (i, line) in lines.iter().enumerate() {
+        let Some(captures) = re_entry.captures(line) else {
+            continue;
+        };
+        if let Ok(day_nr) = captures[2].parse::<i32>() {
+            if day_nr == day {
+                println!("day sollution was found in source code");
+                return None;
+            } else if day_nr > day {
+                // before the cfg line of the next day
+                idx_to_insert = Some(i - 1);
+                break;
+            }
+            // after the entry, it may continue on the next lines
+            idx_to_insert = lines
+                .iter()
+                .skip(i + 1)
+                .position(|x| x.trim_start().starts_with("#[cfg(") || x.trim() == "];")
+                .map(|x| x + i + 1);
+        }
+    }
+
+    let Some(insert_line_nr) = idx_to_insert else {
+        println!("WARNING couldn't find the place to insert new function call");
+        return None;
+    };
+
+    let mut lines: Vec<String> = lines.into_iter().map(String::from).collect();
+    lines.insert(
+        insert_line_nr,
+        format!("    Day::new({day}, day{day:0>2}::answer).with_parsed::<day{day:0>2}::Input>(),"),
+    );
+    lines.insert(
+        insert_line_nr,
+        format!("    #[cfg(feature = \"day{day:0>2}\")]"),
+    );
+    Some(lines.join("\n") + "\n")
+}
+
+/// Adds a `dayNN` feature and turns it on in `all-days`.
+fn add_day_feature(manifest_text: &str, day: i32) -> Option<String> {
+    let re_feature = Regex::new(r"^day(\d+) = ").unwrap();
+    let re_day = Regex::new(r"day(\d+)").unwrap();
+    let name = format!("day{day:0>2}");
+
+    let mut lines: Vec<String> = manifest_text.lines().map(String::from).collect();
+    let all_days = lines.iter().position(|x| x.starts_with("all-days = "))?;
+    let mut days: Vec<i32> = re_day
+        .captures_iter(&lines[all_days])
+        .flat_map(|x| x[1].parse().ok())
+        .collect();
+    days.push(day);
+    days.sort();
+    days.dedup();
+    let days: Vec<String> = days.iter().map(|x| format!("\"day{x:0>2}\"")).collect();
+    lines[all_days] = format!("all-days = [{}]", days.join(", "));
+
+    let mut idx_to_insert = None;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(captures) = re_feature.captures(line) {
+            match captures[1].parse::<i32>() {
+                Ok(day_nr) if day_nr == day => return Some(lines.join("\n") + "\n"),
+                Ok(day_nr) if day_nr > day => {
+                    idx_to_insert = Some(i);
+                    break;
+                }
+                _ => idx_to_insert = Some(i + 1),
+            }
+        }
+    }
+    lines.insert(idx_to_insert?, format!("{name} = []"));
+    Some(lines.join("\n") + "\n")
+}
+
+/// A step of `deinit` or `mv`, every one can be undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    Write {
+        path: PathBuf,
+        old: String,
+        new: String,
+    },
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// A single level, [`create_dirs`] plans one for every missing parent.
+    CreateDir(PathBuf),
+}
+
+impl Change {
+    fn apply(&self) -> std::io::Result<()> {
+        match self {
+            Change::Write { path, new, .. } => std::fs::write(path, new),
+            Change::Rename { from, to } => std::fs::rename(from, to),
+            Change::CreateDir(dir) => std::fs::create_dir(dir),
+        }
+    }
+
+    fn undo(&self) -> std::io::Result<()> {
+        match self {
+            Change::Write { path, old, .. } => std::fs::write(path, old),
+            Change::Rename { from, to } => std::fs::rename(to, from),
+            Change::CreateDir(dir) => std::fs::remove_dir(dir),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Change::Write { path, old, new } => {
+                let mut text = format!("rewrite '{}'", path.display());
+                let old_lines: Vec<&str> = old.lines().collect();
+                let new_lines: Vec<&str> = new.lines().collect();
+                for line in old_lines.iter().filter(|x| !new_lines.contains(x)) {
+                    text.push_str(&format!("\n    - {line}"));
+                }
+                for line in new_lines.iter().filter(|x| !old_lines.contains(x)) {
+                    text.push_str(&format!("\n    + {line}"));
+                }
+                text
+            }
+            Change::Rename { from, to } => {
+                format!("move '{}' to '{}'", from.display(), to.display())
+            }
+            Change::CreateDir(dir) => format!("create directory '{}'", dir.display()),
+        }
+    }
+}
+
+/// Creates the directories and their missing parents, one level per change
+/// so that each is removed again on a rollback.
+fn create_dirs(dirs: impl IntoIterator<Item = PathBuf>) -> Vec<Change> {
+    let mut missing = std::collections::BTreeSet::new();
+    for dir in dirs {
+        missing.extend(
+            dir.ancestors()
+                .take_while(|x| !x.as_os_str().is_empty() && !x.exists())
+                .map(Path::to_path_buf),
+        );
+    }
+    // a parent sorts before its children
+    missing.into_iter().map(Change::CreateDir).collect()
+}
+
+/// Prints the changes for a dry run, otherwise applies them one by one and
+/// undoes the applied ones if a later one fails.
+fn apply_changes(changes: &[Change], dry_run: bool) -> bool {
+    if dry_run {
+        println!("dry run, nothing is changed");
+        for change in changes {
+            println!("would {}", change.describe());
+        }
+        return true;
+    }
+    for (i, change) in changes.iter().enumerate() {
+        if let Err(e) = change.apply() {
+            println!(
+                "WARNING couldn't {}",
+                change.describe().lines().next().unwrap_or_default()
+            );
+            println!("{e}");
+            for change in changes[..i].iter().rev() {
+                if change.undo().is_err() {
+                    println!("something went terribly wrong, I am sorry");
+                    println!(
+                        "couldn't undo: {}",
+                        change.describe().lines().next().unwrap_or_default()
+                    );
+                }
+            }
+            return false;
+        }
+        println!("{}", change.describe().lines().next().unwrap_or_default());
+    }
+    true
+}
+
+/// Files of the day outside of `src`: its examples, its inputs and everything
+/// kept next to them.
+fn day_files(day: i32, input_dir: &Path) -> Vec<PathBuf> {
+    let name = format!("day{day:0>2}");
+    let mut files = vec![examples::example_dir(day)];
+    if let Ok(entries) = std::fs::read_dir(input_dir) {
+        let mut inputs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|x| x.path())
+            .filter(|x| {
+                let file_name = x.file_name().unwrap_or_default().to_string_lossy();
+                file_name == name || file_name.starts_with(&format!("{name}."))
+            })
+            .collect();
+        inputs.sort();
+        files.extend(inputs);
+    }
+    files.retain(|x| x.exists());
+    files
+}
+
+/// The rewrites of the registry, the manifest and the library root.
+fn rewrite_day_registration(
+    update: impl Fn(&str, &str, &str) -> Option<(String, String, String)>,
+) -> Option<Vec<Change>> {
+    let read = |path: &str| match std::fs::read_to_string(path) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            println!("WARNING couldn't read '{path}'");
+            println!("{e}");
+            None
+        }
+    };
+    let registry_text = read(REGISTRY_FILE)?;
+    let manifest_text = read(MANIFEST_FILE)?;
+    let lib_text = read(LIB_FILE)?;
+    let (new_registry, new_manifest, new_lib) = update(&registry_text, &manifest_text, &lib_text)?;
+    let changes = [
+        (REGISTRY_FILE, registry_text, new_registry),
+        (MANIFEST_FILE, manifest_text, new_manifest),
+        (LIB_FILE, lib_text, new_lib),
+    ];
+    Some(
+        changes
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .map(|(path, old, new)| Change::Write {
+                path: path.into(),
+                old,
+                new,
+            })
+            .collect(),
+    )
+}
+
+/// Warns about the files that still mention the day after the changes.
+fn warn_about_mentions(day: i32, changes: &[Change]) {
+    let re_name = Regex::new(&format!(r"\bday{day:0>2}\b")).unwrap();
+    let rewritten: Vec<&PathBuf> = changes
+        .iter()
+        .filter_map(|x| match x {
+            Change::Write { path, .. } => Some(path),
+            _ => None,
+        })
+        .collect();
+    let mut stack = vec![PathBuf::from("src"), PathBuf::from("tests")];
+    let mut mentions = Vec::new();
+    while let Some(path) = stack.pop() {
+        if path.is_dir() {
+            if path == Path::new("src").join(format!("day{day:0>2}")) {
+                continue;
+            }
+            stack.extend(
+                std::fs::read_dir(&path)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|x| x.path()),
+            );
+        } else if path.extension().is_some_and(|x| x == "rs")
+            && !rewritten.contains(&&path)
+            && std::fs::read_to_string(&path).is_ok_and(|x| re_name.is_match(&x))
+        {
+            mentions.push(path);
+        }
+    }
+    mentions.sort();
+    for path in mentions {
+        println!(
+            "WARNING '{}' still mentions day{day:0>2}, update it by hand",
+            path.display()
+        );
+    }
+}
+
+/// Unregisters a day, the opposite of [`init_day`]. Its sources, examples and
+/// inputs move to `archive/dayNN` when `archive` is set.
+pub fn deinit_day(day: i32, archive: bool, dry_run: bool, input_dir: &Path) {
+    let Some(mut changes) = rewrite_day_registration(|registry, manifest, lib| {
+        Some((
+            unregister_day(registry, day)?,
+            remove_day_feature(manifest, day),
+            remove_day_module(lib, day),
+        ))
+    }) else {
+        return;
+    };
+
+    let src_dir = PathBuf::from(format!("src/day{day:0>2}"));
+    if archive {
+        let mut archive_dir = PathBuf::from(format!("{ARCHIVE_DIR}/day{day:0>2}"));
+        let mut copy = 1;
+        while archive_dir.exists() {
+            copy += 1;
+            archive_dir = PathBuf::from(format!("{ARCHIVE_DIR}/day{day:0>2}-{copy}"));
+        }
+        let mut files = day_files(day, input_dir);
+        if src_dir.is_dir() {
+            files.insert(0, src_dir.clone());
+        }
+        changes.extend(create_dirs(
+            files
+                .iter()
+                .filter_map(|x| Some(archive_dir.join(x.parent()?))),
+        ));
+        changes.extend(files.into_iter().map(|from| Change::Rename {
+            to: archive_dir.join(&from),
+            from,
+        }));
+    }
+
+    if !apply_changes(&changes, dry_run) || dry_run {
+        return;
+    }
+    if !archive && src_dir.is_dir() {
+        println!(
+            "'{}' is left in place, the day counts as compiled out and its example tests \
+             warn about the missing feature until it's removed",
+            src_dir.display()
+        );
+    }
+    warn_about_mentions(day, &changes);
+    println!("day {day} was unregistered");
+}
+
+/// Renumbers a day, its sources, examples and inputs move along.
+pub fn move_day(from: i32, to: i32, dry_run: bool, input_dir: &Path) {
+    if !(1..=25).contains(&to) {
+        println!("There is no day number {to}, you were living a lie!");
+        return;
+    }
+    let src_dir = PathBuf::from(format!("src/day{from:0>2}"));
+    let new_src_dir = PathBuf::from(format!("src/day{to:0>2}"));
+    if new_src_dir.exists() || !day_files(to, input_dir).is_empty() {
+        println!("WARNING day {to} already has files, deinit it first");
+        return;
+    }
+
+    let Some(mut changes) = rewrite_day_registration(|registry, manifest, lib| {
+        Some((
+            renumber_day(registry, from, to)?,
+            rename_day_feature(manifest, from, to)?,
+            add_day_module(&remove_day_module(lib, from), to),
+        ))
+    }) else {
+        return;
+    };
+
+    let files = day_files(from, input_dir);
+    if src_dir.is_dir() {
+        changes.push(Change::Rename {
+            from: src_dir.clone(),
+            to: new_src_dir.clone(),
+        });
+        // paths like `examples/dayNN/1.txt` in the day's own sources
+        let re_name = Regex::new(&format!(r"\bday{from:0>2}\b")).unwrap();
+        let mut sources: Vec<PathBuf> = std::fs::read_dir(&src_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|x| x.path())
+            .filter(|x| x.extension().is_some_and(|x| x == "rs"))
+            .collect();
+        sources.sort();
+        for source in sources {
+            let Ok(old) = std::fs::read_to_string(&source) else {
+                continue;
+            };
+            let new = re_name
+                .replace_all(&old, format!("day{to:0>2}"))
+                .into_owned();
+            if new != old {
+                changes.push(Change::Write {
+                    path: new_src_dir.join(source.file_name().unwrap_or_default()),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    let (old_name, new_name) = (format!("day{from:0>2}"), format!("day{to:0>2}"));
+    changes.extend(files.into_iter().map(|from| {
+        let file_name = from.file_name().unwrap_or_default().to_string_lossy();
+        let to = from.with_file_name(file_name.replacen(&old_name, &new_name, 1));
+        Change::Rename { from, to }
+    }));
+
+    if !apply_changes(&changes, dry_run) || dry_run {
+        return;
+    }
+    warn_about_mentions(from, &changes);
+    println!("day {from} is day {to} now");
+}
+
+/// Lines of the `DAYS` entry of the day, its `cfg` line included.
+fn day_entry_lines(lines: &[&str], day: i32) -> Option<std::ops::Range<usize>> {
+    let cfg_line = format!("#[cfg(feature = \"day{day:0>2}\")]");
+    let start = lines.iter().position(|x| x.trim() == cfg_line)?;
+    let end = lines
+        .iter()
+        .skip(start + 1)
+        .position(|x| x.trim_start().starts_with("#[cfg(") || x.trim() == "];")
+        .map(|x| x + start + 1)?;
+    Some(start..end)
+}
+
+/// Where the entry of the day goes to keep `DAYS` sorted.
+fn day_entry_position(lines: &[&str], day: i32) -> Option<usize> {
+    let re_entry = Regex::new(r"^ *Day::(new|streaming)\((\d+),").unwrap();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(captures) = re_entry.captures(line) else {
+            continue;
+        };
+        if captures[2].parse::<i32>().is_ok_and(|x| x > day) {
+            return Some(i - 1);
+        }
+    }
+    lines.iter().position(|x| x.trim() == "];")
+}
+
+fn unregister_day(registry_text: &str, day: i32) -> Option<String> {
+    let mut lines: Vec<&str> = registry_text.lines().collect();
+    let Some(entry) = day_entry_lines(&lines, day) else {
+        println!("WARNING day {day} isn't registered in {REGISTRY_FILE}");
+        return None;
+    };
+    lines.drain(entry);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Moves the entry of the day to its new number, the extras of the entry stay.
+fn renumber_day(registry_text: &str, from: i32, to: i32) -> Option<String> {
+    let mut lines: Vec<&str> = registry_text.lines().collect();
+    if day_entry_lines(&lines, to).is_some() {
+        println!("WARNING day {to} is already registered in {REGISTRY_FILE}");
+        return None;
+    }
+    let Some(entry) = day_entry_lines(&lines, from) else {
+        println!("WARNING day {from} isn't registered in {REGISTRY_FILE}");
+        return None;
+    };
+    let re_name = Regex::new(&format!(r"\bday{from:0>2}\b")).unwrap();
+    let re_number = Regex::new(&format!(r"Day::(new|streaming)\({from},")).unwrap();
+    let moved: Vec<String> = lines
+        .drain(entry)
+        .map(|line| {
+            let line = re_name.replace_all(line, format!("day{to:0>2}"));
+            re_number
+                .replace(&line, format!("Day::${{1}}({to},"))
+                .into_owned()
+        })
+        .collect();
+    let position = day_entry_position(&lines, to)?;
+    let mut lines: Vec<String> = lines.into_iter().map(String::from).collect();
+    lines.splice(position..position, moved);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Drops the `dayNN` feature and takes it out of `all-days`.
+fn remove_day_feature(manifest_text: &str, day: i32) -> String {
+    let name = format!("day{day:0>2}");
+    let lines: Vec<String> = manifest_text
+        .lines()
+        .filter(|x| !x.starts_with(&format!("{name} = ")))
+        .map(|line| match line.strip_prefix("all-days = ") {
+            Some(days) => {
+                let days: Vec<&str> = days
+                    .trim_matches(['[', ']'])
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty() && *x != format!("\"{name}\""))
+                    .collect();
+                format!("all-days = [{}]", days.join(", "))
+            }
+            None => line.to_string(),
+        })
+        .collect();
+    lines.join("\n") + "\n"
+}
+
+/// Renames the `dayNN` feature, the dependencies it turns on stay.
+fn rename_day_feature(manifest_text: &str, from: i32, to: i32) -> Option<String> {
+    let old_prefix = format!("day{from:0>2} = ");
+    let dependencies = manifest_text
+        .lines()
+        .find_map(|x| x.strip_prefix(&old_prefix))
+        .unwrap_or("[]");
+    let removed = remove_day_feature(manifest_text, from);
+    let added = add_day_feature(&removed, to)?;
+    let new_prefix = format!("day{to:0>2} = ");
+    let lines: Vec<String> = added
+        .lines()
+        .map(|line| match line.strip_prefix(&new_prefix) {
+            Some(_) => format!("{new_prefix}{dependencies}"),
+            None => line.to_string(),
+        })
+        .collect();
+    Some(lines.join("\n") + "\n")
+}
+
+fn remove_day_module(lib_text: &str, day: i32) -> String {
+    let cfg_line = format!("#[cfg(feature = \"day{day:0>2}\")]");
+    let mod_line = format!("pub mod day{day:0>2};");
+    let lines: Vec<&str> = lib_text
+        .lines()
+        .filter(|x| x.trim() != cfg_line && x.trim() != mod_line)
+        .collect();
+    lines.join("\n") + "\n"
+}
+
+/// Adds the module of the day among the other days, at the end without them.
+fn add_day_module(lib_text: &str, day: i32) -> String {
+    let re_module = Regex::new(r"^pub mod day(\d+);").unwrap();
+    let mut lines: Vec<String> = lib_text.lines().map(String::from).collect();
+    let mut position = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(captures) = re_module.captures(line) else {
+            continue;
+        };
+        if captures[1].parse::<i32>().is_ok_and(|x| x > day) {
+            // before the cfg line of the next day
+            position = i - 1;
+            break;
+        }
+        position = i + 1;
+    }
+    lines.splice(
+        position..position,
+        [
+            format!("#[cfg(feature = \"day{day:0>2}\")]"),
+            format!("pub mod day{day:0>2};"),
+        ],
+    );
+    lines.join("\n") + "\n"
+}
+
+const SRC_TEMPLATE: &[u8] = b"use crate::registry::Parsed;
+
+pub fn solve(input: String, is_part_one: bool) {
+    match answer(input, is_part_one) {
+        Ok(result) => println!(\"output: {result}\"),
+        Err(err) => println!(\"WARNING {err}\"),
+    }
+}
+
+/// Computes the answer without printing it.
+pub fn answer(input: String, is_part_one: bool) -> Result<String, String> {
+    parse(&input)?.part(is_part_one)
+}
+
+#[derive(Debug)]
+pub struct Input {
+    lines: Vec<String>,
+}
+
+impl Input {
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+pub fn parse(input: &str) -> Result<Input, String> {
+    let lines = input.lines().map(String::from).collect();
+    Ok(Input { lines })
+}
+
+pub fn part_one(input: &Input) -> u64 {
+    0
+}
+
+pub fn part_two(input: &Input) -> u64 {
+    0
+}
+
+impl Parsed for Input {
+    fn parse(input: &str) -> Result<Self, String> {
+        parse(input)
+    }
+
+    fn part(&self, is_part_one: bool) -> Result<String, String> {
+        let result = if is_part_one {
+            part_one(self)
+        } else {
+            part_two(self)
+        };
+        Ok(result.to_string())
+    }
+}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = "pub static DAYS: &[Day] = &[
+    #[cfg(feature = \"day21\")]
+    Day::new(21, day21::answer),
+    #[cfg(feature = \"day22\")]
+    Day::new(22, day22::answer)
+        .with_visualize(day22::visualize)
+        .with_parsed::<day22::Rearrangement>(),
+    #[cfg(feature = \"day24\")]
+    Day::streaming(24, day24::answer, day24::answer_stream),
+];
+";
+
+    const MANIFEST: &str = "[features]
+all-days = [\"day21\", \"day22\", \"day24\"]
+day21 = []
+day22 = [\"dep:itertools\"]
+day24 = []
+";
+
+    const LIB: &str = "pub mod alloc;
+#[cfg(feature = \"day21\")]
+pub mod day21;
+#[cfg(feature = \"day22\")]
+pub mod day22;
+pub mod examples;
+";
+
+    #[test]
+    fn test_unregister_day() {
+        assert_eq!(
+            unregister_day(REGISTRY, 22).unwrap(),
+            "pub static DAYS: &[Day] = &[
+    #[cfg(feature = \"day21\")]
+    Day::new(21, day21::answer),
+    #[cfg(feature = \"day24\")]
+    Day::streaming(24, day24::answer, day24::answer_stream),
+];
+"
+        );
+        assert!(unregister_day(REGISTRY, 24)
+            .unwrap()
+            .ends_with("Rearrangement>(),\n];\n"));
+        assert_eq!(unregister_day(REGISTRY, 23), None);
+    }
+
+    #[test]
+    fn test_renumber_day() {
+        assert_eq!(
+            renumber_day(REGISTRY, 22, 25).unwrap(),
+            "pub static DAYS: &[Day] = &[
+    #[cfg(feature = \"day21\")]
+    Day::new(21, day21::answer),
+    #[cfg(feature = \"day24\")]
+    Day::streaming(24, day24::answer, day24::answer_stream),
+    #[cfg(feature = \"day25\")]
+    Day::new(25, day25::answer)
+        .with_visualize(day25::visualize)
+        .with_parsed::<day25::Rearrangement>(),
+];
+"
+        );
+        assert!(renumber_day(REGISTRY, 24, 20).unwrap().starts_with(
+            "pub static DAYS: &[Day] = &[
+    #[cfg(feature = \"day20\")]
+    Day::streaming(20, day20::answer, day20::answer_stream),
+    #[cfg(feature = \"day21\")]"
+        ));
+        assert_eq!(renumber_day(REGISTRY, 21, 22), None);
+        assert_eq!(renumber_day(REGISTRY, 23, 18), None);
+    }
+
+    #[test]
+    fn test_day_features() {
+        assert_eq!(
+            remove_day_feature(MANIFEST, 22),
+            "[features]
+all-days = [\"day21\", \"day24\"]
+day21 = []
+day24 = []
+"
+        );
+        assert_eq!(
+            rename_day_feature(MANIFEST, 22, 23).unwrap(),
+            "[features]
+all-days = [\"day21\", \"day23\", \"day24\"]
+day21 = []
+day23 = [\"dep:itertools\"]
+day24 = []
+"
+        );
+    }
+
+    #[test]
+    fn test_day_modules() {
+        let without = remove_day_module(LIB, 22);
+        assert_eq!(
+            without,
+            "pub mod alloc;
+#[cfg(feature = \"day21\")]
+pub mod day21;
+pub mod examples;
+"
+        );
+        assert_eq!(add_day_module(&without, 22), LIB);
+        assert_eq!(add_day_module(&remove_day_module(LIB, 21), 21), LIB);
+        assert_eq!(
+            add_day_module("pub mod alloc;\n", 23),
+            "pub mod alloc;\n#[cfg(feature = \"day23\")]\npub mod day23;\n"
+        );
+    }
+
+    #[test]
+    fn test_current_tree() {
+        let registry_text = include_str!("registry.rs");
+        let manifest_text = include_str!("../Cargo.toml");
+        let lib_text = include_str!("lib.rs");
+        let moved = renumber_day(registry_text, 1, 12).unwrap();
+        assert_eq!(renumber_day(&moved, 12, 1).unwrap(), registry_text);
+        let moved = rename_day_feature(manifest_text, 1, 12).unwrap();
+        assert_eq!(rename_day_feature(&moved, 12, 1).unwrap(), manifest_text);
+        let moved = add_day_module(&remove_day_module(lib_text, 1), 12);
+        assert_eq!(add_day_module(&remove_day_module(&moved, 12), 1), lib_text);
+    }
+
+    fn archive_changes(dir: &Path) -> Vec<Change> {
+        std::fs::write(dir.join("registry.rs"), "old\n").unwrap();
+        std::fs::write(dir.join("day05.txt"), "input\n").unwrap();
+        let archive_dir = dir.join("archive/day05/inputs");
+        let mut changes = vec![Change::Write {
+            path: dir.join("registry.rs"),
+            old: "old\n".into(),
+            new: "new\n".into(),
+        }];
+        changes.extend(create_dirs([archive_dir.clone(), archive_dir.clone()]));
+        changes.push(Change::Rename {
+            from: dir.join("day05.txt"),
+            to: archive_dir.join("day05.txt"),
+        });
+        changes
+    }
+
+    #[test]
+    fn test_rollback() {
+        let dir = crate::testing::temp_dir("scaffold-rollback");
+        let mut changes = archive_changes(&dir);
+        assert_eq!(
+            changes[1..4],
+            [
+                Change::CreateDir(dir.join("archive")),
+                Change::CreateDir(dir.join("archive/day05")),
+                Change::CreateDir(dir.join("archive/day05/inputs")),
+            ]
+        );
+        changes.push(Change::Rename {
+            from: dir.join("day05.html"),
+            to: dir.join("archive/day05/inputs/day05.html"),
+        });
+        assert!(!apply_changes(&changes, false));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("registry.rs")).unwrap(),
+            "old\n"
+        );
+        assert!(dir.join("day05.txt").is_file());
+        assert!(!dir.join("archive").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let dir = crate::testing::temp_dir("scaffold-dry-run");
+        let changes = archive_changes(&dir);
+        assert!(apply_changes(&changes, true));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("registry.rs")).unwrap(),
+            "old\n"
+        );
+        assert!(dir.join("day05.txt").is_file());
+        assert!(!dir.join("archive").exists());
+
+        assert!(apply_changes(&changes, false));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("registry.rs")).unwrap(),
+            "new\n"
+        );
+        assert!(dir.join("archive/day05/inputs/day05.txt").is_file());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,521 @@
+//! Local HTTP service for the solvers, behind the `serve` command.
+//!
+//! `GET /days` lists the compiled days, `POST /dayNN/partN` solves the input
+//! in the request body and `GET /verify` hands out the results of the last
+//! `verify` run. Every answer is JSON. Bodies over [`Config::max_body`] are
+//! refused, reading a request and solving it both give up after
+//! [`Config::timeout`]. A solver that runs late can't be stopped, its thread
+//! finishes in the background while the client already got its error, and it
+//! keeps its place among the [`Config::max_solves`] solvers that may run at
+//! once. A panicking solver is answered with its message.
+
+use crate::json;
+use crate::registry::{self, Answer};
+use crate::{inputs, normalize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Request line and headers together.
+const MAX_HEAD: usize = 8 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub max_body: usize,
+    pub timeout: Duration,
+    /// Solvers running at once, late ones included, further solves are
+    /// refused.
+    pub max_solves: usize,
+    /// Where `verify` keeps its results.
+    pub input_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_body: 1024 * 1024,
+            timeout: Duration::from_secs(10),
+            max_solves: std::thread::available_parallelism().map_or(4, usize::from),
+            input_dir: "inputs".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(body: String) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: format!("{{\"error\": {}}}\n", json::quote(message)),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            411 => "Length Required",
+            413 => "Content Too Large",
+            422 => "Unprocessable Content",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    config: Arc<Config>,
+    solving: Arc<AtomicUsize>,
+}
+
+impl Server {
+    pub fn bind(address: impl ToSocketAddrs, config: Config) -> Result<Self, String> {
+        let listener = TcpListener::bind(address).map_err(|e| format!("couldn't listen: {e}"))?;
+        Ok(Server {
+            listener,
+            config: Arc::new(config),
+            solving: Arc::default(),
+        })
+    }
+
+    pub fn local_addr(&self) -> std::net::SocketAddr {
+        self.listener
+            .local_addr()
+            .expect("a bound listener has an address")
+    }
+
+    /// Answers the connections one thread each, forever.
+    pub fn run(&self) {
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let config = self.config.clone();
+            let solving = self.solving.clone();
+            std::thread::spawn(move || handle_connection(stream, &config, &solving));
+        }
+    }
+}
+
+/// Reads one request from the stream, answers it and closes the connection.
+/// `solving` counts the solvers running.
+pub fn handle_connection(mut stream: TcpStream, config: &Config, solving: &Arc<AtomicUsize>) {
+    let _ = stream.set_read_timeout(Some(config.timeout));
+    let _ = stream.set_write_timeout(Some(config.timeout));
+    let response = match read_request(&stream, config) {
+        Ok((method, path, body)) => route(&method, &path, body, config, solving),
+        Err(response) => response,
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(response.body.as_bytes());
+    let _ = stream.flush();
+    // closing with unread data resets the connection, the client could lose
+    // the answer to a refused request
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = std::io::copy(
+        &mut (&stream).take(config.max_body as u64),
+        &mut std::io::sink(),
+    );
+}
+
+fn timed_out(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+    )
+}
+
+/// Method, path and body, or the error to answer with.
+fn read_request(
+    stream: &TcpStream,
+    config: &Config,
+) -> Result<(String, String, Vec<u8>), Response> {
+    let started = Instant::now();
+    let mut reader = BufReader::new(stream);
+    let mut head = Vec::new();
+    loop {
+        if started.elapsed() > config.timeout {
+            return Err(Response::error(408, "the request was too slow"));
+        }
+        let mut line = Vec::new();
+        let read = (&mut reader)
+            .take((MAX_HEAD + 1 - head.len()) as u64)
+            .read_until(b'\n', &mut line);
+        match read {
+            Ok(0) => return Err(Response::error(400, "incomplete request")),
+            Ok(_) => {}
+            Err(e) if timed_out(&e) => {
+                return Err(Response::error(408, "the request was too slow"))
+            }
+            Err(e) => return Err(Response::error(400, &e.to_string())),
+        }
+        head.extend_from_slice(&line);
+        if head.len() > MAX_HEAD {
+            return Err(Response::error(431, "the request head is too large"));
+        }
+        if line == b"\r\n" || line == b"\n" {
+            break;
+        }
+        if !line.ends_with(b"\n") {
+            return Err(Response::error(400, "incomplete request"));
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(Response::error(400, "malformed request line"));
+    };
+    let mut content_length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            let length = value.trim().parse::<usize>();
+            content_length =
+                Some(length.map_err(|_| Response::error(400, "invalid Content-Length"))?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(Response::error(411, "send the body with a Content-Length"));
+        }
+    }
+
+    let mut body = Vec::new();
+    if let Some(length) = content_length {
+        if length > config.max_body {
+            return Err(Response::error(
+                413,
+                &format!("the body is larger than {} bytes", config.max_body),
+            ));
+        }
+        body.resize(length, 0);
+        // the socket timeout is per read, the whole body gets one timeout too
+        let mut filled = 0;
+        while filled < length {
+            if started.elapsed() > config.timeout {
+                return Err(Response::error(408, "the request was too slow"));
+            }
+            match reader.read(&mut body[filled..]) {
+                Ok(0) => return Err(Response::error(400, "the body is shorter than announced")),
+                Ok(read) => filled += read,
+                Err(e) if timed_out(&e) => {
+                    return Err(Response::error(408, "the request was too slow"))
+                }
+                Err(e) => return Err(Response::error(400, &e.to_string())),
+            }
+        }
+    } else if method == "POST" {
+        return Err(Response::error(411, "send the body with a Content-Length"));
+    }
+    Ok((method.to_string(), path.to_string(), body))
+}
+
+/// `/day01/part2` as (1, false).
+fn parse_solve_path(path: &str) -> Option<(i32, bool)> {
+    let (day, part) = path.strip_prefix("/day")?.split_once("/part")?;
+    let is_part_one = match part {
+        "1" => true,
+        "2" => false,
+        _ => return None,
+    };
+    Some((day.parse().ok()?, is_part_one))
+}
+
+pub fn route(
+    method: &str,
+    path: &str,
+    body: Vec<u8>,
+    config: &Config,
+    solving: &Arc<AtomicUsize>,
+) -> Response {
+    let path = path.split('?').next().unwrap_or_default();
+    let allowed = match path {
+        "/days" | "/verify" => "GET",
+        _ if parse_solve_path(path).is_some() => "POST",
+        _ => return Response::error(404, &format!("no endpoint at '{path}'")),
+    };
+    if method != allowed {
+        return Response::error(405, &format!("'{path}' only answers {allowed}"));
+    }
+    match path {
+        "/days" => {
+            let days: Vec<String> = registry::DAYS
+                .iter()
+                .map(|x| x.number.to_string())
+                .collect();
+            Response::json(format!("{{\"days\": [{}]}}\n", days.join(", ")))
+        }
+        "/verify" => {
+            let path = config.input_dir.join(inputs::LATEST_FILE);
+            match std::fs::read_to_string(&path) {
+                Ok(text) => Response::json(text),
+                Err(_) => Response::error(404, "nothing was verified yet, run 'verify'"),
+            }
+        }
+        _ => {
+            let (day, is_part_one) = parse_solve_path(path).unwrap_or((0, true));
+            let Some(entry) = registry::find(day) else {
+                return Response::error(404, &format!("the day {day} isn't compiled in"));
+            };
+            solve(entry.answer, day, is_part_one, body, config, solving)
+        }
+    }
+}
+
+fn solve(
+    answer: Answer,
+    day: i32,
+    is_part_one: bool,
+    body: Vec<u8>,
+    config: &Config,
+    solving: &Arc<AtomicUsize>,
+) -> Response {
+    let Ok(input) = String::from_utf8(body) else {
+        return Response::error(400, "the input isn't valid UTF-8");
+    };
+    let Some(slot) = Slot::take(solving, config.max_solves) else {
+        return Response::error(503, "too many solvers are running, try again later");
+    };
+    let (input, _) = normalize::normalize(&input);
+    let solved = with_timeout(config.timeout, move || {
+        // the slot is given back when the solver is done, even a late one
+        let _slot = slot;
+        let start = Instant::now();
+        let answer = catch_unwind(AssertUnwindSafe(|| answer(input, is_part_one)));
+        (answer, start.elapsed())
+    });
+    let part = if is_part_one { 1 } else { 2 };
+    match solved {
+        Some((Ok(Ok(answer)), time)) => Response::json(format!(
+            "{{\"day\": {day}, \"part\": {part}, \"answer\": {}, \"time_ns\": {}}}\n",
+            json::quote(&answer),
+            time.as_nanos()
+        )),
+        Some((Ok(Err(err)), _)) => Response::error(422, &err),
+        Some((Err(panic), _)) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("no message");
+            Response::error(500, &format!("the solver panicked: {message}"))
+        }
+        None => Response::error(
+            504,
+            &format!("the solver took longer than {:?}", config.timeout),
+        ),
+    }
+}
+
+/// One of the solvers allowed to run at once, given back on drop.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn take(solving: &Arc<AtomicUsize>, max: usize) -> Option<Slot> {
+        solving
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| {
+                (x < max).then_some(x + 1)
+            })
+            .ok()?;
+        Some(Slot(solving.clone()))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The result of `f` unless it takes longer than `timeout`.
+fn with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+    receiver.recv_timeout(timeout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(config: Config) -> std::net::SocketAddr {
+        let server = Server::bind("127.0.0.1:0", config).unwrap();
+        let address = server.local_addr();
+        std::thread::spawn(move || server.run());
+        address
+    }
+
+    /// Status and body of the answer to the raw request.
+    fn request(address: std::net::SocketAddr, raw: &[u8]) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(raw).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    fn post(address: std::net::SocketAddr, path: &str, body: &str) -> (u16, String) {
+        let raw = format!(
+            "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        request(address, raw.as_bytes())
+    }
+
+    #[test]
+    fn test_days_and_routing() {
+        let address = start(Config::default());
+        let (status, body) = request(address, b"GET /days HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 200);
+        let days = json::parse(&body).unwrap();
+        assert_eq!(
+            days.get("days").unwrap().as_array().unwrap().len(),
+            registry::DAYS.len()
+        );
+        assert_eq!(request(address, b"GET /nope HTTP/1.1\r\n\r\n").0, 404);
+        assert_eq!(
+            request(address, b"GET /day01/part1 HTTP/1.1\r\n\r\n").0,
+            405
+        );
+        assert_eq!(
+            request(address, b"POST /day01/part1 HTTP/1.1\r\n\r\n").0,
+            411
+        );
+        assert_eq!(request(address, b"nonsense\r\n\r\n").0, 400);
+        assert_eq!(post(address, "/day26/part1", "").0, 404);
+    }
+
+    #[cfg(feature = "day01")]
+    #[test]
+    fn test_solve() {
+        let address = start(Config::default());
+        let (status, body) = post(address, "/day01/part2", "1\r\n2\r\n\r\n3\r\n\r\n4\r\n");
+        assert_eq!(status, 200, "{body}");
+        let answer = json::parse(&body).unwrap();
+        assert_eq!(answer.get("answer").unwrap().as_str(), Some("10"));
+        assert_eq!(answer.get("part").unwrap().as_i64(), Some(2));
+        assert!(answer.get("time_ns").unwrap().as_i64().is_some());
+    }
+
+    #[cfg(feature = "day07")]
+    #[test]
+    fn test_solve_failed() {
+        let address = start(Config::default());
+        let (status, body) = post(address, "/day07/part1", "$ cd");
+        assert_eq!(status, 422);
+        let error = json::parse(&body).unwrap();
+        assert!(error
+            .get("error")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .contains("directory"));
+    }
+
+    #[test]
+    fn test_limits() {
+        let address = start(Config {
+            max_body: 16,
+            timeout: Duration::from_millis(200),
+            ..Config::default()
+        });
+        assert_eq!(post(address, "/day01/part1", &"1\n".repeat(9)).0, 413);
+        let long_header = format!("GET /days HTTP/1.1\r\nX: {}\r\n\r\n", "x".repeat(MAX_HEAD));
+        assert_eq!(request(address, long_header.as_bytes()).0, 431);
+
+        // announces more than it sends and then stalls
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .write_all(b"POST /day01/part1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n1\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 "), "{response}");
+    }
+
+    #[test]
+    fn test_panic_and_busy() {
+        fn panics(_: String, _: bool) -> Result<String, String> {
+            panic!("no elves")
+        }
+        fn slow(_: String, _: bool) -> Result<String, String> {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok("late".into())
+        }
+        let config = Config {
+            timeout: Duration::from_millis(50),
+            max_solves: 1,
+            ..Config::default()
+        };
+        let solving = Arc::default();
+        let solve = |answer: Answer| solve(answer, 1, true, b"1\n".to_vec(), &config, &solving);
+        assert_eq!(
+            solve(panics),
+            Response::error(500, "the solver panicked: no elves")
+        );
+        assert_eq!(solve(slow).status, 504);
+        // the late solver still runs
+        assert_eq!(solve(panics).status, 503);
+        std::thread::sleep(Duration::from_millis(800));
+        assert_eq!(solve(panics).status, 500);
+        assert_eq!(solving.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        assert_eq!(with_timeout(Duration::from_secs(5), || 7), Some(7));
+        let slow = || std::thread::sleep(Duration::from_secs(2));
+        assert_eq!(with_timeout(Duration::from_millis(20), slow), None);
+    }
+
+    #[test]
+    fn test_verify_results() {
+        let dir = crate::testing::temp_dir("serve-verify");
+        let address = start(Config {
+            input_dir: dir.clone(),
+            ..Config::default()
+        });
+        assert_eq!(request(address, b"GET /verify HTTP/1.1\r\n\r\n").0, 404);
+        std::fs::write(dir.join(inputs::LATEST_FILE), "[]\n").unwrap();
+        assert_eq!(
+            request(address, b"GET /verify HTTP/1.1\r\n\r\n"),
+            (200, "[]\n".into())
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
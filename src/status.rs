@@ -0,0 +1,204 @@
+//! Progress overview behind the `status` command.
+//!
+//! Everything shown is looked up: the registry for the modules, the inputs
+//! directory for the inputs, the answers store for the solved parts and the
+//! last `bench` run for the times.
+
+use crate::bench::{self, Measurement};
+use crate::registry;
+use crate::{answers, examples::Expected, inputs};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Module {
+    Compiled,
+    CompiledOut,
+    Missing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    /// No answer recorded.
+    Open,
+    /// An answer is recorded but the last bench run didn't compute it.
+    Recorded,
+    /// The last bench run computed the recorded answer.
+    Verified,
+    /// The last bench run computed something else.
+    Wrong,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStatus {
+    pub day: i32,
+    pub module: Module,
+    /// `inputs/dayNN.txt` or a named input in `inputs/dayNN/`.
+    pub has_input: bool,
+    pub parts: [Part; 2],
+    /// Both parts of the last bench run.
+    pub bench_time: Option<Duration>,
+}
+
+/// Status of the 25 days, problems with the stores come back as warnings.
+pub fn collect(input_dir: &Path) -> (Vec<DayStatus>, Vec<String>) {
+    let mut warnings = Vec::new();
+    let bench_path = input_dir.join(bench::LATEST_FILE);
+    let measurements = match std::fs::read_to_string(&bench_path) {
+        Ok(text) => bench::parse_json(&text).unwrap_or_else(|e| {
+            warnings.push(format!("'{}': {e}", bench_path.display()));
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    };
+
+    let days = (1..=25)
+        .map(|day| {
+            let module = if registry::find(day).is_some() {
+                Module::Compiled
+            } else if registry::is_compiled_out(day) {
+                Module::CompiledOut
+            } else {
+                Module::Missing
+            };
+            let expected = answers::read(input_dir, day).unwrap_or_else(|e| {
+                warnings.push(e);
+                None
+            });
+            let measured: Vec<&Measurement> =
+                measurements.iter().filter(|x| x.day == day).collect();
+            let part = |is_part_one: bool| {
+                let part_nr = if is_part_one { 1 } else { 2 };
+                let recorded = expected
+                    .as_ref()
+                    .and_then(|x: &Expected| x.part(is_part_one));
+                let computed = measured.iter().find(|x| x.part == part_nr);
+                match (recorded, computed.map(|x| x.answer.as_deref())) {
+                    (None, _) => Part::Open,
+                    (Some(_), None) => Part::Recorded,
+                    (Some(recorded), Some(Ok(computed))) if recorded == computed => Part::Verified,
+                    (Some(_), Some(_)) => Part::Wrong,
+                }
+            };
+            DayStatus {
+                day,
+                module,
+                has_input: input_dir.join(format!("day{day:0>2}.txt")).is_file()
+                    || inputs::has_named_inputs(input_dir, day),
+                parts: [part(true), part(false)],
+                bench_time: (!measured.is_empty()).then(|| measured.iter().map(|x| x.time).sum()),
+            }
+        })
+        .collect();
+    (days, warnings)
+}
+
+const COLUMNS: usize = 5;
+
+/// The days in a grid of five columns, followed by a legend.
+pub fn calendar(days: &[DayStatus]) -> String {
+    let mut text = String::new();
+    for row in days.chunks(COLUMNS) {
+        let cells: Vec<String> = row.iter().map(cell).collect();
+        text.push_str(cells.join("  ").trim_end());
+        text.push('\n');
+    }
+    let stars: usize = days
+        .iter()
+        .flat_map(|x| x.parts)
+        .filter(|x| *x == Part::Verified || *x == Part::Recorded)
+        .count();
+    text.push_str(&format!(
+        "\n{stars} stars\nM module, m compiled out, I input, * verified, + recorded, ! wrong answer\n"
+    ));
+    text
+}
+
+fn cell(status: &DayStatus) -> String {
+    let module = match status.module {
+        Module::Compiled => 'M',
+        Module::CompiledOut => 'm',
+        Module::Missing => '.',
+    };
+    let input = if status.has_input { 'I' } else { '.' };
+    let parts: String = status
+        .parts
+        .iter()
+        .map(|x| match x {
+            Part::Open => '.',
+            Part::Recorded => '+',
+            Part::Verified => '*',
+            Part::Wrong => '!',
+        })
+        .collect();
+    let time = status.bench_time.map_or(String::new(), short_duration);
+    format!("{:>2} {module}{input}{parts} {time:<7}", status.day)
+}
+
+/// At most 6 characters, enough to compare the days.
+fn short_duration(time: Duration) -> String {
+    let micros = time.as_micros();
+    if micros < 1000 {
+        format!("{micros}µs")
+    } else if micros < 1_000_000 {
+        format!("{:.1}ms", micros as f64 / 1000.0)
+    } else {
+        format!("{:.1}s", micros as f64 / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    #[test]
+    fn test_collect() {
+        let dir = temp_dir("status-collect");
+        std::fs::write(dir.join("day01.txt"), "1\n").unwrap();
+        std::fs::write(dir.join("day01.answers"), "part1: 24000\npart2: 45000\n").unwrap();
+        std::fs::write(dir.join("day02.answers"), "part1: 15\n").unwrap();
+        std::fs::write(dir.join("day03.answers"), "part3: 1\n").unwrap();
+        std::fs::create_dir(dir.join("day04")).unwrap();
+        std::fs::write(dir.join("day04").join("alice.txt"), "2-4,6-8\n").unwrap();
+        let measurements = [(1, 1, Ok("24000")), (1, 2, Ok("44999")), (2, 2, Err("no"))].map(
+            |(day, part, answer)| Measurement {
+                day,
+                part,
+                time: Duration::from_micros(1500),
+                memory: None,
+                answer: answer.map(String::from).map_err(String::from),
+            },
+        );
+        std::fs::write(dir.join(bench::LATEST_FILE), bench::json(&measurements)).unwrap();
+
+        let (days, warnings) = collect(&dir);
+        assert_eq!(days.len(), 25);
+        assert!(days[0].has_input);
+        assert_eq!(days[0].parts, [Part::Verified, Part::Wrong]);
+        assert_eq!(days[0].bench_time, Some(Duration::from_millis(3)));
+        assert_eq!(days[1].parts, [Part::Recorded, Part::Open]);
+        assert!(!days[1].has_input);
+        assert!(days[3].has_input);
+        assert_eq!(days[24].module, Module::Missing);
+        assert_eq!(days[24].bench_time, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("day03.answers"));
+
+        let calendar = calendar(&days);
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].contains(" 2 "));
+        assert!(lines[0].contains("*! 3.0ms"));
+        assert!(lines[4].starts_with("21 ....") && lines[4].ends_with("25 ...."));
+        assert_eq!(lines[6], "2 stars");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_short_duration() {
+        assert_eq!(short_duration(Duration::from_nanos(999_000)), "999µs");
+        assert_eq!(short_duration(Duration::from_micros(12_340)), "12.3ms");
+        assert_eq!(short_duration(Duration::from_secs(2)), "2.0s");
+    }
+}
@@ -0,0 +1,12 @@
+//! Helpers shared by the unit tests of the modules.
+
+use std::path::PathBuf;
+
+/// An empty directory of its own for a test, `name` has to be unique among
+/// the tests. It's left behind when the test fails, to look into.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc2022-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
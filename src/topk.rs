@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Keeps the `k` best values seen so far in a bounded binary heap,
+/// so every push costs O(log k) instead of shifting a sorted vector.
+///
+/// Ties are stable: between equal values the one pushed first wins and a
+/// later equal value never evicts it.
+pub struct TopK<T: Ord> {
+    core: Core<T, ()>,
+}
+
+impl<T: Ord> TopK<T> {
+    /// Collector of the `k` largest values.
+    pub fn new(k: usize) -> Self {
+        Self::largest(k)
+    }
+
+    pub fn largest(k: usize) -> Self {
+        TopK {
+            core: Core::new(k, Order::Largest),
+        }
+    }
+
+    /// Min-k variant, collects the `k` smallest values.
+    pub fn smallest(k: usize) -> Self {
+        TopK {
+            core: Core::new(k, Order::Smallest),
+        }
+    }
+
+    /// Offers a value, returns whether it made it into the top k.
+    pub fn push(&mut self, value: T) -> bool {
+        self.core.push(value, ())
+    }
+
+    pub fn k(&self) -> usize {
+        self.core.k
+    }
+
+    pub fn len(&self) -> usize {
+        self.core.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.core.heap.is_empty()
+    }
+
+    /// The kept values, best first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.core.into_sorted_entries().map(|e| e.key).collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+/// Like [`TopK`] but ranks the values by the key computed with `key_fn`,
+/// so the values themselves don't have to be `Ord`.
+pub struct TopKBy<T, K: Ord, F: Fn(&T) -> K> {
+    core: Core<K, T>,
+    key_fn: F,
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> TopKBy<T, K, F> {
+    /// Collector of the `k` values with the largest keys.
+    pub fn largest(k: usize, key_fn: F) -> Self {
+        TopKBy {
+            core: Core::new(k, Order::Largest),
+            key_fn,
+        }
+    }
+
+    /// Collector of the `k` values with the smallest keys.
+    pub fn smallest(k: usize, key_fn: F) -> Self {
+        TopKBy {
+            core: Core::new(k, Order::Smallest),
+            key_fn,
+        }
+    }
+
+    /// Offers a value, returns whether it made it into the top k.
+    pub fn push(&mut self, value: T) -> bool {
+        let key = (self.key_fn)(&value);
+        self.core.push(key, value)
+    }
+
+    pub fn k(&self) -> usize {
+        self.core.k
+    }
+
+    pub fn len(&self) -> usize {
+        self.core.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.core.heap.is_empty()
+    }
+
+    /// The kept values, best first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.core.into_sorted_entries().map(|e| e.value).collect()
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> Extend<T> for TopKBy<T, K, F> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Largest,
+    Smallest,
+}
+
+struct Core<K, V> {
+    k: usize,
+    order: Order,
+    next_seq: u64,
+    heap: BinaryHeap<Entry<K, V>>,
+}
+
+impl<K: Ord, V> Core<K, V> {
+    fn new(k: usize, order: Order) -> Self {
+        Core {
+            k,
+            order,
+            next_seq: 0,
+            // k may come from the user, the heap only grows with the values
+            heap: BinaryHeap::with_capacity(k.min(1024)),
+        }
+    }
+
+    fn push(&mut self, key: K, value: V) -> bool {
+        let entry = Entry {
+            key,
+            seq: self.next_seq,
+            order: self.order,
+            value,
+        };
+        self.next_seq += 1;
+
+        if self.heap.len() < self.k {
+            self.heap.push(entry);
+            return true;
+        }
+        match self.heap.peek_mut() {
+            Some(mut worst) if entry < *worst => {
+                *worst = entry;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn into_sorted_entries(self) -> impl Iterator<Item = Entry<K, V>> {
+        self.heap.into_sorted_vec().into_iter()
+    }
+}
+
+/// Heap entry ordered so that the worst kept entry sits on top of the
+/// max-heap: a smaller entry is a better one.
+struct Entry<K, V> {
+    key: K,
+    seq: u64,
+    order: Order,
+    value: V,
+}
+
+impl<K: Ord, V> Ord for Entry<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_key = match self.order {
+            Order::Largest => other.key.cmp(&self.key),
+            Order::Smallest => self.key.cmp(&other.key),
+        };
+        by_key.then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl<K: Ord, V> PartialOrd for Entry<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> PartialEq for Entry<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, V> Eq for Entry<K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::check_property;
+
+    #[test]
+    fn test_top_k() {
+        let mut topk = TopK::new(4);
+        topk.extend([15, -4, 8, 13, 26, 8, 13, 3]);
+        assert_eq!(topk.into_sorted_vec(), vec![26, 15, 13, 13]);
+
+        let mut topk = TopK::new(4);
+        topk.extend([15, -4]);
+        assert_eq!(topk.len(), 2);
+        assert_eq!(topk.into_sorted_vec(), vec![15, -4]);
+
+        let mut topk = TopK::new(3);
+        topk.extend([6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(topk.into_sorted_vec(), vec![24000, 11000, 10000]);
+
+        let mut topk = TopK::new(0);
+        assert!(!topk.push(1));
+        assert!(topk.is_empty());
+    }
+
+    #[test]
+    fn test_huge_k() {
+        let mut topk = TopK::new(usize::MAX);
+        topk.extend([3, 1, 2]);
+        assert_eq!(topk.into_sorted_vec(), vec![3, 2, 1]);
+
+        let mut topk = TopKBy::smallest(100_000_000_000_000, |&x: &u8| x);
+        topk.extend([3, 1]);
+        assert_eq!(topk.into_sorted_vec(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_min_k() {
+        let mut topk = TopK::smallest(3);
+        topk.extend([15, -4, 8, 13, 26, 8, 13, 3]);
+        assert_eq!(topk.into_sorted_vec(), vec![-4, 3, 8]);
+    }
+
+    #[test]
+    fn test_top_k_by_is_stable() {
+        let mut topk = TopKBy::largest(3, |&(_, score): &(char, u32)| score);
+        topk.extend([('a', 1), ('b', 5), ('c', 5), ('d', 2), ('e', 5)]);
+        assert_eq!(topk.into_sorted_vec(), vec![('b', 5), ('c', 5), ('e', 5)]);
+
+        let mut topk = TopKBy::largest(2, |&(_, score): &(char, u32)| score);
+        topk.extend([('a', 5), ('b', 5), ('c', 5)]);
+        assert_eq!(topk.into_sorted_vec(), vec![('a', 5), ('b', 5)]);
+
+        let mut topk = TopKBy::smallest(2, |word: &&str| word.len());
+        topk.extend(["ccc", "a", "bb", "d"]);
+        assert_eq!(topk.into_sorted_vec(), vec!["a", "d"]);
+    }
+
+    #[test]
+    fn test_property_matches_sorting() {
+        check_property("top k equals the head of a stable sort", |rng| {
+            let k = rng.range(0, 8) as usize;
+            let values: Vec<(i64, usize)> = (0..rng.range(0, 40))
+                .map(|i| (rng.range(0, 20) as i64 - 10, i as usize))
+                .collect();
+
+            let mut topk = TopK::new(k);
+            topk.extend(values.iter().map(|&(x, _)| x));
+            let mut expected: Vec<i64> = values.iter().map(|&(x, _)| x).collect();
+            expected.sort_by(|a, b| b.cmp(a));
+            expected.truncate(k);
+            let found = topk.into_sorted_vec();
+            if found != expected {
+                return Err(format!(
+                    "largest {k} of {values:?}: {found:?} != {expected:?}"
+                ));
+            }
+
+            let mut topk = TopKBy::smallest(k, |&(x, _): &(i64, usize)| x);
+            topk.extend(values.iter().copied());
+            let mut expected = values.clone();
+            expected.sort_by_key(|&(x, _)| x);
+            expected.truncate(k);
+            let found = topk.into_sorted_vec();
+            if found != expected {
+                return Err(format!(
+                    "smallest {k} of {values:?}: {found:?} != {expected:?}"
+                ));
+            }
+            Ok(())
+        });
+    }
+}
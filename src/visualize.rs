@@ -0,0 +1,445 @@
+//! Terminal animations of the puzzles.
+//!
+//! A day with a visualisation calls a frame callback every time its state
+//! changes, [`Visualizer`] draws the frames in place with ANSI escape codes.
+//! Frames bigger than the viewport scroll to keep their focus visible and the
+//! last frame is printed whole once the day is done.
+//!
+//! When stdin is a terminal the animation reacts to keys: space pauses,
+//! `n` draws the next frame while paused, `+`/`-` change the frame rate and
+//! `q` skips to the end. Ctrl-C and panics put the terminal back in order
+//! before the process ends.
+
+use std::io::{IsTerminal, Read, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// One picture of the puzzle state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub lines: Vec<String>,
+    /// (row, column) the viewport keeps visible, usually the moving part.
+    pub focus: Option<(usize, usize)>,
+}
+
+impl Frame {
+    pub fn new(caption: impl Into<String>, lines: Vec<String>) -> Self {
+        Frame {
+            caption: caption.into(),
+            lines,
+            focus: None,
+        }
+    }
+
+    pub fn with_focus(mut self, row: usize, column: usize) -> Self {
+        self.focus = Some((row, column));
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|x| x.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// Computes the answer of one part and sends every frame to the sink.
+pub type Visualize = fn(&str, bool, &mut dyn FrameSink) -> Result<String, String>;
+
+/// Receives the frames of a [`Visualize`], closures taking a [`Frame`] want
+/// every frame.
+pub trait FrameSink {
+    fn show(&mut self, frame: Frame);
+
+    /// `false` once the frames aren't drawn anymore, building them can be
+    /// skipped then, except for the final one.
+    fn wants_frames(&self) -> bool {
+        true
+    }
+}
+
+impl<F: FnMut(Frame)> FrameSink for F {
+    fn show(&mut self, frame: Frame) {
+        self(frame)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Frames per second, 0 draws as fast as possible.
+    pub fps: u32,
+    pub start_paused: bool,
+    /// Columns and rows available for the frame, without the status lines.
+    pub viewport: (usize, usize),
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fps: 10,
+            start_paused: false,
+            viewport: viewport(),
+        }
+    }
+}
+
+/// Size of the terminal from `COLUMNS` and `LINES`, 80x24 if they're not set.
+pub fn terminal_size() -> (usize, usize) {
+    let env_number = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .and_then(|x| x.parse::<usize>().ok())
+    };
+    let columns = env_number("COLUMNS").unwrap_or(80);
+    let rows = env_number("LINES").unwrap_or(24);
+    (columns.max(1), rows.max(1))
+}
+
+/// The terminal without the caption and the key help, two rows.
+fn viewport() -> (usize, usize) {
+    let (columns, rows) = terminal_size();
+    (columns, rows.saturating_sub(2).max(1))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Pause,
+    Step,
+    Faster,
+    Slower,
+    Skip,
+}
+
+pub struct Visualizer<W: Write> {
+    out: W,
+    options: Options,
+    keys: Option<&'static Mutex<Receiver<u8>>>,
+    paused: bool,
+    skipping: bool,
+    frame_count: usize,
+    /// (row, column) of the top left corner of the viewport
+    offset: (usize, usize),
+    last_frame: Option<Frame>,
+    _terminal: Option<RawTerminal>,
+}
+
+impl<W: Write> Visualizer<W> {
+    /// A visualizer without key handling, used for tests and redirected output.
+    pub fn new(out: W, options: Options) -> Self {
+        Visualizer {
+            out,
+            paused: options.start_paused,
+            options,
+            keys: None,
+            skipping: false,
+            frame_count: 0,
+            offset: (0, 0),
+            last_frame: None,
+            _terminal: None,
+        }
+    }
+
+    /// Reads the keys from stdin if it's a terminal.
+    pub fn interactive(out: W, options: Options) -> Self {
+        let mut visualizer = Visualizer::new(out, options);
+        if std::io::stdin().is_terminal() {
+            visualizer._terminal = RawTerminal::enable(SHOW_CURSOR);
+            visualizer.keys = Some(stdin_bytes());
+        } else {
+            // nobody could unpause it
+            visualizer.paused = false;
+        }
+        let _ = write!(visualizer.out, "\x1b[?25l\x1b[2J");
+        visualizer
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Draws the frame and waits for the next one according to the frame rate
+    /// and the pressed keys.
+    pub fn show(&mut self, frame: Frame) {
+        self.frame_count += 1;
+        if !self.skipping {
+            self.scroll_to(&frame);
+            self.draw(&frame);
+            self.wait();
+        }
+        self.last_frame = Some(frame);
+    }
+
+    /// Prints the last frame whole, below the animation.
+    pub fn finish(mut self) -> W {
+        let _ = write!(self.out, "\x1b[?25h\x1b[2J\x1b[H");
+        if let Some(frame) = self.last_frame.take() {
+            let _ = writeln!(self.out, "{}", frame.caption);
+            for line in &frame.lines {
+                let _ = writeln!(self.out, "{line}");
+            }
+        }
+        let _ = self.out.flush();
+        self.out
+    }
+
+    fn scroll_to(&mut self, frame: &Frame) {
+        let (columns, rows) = self.options.viewport;
+        let (mut row, mut column) = self.offset;
+        if let Some((focus_row, focus_column)) = frame.focus {
+            row = scroll_axis(row, rows, focus_row);
+            column = scroll_axis(column, columns, focus_column);
+        }
+        row = row.min(frame.height().saturating_sub(rows));
+        column = column.min(frame.width().saturating_sub(columns));
+        self.offset = (row, column);
+    }
+
+    fn draw(&mut self, frame: &Frame) {
+        let (columns, rows) = self.options.viewport;
+        let (row, column) = self.offset;
+        let mut screen = String::from("\x1b[H");
+        screen.push_str(&format!(
+            "{}  (frame {})\x1b[K\r\n",
+            frame.caption, self.frame_count
+        ));
+        for line in frame.lines.iter().skip(row).take(rows) {
+            let visible: String = line.chars().skip(column).take(columns).collect();
+            screen.push_str(&visible);
+            screen.push_str("\x1b[K\r\n");
+        }
+        if self.keys.is_some() {
+            let state = if self.paused { "paused" } else { "playing" };
+            screen.push_str(&format!(
+                "{state} at {} fps, [space] pause, [n] step, [+/-] speed, [q] skip\x1b[K",
+                self.options.fps
+            ));
+        }
+        screen.push_str("\x1b[J");
+        let _ = self.out.write_all(screen.as_bytes());
+        let _ = self.out.flush();
+    }
+
+    fn wait(&mut self) {
+        let Some(keys) = self.keys else {
+            if self.options.fps > 0 {
+                std::thread::sleep(Duration::from_secs(1) / self.options.fps);
+            }
+            return;
+        };
+        let keys = keys.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let byte = if self.paused {
+                match keys.recv() {
+                    Ok(byte) => Some(byte),
+                    // stdin is closed, nobody can unpause anymore
+                    Err(_) => {
+                        self.paused = false;
+                        return;
+                    }
+                }
+            } else if self.options.fps > 0 {
+                keys.recv_timeout(Duration::from_secs(1) / self.options.fps)
+                    .ok()
+            } else {
+                keys.try_recv().ok()
+            };
+            let key = match byte.map(key) {
+                Some(None) => continue,
+                Some(Some(key)) => Some(key),
+                None => None,
+            };
+            match key {
+                None => return,
+                Some(Key::Pause) => self.paused = !self.paused,
+                Some(Key::Step) if self.paused => return,
+                Some(Key::Step) => {}
+                Some(Key::Faster) => self.options.fps = self.options.fps.saturating_mul(2),
+                Some(Key::Slower) => self.options.fps = (self.options.fps / 2).max(1),
+                Some(Key::Skip) => {
+                    self.skipping = true;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> FrameSink for Visualizer<W> {
+    fn show(&mut self, frame: Frame) {
+        Visualizer::show(self, frame);
+    }
+
+    fn wants_frames(&self) -> bool {
+        !self.skipping
+    }
+}
+
+/// New offset along one axis so that `focus` is inside `offset..offset + size`.
+fn scroll_axis(offset: usize, size: usize, focus: usize) -> usize {
+    if focus < offset {
+        focus
+    } else if focus >= offset + size {
+        focus + 1 - size
+    } else {
+        offset
+    }
+}
+
+fn key(byte: u8) -> Option<Key> {
+    match byte {
+        b' ' | b'p' => Some(Key::Pause),
+        b'n' | b'.' => Some(Key::Step),
+        b'+' | b'=' => Some(Key::Faster),
+        b'-' => Some(Key::Slower),
+        b'q' => Some(Key::Skip),
+        _ => None,
+    }
+}
+
+/// The bytes typed on stdin, read by a single thread for the whole process so
+/// that the dashboard and the animations it starts don't steal each other's keys.
+///
+/// Ctrl-C only reaches the process as a byte while a [`RawTerminal`] is
+/// enabled, it restores the terminal and ends the process like the signal
+/// would have.
+pub(crate) fn stdin_bytes() -> &'static Mutex<Receiver<u8>> {
+    static BYTES: OnceLock<Mutex<Receiver<u8>>> = OnceLock::new();
+    BYTES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for byte in std::io::stdin().lock().bytes() {
+                let Ok(byte) = byte else {
+                    break;
+                };
+                if byte == CTRL_C && restore_terminal() {
+                    std::process::exit(130);
+                }
+                if sender.send(byte).is_err() {
+                    break;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+const CTRL_C: u8 = 3;
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// Escape codes that undo the screen changes of the enabled [`RawTerminal`].
+static SCREEN_RESET: Mutex<Option<&'static str>> = Mutex::new(None);
+
+/// Puts the terminal back in order if a [`RawTerminal`] is enabled,
+/// returns whether one was.
+fn restore_terminal() -> bool {
+    let reset = SCREEN_RESET
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    let Some(reset) = reset else {
+        return false;
+    };
+    let mut out = std::io::stdout();
+    let _ = write!(out, "{reset}");
+    let _ = out.flush();
+    stty(&["icanon", "echo", "isig"]);
+    true
+}
+
+fn stty(args: &[&str]) -> bool {
+    std::process::Command::new("stty")
+        .args(args)
+        .stdin(std::process::Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Turns off line buffering, echo and signal keys of the terminal until
+/// dropped. `screen_reset` undoes what the owner does to the screen, it's
+/// written out if the process ends early by Ctrl-C or a panic.
+pub(crate) struct RawTerminal;
+
+impl RawTerminal {
+    pub(crate) fn enable(screen_reset: &'static str) -> Option<Self> {
+        static PANIC_HOOK: OnceLock<()> = OnceLock::new();
+        PANIC_HOOK.get_or_init(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                restore_terminal();
+                previous(info);
+            }));
+        });
+
+        if !stty(&["-icanon", "-echo", "-isig", "min", "1"]) {
+            println!(
+                "WARNING couldn't switch the terminal to single key input, end keys with enter"
+            );
+            return None;
+        }
+        *SCREEN_RESET.lock().unwrap_or_else(|e| e.into_inner()) = Some(screen_reset);
+        Some(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        SCREEN_RESET
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        stty(&["icanon", "echo", "isig"]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(viewport: (usize, usize)) -> Options {
+        Options {
+            fps: 0,
+            start_paused: false,
+            viewport,
+        }
+    }
+
+    #[test]
+    fn test_viewport_follows_focus() {
+        let lines: Vec<String> = (0..10).map(|x| format!("{x}123456789")).collect();
+        let mut visualizer = Visualizer::new(Vec::new(), options((4, 3)));
+
+        visualizer.show(Frame::new("", lines.clone()).with_focus(5, 6));
+        assert_eq!(visualizer.offset, (3, 3));
+        visualizer.show(Frame::new("", lines.clone()).with_focus(4, 4));
+        assert_eq!(visualizer.offset, (3, 3));
+        visualizer.show(Frame::new("", lines.clone()).with_focus(0, 9));
+        assert_eq!(visualizer.offset, (0, 6));
+        // never past the end of the frame
+        visualizer.show(Frame::new("", lines[..2].to_vec()).with_focus(1, 9));
+        assert_eq!(visualizer.offset, (0, 6));
+        visualizer.show(Frame::new("", vec!["ab".into()]).with_focus(0, 1));
+        assert_eq!(visualizer.offset, (0, 0));
+    }
+
+    #[test]
+    fn test_final_frame_is_dumped_whole() {
+        let mut visualizer = Visualizer::new(Vec::new(), options((2, 1)));
+        visualizer.show(Frame::new("first", vec!["xx".into()]));
+        visualizer.show(Frame::new("last", vec!["abc".into(), "def".into()]));
+        assert_eq!(visualizer.frame_count(), 2);
+
+        let out = String::from_utf8(visualizer.finish()).unwrap();
+        let (animation, dump) = out.split_once("\x1b[?25h\x1b[2J\x1b[H").unwrap();
+        assert!(animation.contains("ab\x1b[K"));
+        assert!(!animation.contains("abc"));
+        assert_eq!(dump, "last\nabc\ndef\n");
+    }
+}
@@ -0,0 +1,85 @@
+//! Uses the per-day library API the way a downstream tool would.
+
+#[test]
+#[cfg(feature = "day01")]
+fn day01_typed_answers() {
+    use aoc2022::day01;
+
+    let inventory = day01::parse(include_str!("../examples/day01/1.txt")).unwrap();
+    assert_eq!(inventory.elves(), [6000, 4000, 11000, 24000, 10000]);
+    assert_eq!(Ok(24000), day01::part_one(&inventory));
+    assert_eq!(Ok(45000), day01::part_two(&inventory));
+}
+
+#[test]
+#[cfg(feature = "day05")]
+fn day05_stacks() {
+    use aoc2022::day05;
+
+    let rearrangement = day05::parse(include_str!("../examples/day05/1.txt")).unwrap();
+    assert_eq!(rearrangement.stacks().len(), 3);
+    assert_eq!(rearrangement.stacks().tops(), "NDP");
+    assert_eq!(
+        rearrangement.moves()[0],
+        day05::Move {
+            count: 1,
+            from: 2,
+            to: 1
+        }
+    );
+    assert_eq!(Ok("CMZ".to_string()), day05::part_one(&rearrangement));
+}
+
+#[test]
+#[cfg(feature = "day06")]
+fn day06_no_marker() {
+    use aoc2022::day06;
+
+    let signal = day06::parse("aaaa").unwrap();
+    assert_eq!(None, day06::part_one(&signal));
+}
+
+#[test]
+#[cfg(feature = "day07")]
+fn day07_file_system_from_transcript() {
+    use aoc2022::day07;
+
+    let fs = day07::parse(include_str!("../examples/day07/1.txt")).unwrap();
+    let e = fs.find("/a/e").unwrap();
+    assert_eq!(Ok(584), fs.dir_size(e));
+    assert_eq!(Ok(48381165), fs.dir_size(fs.root_id()));
+    assert_eq!(
+        Some(&4060174),
+        fs.dir(fs.find("/d").unwrap()).files.get("j")
+    );
+}
+
+#[test]
+#[cfg(feature = "day08")]
+fn day08_forest_queries() {
+    use aoc2022::day08;
+
+    let forest = day08::parse(include_str!("../examples/day08/1.txt")).unwrap();
+    assert_eq!((forest.width(), forest.height()), (5, 5));
+    assert_eq!(Some(5), forest.get((2, 3)));
+    assert_eq!(Some(true), forest.is_tree_visible((1, 1)));
+    assert_eq!(Some(Ok(8)), forest.calc_tree_scenic_score((2, 3)));
+    assert_eq!(None, forest.is_tree_visible((5, 0)));
+}
+
+#[test]
+#[cfg(feature = "day09")]
+fn day09_chain_rope() {
+    use aoc2022::day09;
+
+    let mut rope = day09::ChainRope::new(3);
+    for _ in 0..3 {
+        rope.step(day09::Direction::Right);
+    }
+    assert_eq!(rope.knots()[0], day09::Point::new(3, 0));
+    assert_eq!(rope.tail(), Some(day09::Point::new(1, 0)));
+
+    let motions = day09::parse(include_str!("../examples/day09/1.txt")).unwrap();
+    assert_eq!(motions.motions()[0], (day09::Direction::Right, 4));
+    assert_eq!(13, day09::part_one(&motions));
+}
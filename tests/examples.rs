@@ -0,0 +1,3 @@
+//! One test per fixture in `examples/`, generated by `build.rs`.
+
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
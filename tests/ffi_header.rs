@@ -0,0 +1,18 @@
+//! `include/aoc2022.h` has to match the header generated from `src/ffi.rs`.
+//!
+//! Run with `AOC_BLESS=1` to update the shipped header.
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/aoc2022.h"));
+const SHIPPED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/include/aoc2022.h");
+
+#[test]
+fn shipped_header_is_up_to_date() {
+    if std::env::var_os("AOC_BLESS").is_some() {
+        std::fs::write(SHIPPED, GENERATED).expect("couldn't write the header");
+    }
+    let shipped = std::fs::read_to_string(SHIPPED).unwrap_or_default();
+    assert!(
+        shipped == GENERATED,
+        "include/aoc2022.h is out of date, rerun the tests with AOC_BLESS=1"
+    );
+}
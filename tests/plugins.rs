@@ -0,0 +1,69 @@
+//! Loads the `sample-plugin` example of the crate as a real shared library,
+//! on its own and through the runner.
+#![cfg(feature = "plugins")]
+
+use aoc2022::plugin;
+use aoc2022::registry::Solver;
+use std::env::consts::{DLL_EXTENSION, DLL_PREFIX};
+use std::path::PathBuf;
+use std::process::Command;
+
+const EXAMPLE: &str = include_str!("../examples/day01/1.txt");
+
+/// `cargo test` builds the examples next to the directory of the test binaries.
+fn sample_plugin() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let path = exe
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .join("examples")
+        .join(format!("{DLL_PREFIX}sample_plugin.{DLL_EXTENSION}"));
+    assert!(
+        path.is_file(),
+        "'{}' is missing, build it with 'cargo build --example sample-plugin'",
+        path.display()
+    );
+    path
+}
+
+#[test]
+fn sample_plugin_solves_day01() {
+    let solvers = plugin::load(&sample_plugin()).unwrap();
+    let listed: Vec<(i32, &str)> = solvers.iter().map(|x| (x.day(), x.variant())).collect();
+    assert_eq!(listed, [(1, "sorted")]);
+    assert_eq!(solvers[0].solve(EXAMPLE.into(), true), Ok("24000".into()));
+    assert_eq!(solvers[0].solve(EXAMPLE.into(), false), Ok("45000".into()));
+    assert_eq!(
+        solvers[0].solve("1000\nlots\n".into(), true),
+        Err("'lots' isn't a number of calories".into())
+    );
+}
+
+#[test]
+fn runner_runs_the_sample_plugin() {
+    // the examples directory holds other builds of the plugin, with a hash
+    let dir = std::env::temp_dir().join(format!("aoc2022-plugins-run-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let plugin = sample_plugin();
+    std::fs::copy(&plugin, dir.join(plugin.file_name().unwrap())).unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_aoc2022-runner"))
+            .args(args)
+            .arg("--plugins")
+            .arg(&dir)
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let listed = run(&["list"]);
+    let variant = format!("plugins: sorted ({DLL_PREFIX}sample_plugin");
+    assert!(listed.contains(&variant), "{listed}");
+    let solved = run(&["1", "-e", "1", "--variant", "sorted"]);
+    assert!(solved.contains("part 1 output: 24000"), "{solved}");
+    assert!(solved.contains("part 2 output: 45000"), "{solved}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,155 @@
+//! Feeds malformed inputs into every day and checks that nothing panics.
+//!
+//! The inputs are random bytes, truncated generated inputs and generated
+//! inputs with a few bytes overwritten. A failure names the day and the seed,
+//! `AOC_PROP_SEED` reruns a single seed and `AOC_PROP_CASES` changes the
+//! number of cases, like for the property tests of the day modules.
+
+use aoc2022::generate::{generate, Params, Rng};
+use aoc2022::registry::{self, Answer, StreamAnswer};
+use std::io::Cursor;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Characters that show up in the puzzle inputs, so that random inputs get
+/// past the first checks of the parsers more often than pure noise would.
+const INPUT_ALPHABET: &[u8] = b"0123456789 \n\n\n-,$/.[]abcdefgmorstvwxyzABCDLRUXYZ";
+
+fn seeds() -> std::ops::Range<u64> {
+    let env_number = |key: &str| std::env::var(key).ok().and_then(|x| x.parse::<u64>().ok());
+    match env_number("AOC_PROP_SEED") {
+        Some(seed) => seed..seed + 1,
+        None => 0..env_number("AOC_PROP_CASES").unwrap_or(200),
+    }
+}
+
+fn random_bytes(rng: &mut Rng) -> Vec<u8> {
+    let len = rng.range(0, 200) as usize;
+    (0..len)
+        .map(|_| {
+            if rng.chance(1, 10) {
+                rng.next_u64() as u8
+            } else {
+                *rng.choose(INPUT_ALPHABET)
+            }
+        })
+        .collect()
+}
+
+fn generated_bytes(day: i32, rng: &mut Rng) -> Vec<u8> {
+    let params = Params {
+        seed: rng.next_u64(),
+        size: rng.range(1, 30) as usize,
+        marker: None,
+    };
+    match generate(day, params) {
+        Some(Ok(generated)) => generated.input.into_bytes(),
+        // day06 can't place its marker in very short signals
+        _ => Vec::new(),
+    }
+}
+
+fn truncated(mut bytes: Vec<u8>, rng: &mut Rng) -> Vec<u8> {
+    bytes.truncate(rng.index(bytes.len() + 1));
+    bytes
+}
+
+fn mutated(mut bytes: Vec<u8>, rng: &mut Rng) -> Vec<u8> {
+    if !bytes.is_empty() {
+        for _ in 0..rng.range(1, 4) {
+            let i = rng.index(bytes.len());
+            bytes[i] = if rng.chance(1, 4) {
+                rng.next_u64() as u8
+            } else {
+                *rng.choose(INPUT_ALPHABET)
+            };
+        }
+    }
+    bytes
+}
+
+/// Runs both parts on the input, the answers themselves don't matter.
+fn run_day(answer: Answer, answer_stream: Option<StreamAnswer>, bytes: &[u8]) {
+    for is_part_one in [true, false] {
+        let _ = answer(String::from_utf8_lossy(bytes).into_owned(), is_part_one);
+        if let Some(answer_stream) = answer_stream {
+            let _ = answer_stream(Box::new(Cursor::new(bytes.to_vec())), is_part_one);
+        }
+    }
+}
+
+fn check_no_panics(kind: &str, make_input: impl Fn(i32, &mut Rng) -> Vec<u8>) {
+    let mut failures = Vec::new();
+    for entry in registry::DAYS {
+        let (day, answer, answer_stream) = (entry.number, entry.answer, entry.answer_stream);
+        for seed in seeds() {
+            let mut rng = Rng::new(seed);
+            let bytes = make_input(day, &mut rng);
+            let result = catch_unwind(AssertUnwindSafe(|| run_day(answer, answer_stream, &bytes)));
+            if result.is_err() {
+                failures.push(format!(
+                    "day {day:0>2} with AOC_PROP_SEED={seed}: {:?}",
+                    String::from_utf8_lossy(&bytes)
+                ));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{kind} inputs panicked:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn random_inputs_dont_panic() {
+    check_no_panics("random", |_, rng| random_bytes(rng));
+}
+
+#[test]
+fn truncated_inputs_dont_panic() {
+    check_no_panics("truncated", |day, rng| {
+        let bytes = generated_bytes(day, rng);
+        truncated(bytes, rng)
+    });
+}
+
+#[test]
+fn mutated_inputs_dont_panic() {
+    check_no_panics("mutated", |day, rng| {
+        let bytes = generated_bytes(day, rng);
+        mutated(bytes, rng)
+    });
+}
+
+/// A reader that breaks down after the bytes it was given.
+struct FailingReader(Cursor<Vec<u8>>);
+
+impl std::io::Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.read(buf)? {
+            0 => Err(std::io::Error::other("the disk is on fire")),
+            read => Ok(read),
+        }
+    }
+}
+
+#[test]
+fn read_errors_are_reported() {
+    for entry in registry::DAYS {
+        let Some(answer_stream) = entry.answer_stream else {
+            continue;
+        };
+        let bytes = generated_bytes(entry.number, &mut Rng::new(7));
+        for is_part_one in [true, false] {
+            let reader = std::io::BufReader::new(FailingReader(Cursor::new(bytes.clone())));
+            let result = answer_stream(Box::new(reader), is_part_one);
+            assert!(
+                result
+                    .as_ref()
+                    .is_err_and(|err| err.contains("the disk is on fire")),
+                "day {:0>2} answered {result:?} to a broken reader",
+                entry.number
+            );
+        }
+    }
+}